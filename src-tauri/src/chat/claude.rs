@@ -1,5 +1,3 @@
-use tauri::Manager;
-
 use super::types::{
     CompactMetadata, ContentBlock, EffortLevel, ThinkingLevel, ToolCall, UsageData,
 };
@@ -27,6 +25,8 @@ pub struct ClaudeResponse {
     pub cancelled: bool,
     /// Token usage for this response
     pub usage: Option<UsageData>,
+    /// Model reported by the CLI's init message (the model that actually ran)
+    pub resolved_model: Option<String>,
 }
 
 /// Payload for text chunk events sent to frontend
@@ -35,6 +35,8 @@ struct ChunkEvent {
     session_id: String,
     worktree_id: String, // Kept for backward compatibility
     content: String,
+    /// Position of this event within the run (shared counter, starts at 0 per run)
+    seq: u64,
 }
 
 /// Payload for tool use events sent to frontend
@@ -48,6 +50,8 @@ struct ToolUseEvent {
     /// Parent tool use ID for sub-agent tool calls (for parallel task attribution)
     #[serde(skip_serializing_if = "Option::is_none")]
     parent_tool_use_id: Option<String>,
+    /// Position of this event within the run (shared counter, starts at 0 per run)
+    seq: u64,
 }
 
 /// Payload for done events sent to frontend
@@ -55,6 +59,38 @@ struct ToolUseEvent {
 struct DoneEvent {
     session_id: String,
     worktree_id: String, // Kept for backward compatibility
+    /// Position of this event within the run (shared counter, starts at 0 per run)
+    seq: u64,
+    /// Final token usage from the result message (None for partial responses)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<UsageData>,
+    /// Run duration reported by the CLI's result message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u64>,
+    /// Claude CLI session ID, if the CLI reported one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    claude_session_id: Option<String>,
+}
+
+impl DoneEvent {
+    fn new(
+        session_id: &str,
+        worktree_id: &str,
+        seq: u64,
+        usage: Option<UsageData>,
+        duration_ms: Option<u64>,
+        claude_session_id: &str,
+    ) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            worktree_id: worktree_id.to_string(),
+            seq,
+            usage,
+            duration_ms,
+            claude_session_id: (!claude_session_id.is_empty())
+                .then(|| claude_session_id.to_string()),
+        }
+    }
 }
 
 /// Payload for error events sent to frontend
@@ -71,6 +107,57 @@ pub struct CancelledEvent {
     pub session_id: String,
     pub worktree_id: String, // Kept for backward compatibility
     pub undo_send: bool, // True if user message should be restored to input (instant cancellation)
+    /// Run that was cancelled (set when cancelling a specific run via cancel_run)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+}
+
+/// Payload for running token usage events sent to frontend
+/// Emitted during a run as assistant messages report usage; the final
+/// usage from the result message (in `chat:done` handling) stays authoritative
+#[derive(serde::Serialize, Clone)]
+struct UsageUpdateEvent {
+    session_id: String,
+    worktree_id: String, // Kept for backward compatibility
+    usage: UsageData,
+}
+
+/// Payload for policy adjustment events sent to frontend
+/// Emitted when a run executes with a different setting than the user requested
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct PolicyAdjustedEvent {
+    pub session_id: String,
+    pub worktree_id: String, // Kept for backward compatibility
+    /// Setting that was overridden (e.g., "thinking_level", "effort_level")
+    pub field: String,
+    pub requested: String,
+    pub effective: String,
+    /// Why the setting was overridden
+    pub reason: String,
+}
+
+/// Payload for stream warning events sent to frontend
+/// Emitted (rate-limited) when a line of the CLI output can't be parsed as JSON
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct StreamWarningEvent {
+    pub session_id: String,
+    pub worktree_id: String, // Kept for backward compatibility
+    pub message: String,
+    /// Start of the offending line (truncated)
+    pub line_preview: String,
+    /// Unparseable lines seen so far in this run
+    pub unparseable_count: u64,
+}
+
+/// Payload for startup progress events sent to frontend
+/// Emitted while waiting for the CLI's first output so the UI can show it's starting
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct StreamProgressEvent {
+    pub session_id: String,
+    pub worktree_id: String, // Kept for backward compatibility
+    /// Seconds since the tailer started waiting
+    pub elapsed_secs: u64,
+    pub process_alive: bool,
 }
 
 /// Payload for tool block position events sent to frontend
@@ -88,6 +175,8 @@ struct ThinkingEvent {
     session_id: String,
     worktree_id: String, // Kept for backward compatibility
     content: String,
+    /// Position of this event within the run (shared counter, starts at 0 per run)
+    seq: u64,
 }
 
 /// Payload for tool result events sent to frontend
@@ -98,6 +187,12 @@ struct ToolResultEvent {
     worktree_id: String, // Kept for backward compatibility
     tool_use_id: String,
     output: String,
+    /// True if the tool reported a failure (e.g., non-zero exit code)
+    is_error: bool,
+    /// Time from tool_use to tool_result in milliseconds, if the start was seen
+    duration_ms: Option<u64>,
+    /// Position of this event within the run (shared counter, starts at 0 per run)
+    seq: u64,
 }
 
 /// A single permission denial from Claude CLI
@@ -138,6 +233,855 @@ struct CompactedEvent {
 // Detached Claude CLI execution
 // =============================================================================
 
+/// Git commands that only read repository state, allowed without prompts in plan mode.
+///
+/// Plan mode keeps the worktree read-only, but planning often needs repo history
+/// (`git log`, `git diff`), which would otherwise stall on an approval prompt.
+/// Only read subcommands are listed - anything that mutates refs or the index stays gated.
+/// `log`, `diff` and `show` accept `--output=<file>`, so they are only allowed in
+/// fixed forms; the `:*` wildcard is reserved for subcommands that never write files.
+const PLAN_MODE_GIT_READ_TOOLS: &[&str] = &[
+    "Bash(git log)",
+    "Bash(git log --oneline)",
+    "Bash(git log --stat)",
+    "Bash(git diff)",
+    "Bash(git diff --stat)",
+    "Bash(git diff --name-only)",
+    "Bash(git diff --cached)",
+    "Bash(git diff --staged)",
+    "Bash(git show)",
+    "Bash(git show --stat)",
+    "Bash(git status:*)",
+    "Bash(git blame:*)",
+    "Bash(git ls-files:*)",
+    "Bash(git rev-parse:*)",
+    "Bash(git branch --show-current)",
+];
+
+/// Minimum time between `chat:chunk` events; text arriving faster is combined
+const CHUNK_FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(33);
+
+/// Coalesces streamed text into fewer `chat:chunk` events.
+///
+/// Text is buffered and released at most once per interval. Callers must
+/// `flush()` before emitting any other event (tool use, thinking, done, ...)
+/// so text never gets reordered relative to those events.
+struct ChunkCoalescer {
+    buffer: String,
+    interval: std::time::Duration,
+    last_flush: Option<std::time::Instant>,
+}
+
+impl ChunkCoalescer {
+    fn new(interval: std::time::Duration) -> Self {
+        Self {
+            buffer: String::new(),
+            interval,
+            last_flush: None,
+        }
+    }
+
+    /// Buffer text, returning the combined content if it's time to emit
+    fn push(&mut self, text: &str) -> Option<String> {
+        self.buffer.push_str(text);
+        self.flush_if_due()
+    }
+
+    /// Return buffered content if the interval has elapsed since the last emit
+    fn flush_if_due(&mut self) -> Option<String> {
+        let due = self
+            .last_flush
+            .is_none_or(|last| last.elapsed() >= self.interval);
+        if due {
+            self.flush()
+        } else {
+            None
+        }
+    }
+
+    /// Return all buffered content immediately (None if empty)
+    fn flush(&mut self) -> Option<String> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+        self.last_flush = Some(std::time::Instant::now());
+        Some(std::mem::take(&mut self.buffer))
+    }
+}
+
+/// Sequence numbers for the streaming events of one run.
+///
+/// Chunk, tool use, tool result, thinking and done events share one counter so
+/// the frontend can restore their order or detect gaps.
+#[derive(Default)]
+struct EventSeq(std::sync::atomic::AtomicU64);
+
+impl EventSeq {
+    fn next(&self) -> u64 {
+        self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+/// Number of unparseable lines per run that produce a `chat:stream_warning`
+const MAX_PARSE_WARNINGS: u64 = 3;
+
+/// Maximum characters of an unparseable line included in a warning
+const LINE_PREVIEW_MAX_CHARS: usize = 120;
+
+/// Truncate a line for inclusion in a warning
+fn line_preview(line: &str) -> String {
+    let line = line.trim();
+    match line.char_indices().nth(LINE_PREVIEW_MAX_CHARS) {
+        Some((idx, _)) => format!("{}…", &line[..idx]),
+        None => line.to_string(),
+    }
+}
+
+/// Counts unparseable output lines in one run and rate-limits warnings about them.
+///
+/// The CLI's stderr is redirected into the same file, so lines that don't even
+/// start like a JSON object are counted as stderr and never warned about.
+/// A line identical to the last one warned about is counted but not warned
+/// about again.
+#[derive(Default)]
+struct ParseFailures {
+    count: u64,
+    stderr_lines: u64,
+    warned: u64,
+    last_warned_line: Option<String>,
+}
+
+impl ParseFailures {
+    /// Record a failed line; returns a warning to emit for the first few failures only
+    fn record(
+        &mut self,
+        session_id: &str,
+        worktree_id: &str,
+        line: &str,
+    ) -> Option<StreamWarningEvent> {
+        let line = line.trim();
+        if !line.starts_with('{') {
+            self.stderr_lines += 1;
+            log::trace!("CLI stderr: {}", line_preview(line));
+            return None;
+        }
+        self.count += 1;
+        if self.warned >= MAX_PARSE_WARNINGS || self.last_warned_line.as_deref() == Some(line) {
+            return None;
+        }
+        self.warned += 1;
+        self.last_warned_line = Some(line.to_string());
+        let so_far = match self.count {
+            1 => "1 unparseable line so far".to_string(),
+            n => format!("{n} unparseable lines so far"),
+        };
+        Some(StreamWarningEvent {
+            session_id: session_id.to_string(),
+            worktree_id: worktree_id.to_string(),
+            message: format!("Skipped a line of CLI output that isn't valid JSON ({so_far})"),
+            line_preview: line_preview(line),
+            unparseable_count: self.count,
+        })
+    }
+}
+
+/// Tools that modify files, which plan mode's permission mode blocks
+const WRITE_TOOLS: [&str; 4] = ["Edit", "MultiEdit", "Write", "NotebookEdit"];
+
+/// Flags write tool calls in plan-mode runs, which the CLI refuses; without a
+/// warning the user only sees the tool fail with a permission error.
+#[derive(Default)]
+struct PlanModeWrites {
+    /// Whether the run's `_run_meta` header says it runs in plan mode
+    plan_mode: bool,
+    /// Tools already warned about (one warning per tool per run)
+    warned: std::collections::HashSet<String>,
+}
+
+impl PlanModeWrites {
+    /// Read the permission mode from the run log's `_run_meta` header line
+    fn observe_run_meta(&mut self, line: &str) {
+        if let Ok(meta) = serde_json::from_str::<serde_json::Value>(line) {
+            self.plan_mode = meta.get("permission_mode").and_then(|v| v.as_str()) == Some("plan");
+        }
+    }
+
+    /// Warning for a write tool called during a plan-mode run, once per tool
+    fn check_tool(
+        &mut self,
+        session_id: &str,
+        worktree_id: &str,
+        tool_name: &str,
+    ) -> Option<StreamWarningEvent> {
+        if !self.plan_mode
+            || !WRITE_TOOLS.contains(&tool_name)
+            || !self.warned.insert(tool_name.to_string())
+        {
+            return None;
+        }
+        Some(StreamWarningEvent {
+            session_id: session_id.to_string(),
+            worktree_id: worktree_id.to_string(),
+            message: format!(
+                "Claude tried to use {tool_name} in plan mode, which is read-only, so it was blocked. Switch to build mode to let it make changes."
+            ),
+            line_preview: String::new(),
+            unparseable_count: 0,
+        })
+    }
+}
+
+/// Warn the frontend (once) that the run log was deleted while streaming
+fn emit_missing_log_warning(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    worktree_id: &str,
+    warned: &mut bool,
+) {
+    if std::mem::replace(warned, true) {
+        return;
+    }
+    let warning = StreamWarningEvent {
+        session_id: session_id.to_string(),
+        worktree_id: worktree_id.to_string(),
+        message: "The run log was deleted while streaming; this response won't be saved"
+            .to_string(),
+        line_preview: String::new(),
+        unparseable_count: 0,
+    };
+    if let Err(e) = app.emit_all("chat:stream_warning", &warning) {
+        log::error!("Failed to emit stream_warning: {e}");
+    }
+}
+
+/// Interval between `chat:stream_progress` events while waiting for first output
+const STARTUP_HEARTBEAT_INTERVAL_SECS: u64 = 10;
+
+/// Schedules startup heartbeats: once immediately, then every
+/// [`STARTUP_HEARTBEAT_INTERVAL_SECS`] seconds.
+#[derive(Default)]
+struct StartupHeartbeat {
+    next_at_secs: u64,
+}
+
+impl StartupHeartbeat {
+    /// Returns the elapsed seconds to report if a heartbeat is due
+    fn due(&mut self, elapsed: std::time::Duration) -> Option<u64> {
+        let secs = elapsed.as_secs();
+        if secs < self.next_at_secs {
+            return None;
+        }
+        self.next_at_secs =
+            (secs / STARTUP_HEARTBEAT_INTERVAL_SECS + 1) * STARTUP_HEARTBEAT_INTERVAL_SECS;
+        Some(secs)
+    }
+}
+
+/// Emit a `chat:chunk` event with streamed text
+fn emit_chunk(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    worktree_id: &str,
+    content: String,
+    seq: u64,
+) {
+    let event = ChunkEvent {
+        session_id: session_id.to_string(),
+        worktree_id: worktree_id.to_string(),
+        content,
+        seq,
+    };
+    if let Err(e) = app.emit_all("chat:chunk", &event) {
+        log::error!("Failed to emit chunk: {e}");
+    }
+}
+
+/// A streaming event re-emitted when replaying a finished run
+#[derive(Debug, Clone, PartialEq)]
+enum ReplayEvent {
+    Chunk(String),
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+        parent_tool_use_id: Option<String>,
+    },
+    ToolResult {
+        tool_use_id: String,
+        output: String,
+        is_error: bool,
+    },
+    Thinking(String),
+}
+
+/// Streaming events of a run log, in the order the CLI wrote them
+/// (without thinking if the run hides it)
+fn replay_events(lines: &[String]) -> Vec<ReplayEvent> {
+    let mut events = Vec::new();
+    let mut thinking_hidden = false;
+    for line in lines {
+        let Ok(msg) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        if msg.get("_run_meta").is_some() {
+            thinking_hidden = super::run_log::run_meta_hides_thinking(&msg);
+            continue;
+        }
+        let str_field = |v: &serde_json::Value, name: &str| {
+            v.get(name)
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string()
+        };
+        let parent_tool_use_id = msg
+            .get("parent_tool_use_id")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let msg_type = msg.get("type").and_then(|v| v.as_str()).unwrap_or("");
+        let Some(blocks) = msg
+            .get("message")
+            .and_then(|m| m.get("content"))
+            .and_then(|c| c.as_array())
+        else {
+            continue;
+        };
+
+        for block in blocks {
+            let block_type = block.get("type").and_then(|v| v.as_str()).unwrap_or("");
+            let event = match (msg_type, block_type) {
+                ("assistant", "text") => ReplayEvent::Chunk(str_field(block, "text")),
+                ("assistant", "tool_use") => ReplayEvent::ToolUse {
+                    id: str_field(block, "id"),
+                    name: str_field(block, "name"),
+                    input: block
+                        .get("input")
+                        .cloned()
+                        .unwrap_or(serde_json::Value::Null),
+                    parent_tool_use_id: parent_tool_use_id.clone(),
+                },
+                ("assistant", "thinking") if !thinking_hidden => {
+                    ReplayEvent::Thinking(str_field(block, "thinking"))
+                }
+                ("user", "tool_result") => {
+                    let (output, is_error) = parse_tool_result(block);
+                    ReplayEvent::ToolResult {
+                        tool_use_id: str_field(block, "tool_use_id"),
+                        output,
+                        is_error,
+                    }
+                }
+                _ => continue,
+            };
+            events.push(event);
+        }
+    }
+    events
+}
+
+/// Slowest replay speed accepted (about a second between events)
+pub(super) const MAX_REPLAY_SPEED: f32 = 20.0;
+
+/// Pause between replayed events for a replay `speed` (1.0 = no pause),
+/// clamped to `MAX_REPLAY_SPEED`
+fn replay_delay(speed: f32) -> std::time::Duration {
+    const REPLAY_STEP_MS: f32 = 50.0;
+    let factor = speed.clamp(1.0, MAX_REPLAY_SPEED) - 1.0;
+    // NaN casts to 0
+    std::time::Duration::from_millis((REPLAY_STEP_MS * factor).round() as u64)
+}
+
+/// Re-emit a finished run's streaming events, ending with `chat:done`.
+///
+/// Uses the same payloads as live streaming but never touches metadata.
+/// Blocks for the whole replay, so call it off the async runtime.
+pub(super) fn replay_run_events(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    worktree_id: &str,
+    lines: &[String],
+    speed: f32,
+) {
+    let delay = replay_delay(speed);
+    let seq = EventSeq::default();
+
+    for event in replay_events(lines) {
+        let emitted = match event {
+            ReplayEvent::Chunk(content) => {
+                emit_chunk(app, session_id, worktree_id, content, seq.next());
+                Ok(())
+            }
+            ReplayEvent::ToolUse {
+                id,
+                name,
+                input,
+                parent_tool_use_id,
+            } => app.emit_all(
+                "chat:tool_use",
+                &ToolUseEvent {
+                    session_id: session_id.to_string(),
+                    worktree_id: worktree_id.to_string(),
+                    id,
+                    name,
+                    input,
+                    parent_tool_use_id,
+                    seq: seq.next(),
+                },
+            ),
+            ReplayEvent::ToolResult {
+                tool_use_id,
+                output,
+                is_error,
+            } => app.emit_all(
+                "chat:tool_result",
+                &ToolResultEvent {
+                    session_id: session_id.to_string(),
+                    worktree_id: worktree_id.to_string(),
+                    tool_use_id,
+                    output,
+                    is_error,
+                    duration_ms: None,
+                    seq: seq.next(),
+                },
+            ),
+            ReplayEvent::Thinking(content) => app.emit_all(
+                "chat:thinking",
+                &ThinkingEvent {
+                    session_id: session_id.to_string(),
+                    worktree_id: worktree_id.to_string(),
+                    content,
+                    seq: seq.next(),
+                },
+            ),
+        };
+        if let Err(e) = emitted {
+            log::error!("Failed to emit replayed event: {e}");
+        }
+        if !delay.is_zero() {
+            std::thread::sleep(delay);
+        }
+    }
+
+    let done_event = DoneEvent::new(session_id, worktree_id, seq.next(), None, None, "");
+    if let Err(e) = app.emit_all("chat:done", &done_event) {
+        log::error!("Failed to emit done event: {e}");
+    }
+}
+
+/// Parse a Claude API `usage` object (missing fields count as zero)
+pub fn parse_usage(usage_obj: &serde_json::Value) -> UsageData {
+    let field = |name: &str| usage_obj.get(name).and_then(|v| v.as_u64()).unwrap_or(0);
+    UsageData {
+        input_tokens: field("input_tokens"),
+        output_tokens: field("output_tokens"),
+        cache_read_input_tokens: field("cache_read_input_tokens"),
+        cache_creation_input_tokens: field("cache_creation_input_tokens"),
+    }
+}
+
+/// Running token usage across the assistant messages of a run.
+///
+/// Claude CLI repeats a message's usage on every content block of that message,
+/// so usage is keyed by message ID (latest wins) and summed across messages.
+#[derive(Default)]
+struct UsageAccumulator {
+    per_message: std::collections::HashMap<String, UsageData>,
+    last_total: Option<UsageData>,
+}
+
+impl UsageAccumulator {
+    /// Record usage for a message, returning the new total if it changed
+    fn record(&mut self, message_id: &str, usage: UsageData) -> Option<UsageData> {
+        self.per_message.insert(message_id.to_string(), usage);
+
+        let total = self
+            .per_message
+            .values()
+            .fold(UsageData::default(), |mut acc, u| {
+                acc.input_tokens += u.input_tokens;
+                acc.output_tokens += u.output_tokens;
+                acc.cache_read_input_tokens += u.cache_read_input_tokens;
+                acc.cache_creation_input_tokens += u.cache_creation_input_tokens;
+                acc
+            });
+
+        if self.last_total.as_ref() == Some(&total) {
+            return None;
+        }
+        self.last_total = Some(total.clone());
+        Some(total)
+    }
+}
+
+/// Measures tool calls from their tool_use block to their tool_result.
+///
+/// Uses monotonic instants taken as lines are tailed, so when a run is resumed
+/// and existing output is replayed, those durations only reflect replay time.
+#[derive(Default)]
+struct ToolTimer {
+    started: std::collections::HashMap<String, std::time::Instant>,
+}
+
+impl ToolTimer {
+    /// Record the start of a tool call (the first sighting wins)
+    fn start(&mut self, tool_id: &str) {
+        self.started
+            .entry(tool_id.to_string())
+            .or_insert_with(std::time::Instant::now);
+    }
+
+    /// Elapsed milliseconds since the tool call started, if it was seen
+    fn finish(&mut self, tool_id: &str) -> Option<u64> {
+        self.started
+            .remove(tool_id)
+            .map(|started| started.elapsed().as_millis() as u64)
+    }
+}
+
+/// Extract the text output and error flag from a `tool_result` content block.
+///
+/// Content can be a string OR an array of content blocks (text items are joined).
+/// Shared by live streaming and run log reconstruction so both show the same result.
+pub fn parse_tool_result(block: &serde_json::Value) -> (String, bool) {
+    let output = block
+        .get("content")
+        .map(|v| {
+            if let Some(s) = v.as_str() {
+                s.to_string()
+            } else if let Some(arr) = v.as_array() {
+                arr.iter()
+                    .filter_map(|item| {
+                        if item.get("type").and_then(|t| t.as_str()) == Some("text") {
+                            item.get("text")
+                                .and_then(|t| t.as_str())
+                                .map(|s| s.to_string())
+                        } else {
+                            None
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            } else {
+                String::new()
+            }
+        })
+        .unwrap_or_default();
+    let output = strip_ansi(&output);
+    let is_error = block
+        .get("is_error")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    (output, is_error)
+}
+
+/// Remove ANSI escape sequences (colors, cursor movement, OSC titles/links)
+///
+/// Tools run through Bash often colorize output even without a TTY, and the
+/// raw escapes render as `^[[0m` noise in the UI. Text is otherwise untouched.
+fn strip_ansi(text: &str) -> String {
+    if !text.contains('\x1b') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters and intermediates, then a final byte in @..~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: terminated by BEL or ST (ESC \)
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Charset selection takes one more character
+            Some('(' | ')' | '*' | '+') => {
+                chars.next();
+            }
+            // Two-character escapes (keypad mode, reset, ...)
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Default seconds to wait for the first Claude output before giving up
+const DEFAULT_STARTUP_TIMEOUT_SECS: u64 = 120;
+
+/// Accepted range for a configured startup timeout (seconds)
+const STARTUP_TIMEOUT_RANGE_SECS: (u64, u64) = (10, 900);
+
+/// Resolve the startup timeout, clamping configured values to a sane range
+fn resolve_startup_timeout_secs(configured: Option<u64>) -> u64 {
+    let Some(secs) = configured else {
+        return DEFAULT_STARTUP_TIMEOUT_SECS;
+    };
+    let (min, max) = STARTUP_TIMEOUT_RANGE_SECS;
+    let clamped = secs.clamp(min, max);
+    if clamped != secs {
+        log::warn!("Startup timeout {secs}s is outside {min}-{max}s, using {clamped}s");
+    }
+    clamped
+}
+
+/// Default seconds to wait for more output after the Claude process has exited
+const DEFAULT_DEAD_PROCESS_TIMEOUT_SECS: u64 = 2;
+
+/// Accepted range for a configured dead-process timeout (seconds)
+const DEAD_PROCESS_TIMEOUT_RANGE_SECS: (u64, u64) = (1, 60);
+
+/// Resolve the dead-process timeout, clamping configured values to a sane range
+fn resolve_dead_process_timeout_secs(configured: Option<u64>) -> u64 {
+    let Some(secs) = configured else {
+        return DEFAULT_DEAD_PROCESS_TIMEOUT_SECS;
+    };
+    let (min, max) = DEAD_PROCESS_TIMEOUT_RANGE_SECS;
+    let clamped = secs.clamp(min, max);
+    if clamped != secs {
+        log::warn!("Dead-process timeout {secs}s is outside {min}-{max}s, using {clamped}s");
+    }
+    clamped
+}
+
+/// Configured tailer timeouts (None = default, see the resolve_* functions)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TailTimeouts {
+    /// Seconds to wait for the first Claude output
+    pub startup_secs: Option<u64>,
+    /// Seconds to wait for more output once the process has exited
+    pub dead_process_secs: Option<u64>,
+}
+
+impl TailTimeouts {
+    /// Read the configured timeouts from preferences (defaults if unavailable)
+    pub async fn load(app: &tauri::AppHandle) -> Self {
+        match crate::load_preferences(app.clone()).await {
+            Ok(prefs) => Self::from_preferences(&prefs),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// The configured timeouts from already-loaded preferences
+    pub fn from_preferences(prefs: &crate::AppPreferences) -> Self {
+        Self {
+            startup_secs: prefs.claude_startup_timeout_secs,
+            dead_process_secs: prefs.claude_dead_process_timeout_secs,
+        }
+    }
+}
+
+/// Most lines handled per tail loop iteration before re-checking cancellation
+const MAX_LINES_PER_POLL: usize = 500;
+
+/// Read what's left of the output after the process exited: new complete
+/// lines plus a final line that was flushed without a trailing newline.
+fn drain_after_exit(tailer: &mut super::tail::NdjsonTailer) -> Result<Vec<String>, String> {
+    let mut lines = tailer.poll()?;
+    while tailer.last_poll_capped() {
+        lines.extend(tailer.poll()?);
+    }
+    lines.extend(tailer.flush_buffer());
+    Ok(lines)
+}
+
+/// Queue the tailer's unterminated final line for one last pass through the
+/// tail loop, so it goes through the same event handling as every other line.
+/// Returns false if there is nothing left or the final pass already happened.
+fn queue_final_line(
+    tailer: &mut super::tail::NdjsonTailer,
+    drained_lines: &mut Vec<String>,
+    final_pass: &mut bool,
+) -> bool {
+    if *final_pass {
+        return false;
+    }
+    let Some(line) = tailer.flush_buffer() else {
+        return false;
+    };
+    drained_lines.push(line);
+    *final_pass = true;
+    true
+}
+
+/// Thinking/effort setting a run actually uses
+#[derive(Debug, PartialEq)]
+enum EffectiveThinking<'a> {
+    /// Adaptive thinking via `effortLevel` (takes precedence over thinking levels)
+    Effort(&'a EffortLevel),
+    /// Traditional thinking level (unset leaves the CLI default)
+    Level(Option<&'a ThinkingLevel>),
+}
+
+/// Apply the run's overrides to the requested thinking/effort setting.
+///
+/// Shared by build_claude_args (what is sent) and policy_adjustments (what the
+/// frontend is told), so the two can't drift apart. Also returns why the
+/// setting was overridden, if it was.
+fn effective_thinking<'a>(
+    execution_mode: Option<&str>,
+    thinking_level: Option<&'a ThinkingLevel>,
+    effort_level: Option<&'a EffortLevel>,
+    model: Option<&str>,
+    disable_thinking_in_non_plan_modes: bool,
+) -> (EffectiveThinking<'a>, Option<String>) {
+    let mode = execution_mode.unwrap_or("plan");
+    let thinking_disabled =
+        disable_thinking_in_non_plan_modes && (mode == "build" || mode == "yolo");
+    let disabled_reason = || format!("Thinking is disabled in {mode} mode by your settings");
+
+    match effort_level {
+        Some(_) if thinking_disabled => (
+            EffectiveThinking::Effort(&EffortLevel::Off),
+            Some(disabled_reason()),
+        ),
+        Some(effort) => {
+            let effective = effort_for_model(effort, model);
+            let reason = (effective != effort).then(|| {
+                format!(
+                    "Max effort isn't supported by model {}",
+                    model.unwrap_or("(default)")
+                )
+            });
+            (EffectiveThinking::Effort(effective), reason)
+        }
+        None if thinking_disabled => (
+            EffectiveThinking::Level(Some(&ThinkingLevel::Off)),
+            Some(disabled_reason()),
+        ),
+        None => (EffectiveThinking::Level(thinking_level), None),
+    }
+}
+
+/// Serialized name of a thinking level (e.g., "megathink")
+fn thinking_level_name(level: &ThinkingLevel) -> String {
+    serde_json::to_value(level)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Settings that build_claude_args will override compared to what was requested,
+/// derived from the same `effective_thinking` so the frontend can be told about them.
+fn policy_adjustments(
+    session_id: &str,
+    worktree_id: &str,
+    execution_mode: Option<&str>,
+    thinking_level: Option<&ThinkingLevel>,
+    effort_level: Option<&EffortLevel>,
+    model: Option<&str>,
+    disable_thinking_in_non_plan_modes: bool,
+) -> Vec<PolicyAdjustedEvent> {
+    let (effective, reason) = effective_thinking(
+        execution_mode,
+        thinking_level,
+        effort_level,
+        model,
+        disable_thinking_in_non_plan_modes,
+    );
+    let Some(reason) = reason else {
+        return Vec::new();
+    };
+
+    // Settings requested as off can't be overridden to anything less
+    let (field, requested, effective) = match effective {
+        EffectiveThinking::Effort(effective) => (
+            "effort_level",
+            effort_level
+                .and_then(|e| e.effort_value())
+                .map(str::to_string),
+            effective.effort_value().unwrap_or("off").to_string(),
+        ),
+        EffectiveThinking::Level(effective) => (
+            "thinking_level",
+            thinking_level
+                .filter(|l| l.is_enabled())
+                .map(thinking_level_name),
+            effective
+                .filter(|l| l.is_enabled())
+                .map_or_else(|| "off".to_string(), thinking_level_name),
+        ),
+    };
+
+    match requested {
+        Some(requested) if requested != effective => vec![PolicyAdjustedEvent {
+            session_id: session_id.to_string(),
+            worktree_id: worktree_id.to_string(),
+            field: field.to_string(),
+            requested,
+            effective,
+            reason,
+        }],
+        _ => Vec::new(),
+    }
+}
+
+/// Claude CLI `--permission-mode` for an execution mode (plan when unset)
+pub fn permission_mode(execution_mode: Option<&str>) -> &'static str {
+    match execution_mode.unwrap_or("plan") {
+        "build" => "acceptEdits",
+        "yolo" => "bypassPermissions",
+        _ => "plan",
+    }
+}
+
+/// Whether a run can use the web tools (WebFetch/WebSearch) without a permission
+/// prompt, which `--print` mode can't answer. Recorded on the run for debugging.
+pub fn web_access_allowed(permission_mode: &str, allowed_tools: &[String]) -> bool {
+    permission_mode == "bypassPermissions"
+        || allowed_tools
+            .iter()
+            .any(|tool| tool == "WebFetch" || tool == "WebSearch")
+}
+
+/// Whether models matching each name prefix accept the "max" effort level.
+/// First match wins, so more specific prefixes come first.
+const MAX_EFFORT_SUPPORT: &[(&str, bool)] = &[
+    ("claude-opus-4-6", true),
+    ("claude-opus", false),
+    ("opus", true),
+    ("claude-sonnet", false),
+    ("sonnet", false),
+    ("claude-haiku", false),
+    ("haiku", false),
+];
+
+/// Effort level to send for `model`: "max" is kept only for models known to
+/// accept it and otherwise downgraded to "high" (including unknown models).
+fn effort_for_model<'a>(effort: &'a EffortLevel, model: Option<&str>) -> &'a EffortLevel {
+    if *effort != EffortLevel::Max {
+        return effort;
+    }
+    let supports_max = model.is_some_and(|model| {
+        let model = model.trim().to_ascii_lowercase();
+        MAX_EFFORT_SUPPORT
+            .iter()
+            .find(|(prefix, _)| model.starts_with(prefix))
+            .is_some_and(|(_, supported)| *supported)
+    });
+    if supports_max {
+        effort
+    } else {
+        log::trace!("Model {model:?} doesn't support max effort, using high");
+        &EffortLevel::High
+    }
+}
+
 /// Build CLI arguments for Claude CLI.
 ///
 /// Returns a tuple of (args, env_vars) where env_vars are (key, value) pairs.
@@ -158,6 +1102,7 @@ fn build_claude_args(
     mcp_config: Option<&str>,
     chrome_enabled: bool,
     custom_profile_name: Option<&str>,
+    extra_env: &[(String, String)],
 ) -> (Vec<String>, Vec<(String, String)>) {
     let mut args = Vec::new();
     let mut env_vars = Vec::new();
@@ -171,7 +1116,7 @@ fn build_claude_args(
     args.push("--verbose".to_string());
 
     // Add app data directories
-    if let Ok(app_data_dir) = app.path().app_data_dir() {
+    if let Ok(app_data_dir) = crate::platform::resolve_app_data_dir(app) {
         if cfg!(debug_assertions) {
             args.push("--add-dir".to_string());
             args.push(app_data_dir.to_string_lossy().to_string());
@@ -212,21 +1157,10 @@ fn build_claude_args(
     }
 
     // Permission mode
-    let perm_mode = match execution_mode.unwrap_or("plan") {
-        "build" => "acceptEdits",
-        "yolo" => "bypassPermissions",
-        _ => "plan",
-    };
+    let perm_mode = permission_mode(execution_mode);
     args.push("--permission-mode".to_string());
     args.push(perm_mode.to_string());
 
-    // Thinking/Effort configuration
-    // If disable_thinking_in_non_plan_modes is true and mode is build/yolo, force off
-    let is_non_plan_override = disable_thinking_in_non_plan_modes && {
-        let mode = execution_mode.unwrap_or("plan");
-        mode == "build" || mode == "yolo"
-    };
-
     // Custom profile settings: resolve name → file path, pass to --settings (secrets stay in file, not in ps)
     if let Some(name) = custom_profile_name {
         if !name.is_empty() {
@@ -244,43 +1178,42 @@ fn build_claude_args(
     // Thinking/effort settings: passed as separate --settings JSON (no secrets here)
     let mut settings_json: Option<serde_json::Value> = None;
 
-    if let Some(effort) = effort_level {
-        // Opus 4.6 adaptive thinking: use effort parameter via --settings JSON
-        let effective_effort = if is_non_plan_override {
-            &EffortLevel::Off
-        } else {
-            effort
-        };
-
-        if let Some(effort_value) = effective_effort.effort_value() {
-            let obj = settings_json.get_or_insert_with(|| serde_json::json!({}));
-            if let Some(map) = obj.as_object_mut() {
-                map.insert(
-                    "effortLevel".to_string(),
-                    serde_json::Value::String(effort_value.to_string()),
-                );
+    // Overrides (e.g., thinking disabled in build/yolo mode) are applied here
+    let (effective, _) = effective_thinking(
+        execution_mode,
+        thinking_level,
+        effort_level,
+        model,
+        disable_thinking_in_non_plan_modes,
+    );
+    match effective {
+        EffectiveThinking::Effort(effective_effort) => {
+            // Opus 4.6 adaptive thinking: use effort parameter via --settings JSON
+            if let Some(effort_value) = effective_effort.effort_value() {
+                let obj = settings_json.get_or_insert_with(|| serde_json::json!({}));
+                if let Some(map) = obj.as_object_mut() {
+                    map.insert(
+                        "effortLevel".to_string(),
+                        serde_json::Value::String(effort_value.to_string()),
+                    );
+                }
             }
+            // If Off, don't send any thinking/effort settings (but still send custom profile if present)
         }
-        // If Off, don't send any thinking/effort settings (but still send custom profile if present)
-    } else {
-        // Traditional thinking levels (Opus 4.5, Sonnet, Haiku)
-        let effective_thinking_level = if is_non_plan_override {
-            Some(&ThinkingLevel::Off)
-        } else {
-            thinking_level
-        };
-
-        if let Some(level) = effective_thinking_level {
-            let obj = settings_json.get_or_insert_with(|| serde_json::json!({}));
-            if let Some(map) = obj.as_object_mut() {
-                map.insert(
-                    "alwaysThinkingEnabled".to_string(),
-                    serde_json::Value::Bool(level.is_enabled()),
-                );
-            }
+        EffectiveThinking::Level(effective_thinking_level) => {
+            // Traditional thinking levels (Opus 4.5, Sonnet, Haiku)
+            if let Some(level) = effective_thinking_level {
+                let obj = settings_json.get_or_insert_with(|| serde_json::json!({}));
+                if let Some(map) = obj.as_object_mut() {
+                    map.insert(
+                        "alwaysThinkingEnabled".to_string(),
+                        serde_json::Value::Bool(level.is_enabled()),
+                    );
+                }
 
-            if let Some(tokens) = level.thinking_tokens() {
-                env_vars.push(("MAX_THINKING_TOKENS".to_string(), tokens.to_string()));
+                if let Some(tokens) = level.thinking_tokens() {
+                    env_vars.push(("MAX_THINKING_TOKENS".to_string(), tokens.to_string()));
+                }
             }
         }
     }
@@ -299,6 +1232,14 @@ fn build_claude_args(
         }
     }
 
+    // Read-only git commands in plan mode (history/diff inspection without prompts)
+    if perm_mode == "plan" {
+        for tool in PLAN_MODE_GIT_READ_TOOLS {
+            args.push("--allowedTools".to_string());
+            args.push(tool.to_string());
+        }
+    }
+
     // Allow embedded CLI binaries without approval via --allowedTools
     // Claude wraps paths with spaces in quotes, so the actual command is:
     // "/Users/.../Application Support/.../gh-cli/gh" --version
@@ -433,7 +1374,7 @@ fn build_claude_args(
     }
 
     // Check for attached saved context files
-    if let Ok(app_data_dir) = app.path().app_data_dir() {
+    if let Ok(app_data_dir) = crate::platform::resolve_app_data_dir(app) {
         let saved_contexts_dir = app_data_dir.join("session-context");
         if saved_contexts_dir.exists() {
             let prefix = format!("{session_id}-context-");
@@ -463,7 +1404,7 @@ fn build_claude_args(
     // If we have context files OR system prompt parts, create a combined context file
     let has_system_prompts = !system_prompt_parts.is_empty();
     if !all_context_paths.is_empty() || has_system_prompts {
-        if let Ok(app_data_dir) = app.path().app_data_dir() {
+        if let Ok(app_data_dir) = crate::platform::resolve_app_data_dir(app) {
             let combined_contexts_dir = app_data_dir.join("combined-contexts");
             let _ = std::fs::create_dir_all(&combined_contexts_dir);
 
@@ -581,9 +1522,51 @@ fn build_claude_args(
         env_vars.push(("JEAN_CLAUDE_SESSION_ID".to_string(), claude_sid.to_string()));
     }
 
+    merge_extra_env(&mut env_vars, extra_env);
+
     (args, env_vars)
 }
 
+/// Check user-provided env vars before they're passed to the CLI
+fn validate_extra_env(extra_env: &[(String, String)]) -> Result<(), String> {
+    for (key, _) in extra_env {
+        if key.trim().is_empty() {
+            return Err("Extra environment variable names must not be empty".to_string());
+        }
+        if key.contains('=') || key.contains('\0') {
+            return Err(format!(
+                "Invalid extra environment variable name '{key}': must not contain '=' or NUL"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Add user-provided env vars, replacing built-in ones with the same key
+fn merge_extra_env(env_vars: &mut Vec<(String, String)>, extra_env: &[(String, String)]) {
+    for (key, value) in extra_env {
+        env_vars.retain(|(k, _)| k != key);
+        env_vars.push((key.clone(), value.clone()));
+    }
+}
+
+/// Send an error to the frontend as a `chat:error` event
+pub(super) fn emit_chat_error(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    worktree_id: &str,
+    error_msg: &str,
+) {
+    let error_event = ErrorEvent {
+        session_id: session_id.to_string(),
+        worktree_id: worktree_id.to_string(),
+        error: error_msg.to_string(),
+    };
+    if let Err(e) = app.emit_all("chat:error", &error_event) {
+        log::error!("Failed to emit chat:error event: {e}");
+    }
+}
+
 /// Execute Claude CLI in detached mode.
 ///
 /// Spawns Claude CLI as a fully detached process that survives Jean quitting.
@@ -609,6 +1592,8 @@ pub fn execute_claude_detached(
     mcp_config: Option<&str>,
     chrome_enabled: bool,
     custom_profile_name: Option<&str>,
+    extra_env: &[(String, String)],
+    timeouts: TailTimeouts,
 ) -> Result<(u32, ClaudeResponse), String> {
     use super::detached::spawn_detached_claude;
     use crate::claude_cli::get_cli_binary_path;
@@ -623,12 +1608,7 @@ pub fn execute_claude_detached(
         let error_msg =
             format!("Failed to get CLI path: {e}. Please complete setup in Settings > Advanced.");
         log::error!("{error_msg}");
-        let error_event = ErrorEvent {
-            session_id: session_id.to_string(),
-            worktree_id: worktree_id.to_string(),
-            error: error_msg.clone(),
-        };
-        let _ = app.emit_all("chat:error", &error_event);
+        emit_chat_error(app, session_id, worktree_id, &error_msg);
         error_msg
     })?;
 
@@ -636,12 +1616,20 @@ pub fn execute_claude_detached(
         let error_msg =
             "Claude CLI not installed. Please complete setup in Settings > Advanced.".to_string();
         log::error!("{error_msg}");
-        let error_event = ErrorEvent {
-            session_id: session_id.to_string(),
-            worktree_id: worktree_id.to_string(),
-            error: error_msg.clone(),
-        };
-        let _ = app.emit_all("chat:error", &error_event);
+        emit_chat_error(app, session_id, worktree_id, &error_msg);
+        return Err(error_msg);
+    }
+
+    // Refuse to spawn a CLI too old for the flags built below
+    if let Err(error_msg) = crate::claude_cli::ensure_supported_cli_version(&cli_path) {
+        log::error!("{error_msg}");
+        emit_chat_error(app, session_id, worktree_id, &error_msg);
+        return Err(error_msg);
+    }
+
+    if let Err(error_msg) = validate_extra_env(extra_env) {
+        log::error!("{error_msg}");
+        emit_chat_error(app, session_id, worktree_id, &error_msg);
         return Err(error_msg);
     }
 
@@ -662,8 +1650,29 @@ pub fn execute_claude_detached(
         mcp_config,
         chrome_enabled,
         custom_profile_name,
+        extra_env,
     );
 
+    // Tell the frontend about settings that were overridden for this run
+    for event in policy_adjustments(
+        session_id,
+        worktree_id,
+        execution_mode,
+        thinking_level,
+        effort_level,
+        model,
+        disable_thinking_in_non_plan_modes,
+    ) {
+        log::debug!(
+            "Policy adjusted for session {session_id}: {} {} -> {} ({})",
+            event.field,
+            event.requested,
+            event.effective,
+            event.reason
+        );
+        let _ = app.emit_all("chat:policy_adjusted", &event);
+    }
+
     // Log the full Claude CLI command for debugging
     log::debug!(
         "Claude CLI command: {} {}",
@@ -694,35 +1703,29 @@ pub fn execute_claude_detached(
     .map_err(|e| {
         let error_msg = format!("Failed to start Claude CLI: {e}");
         log::error!("{error_msg}");
-        let _ = app.emit_all(
-            "chat:error",
-            &ErrorEvent {
-                session_id: session_id.to_string(),
-                worktree_id: worktree_id.to_string(),
-                error: error_msg.clone(),
-            },
-        );
+        emit_chat_error(app, session_id, worktree_id, &error_msg);
         error_msg
     })?;
 
     log::trace!("Detached Claude CLI spawned with PID: {pid}");
 
     // Register the process for cancellation
-    super::registry::register_process(session_id.to_string(), pid);
-
-    // Tail the output file for real-time updates
-    // Use match to ensure unregister_process is always called, even on error
-    let response = match tail_claude_output(app, session_id, worktree_id, output_file, pid) {
-        Ok(resp) => {
-            super::registry::unregister_process(session_id);
-            resp
-        }
-        Err(e) => {
-            super::registry::unregister_process(session_id);
-            return Err(e);
+    // If another spawn for this session won the race, stop ours instead of sharing the output file
+    let _registration = match super::registry::ProcessRegistration::register(session_id, pid) {
+        Ok(registration) => registration,
+        Err(error_msg) => {
+            if let Err(e) = crate::platform::kill_process_tree(pid) {
+                log::warn!("Failed to kill duplicate Claude process {pid}: {e}");
+            }
+            emit_chat_error(app, session_id, worktree_id, &error_msg);
+            return Err(error_msg);
         }
     };
 
+    // Tail the output file for real-time updates
+    // The registration is dropped (unregistered) on every exit path, even on error
+    let response = tail_claude_output(app, session_id, worktree_id, output_file, pid, timeouts)?;
+
     Ok((pid, response))
 }
 
@@ -745,6 +1748,7 @@ pub fn tail_claude_output(
     worktree_id: &str,
     output_file: &std::path::Path,
     pid: u32,
+    timeouts: TailTimeouts,
 ) -> Result<ClaudeResponse, String> {
     use super::detached::is_process_alive;
     use super::tail::{NdjsonTailer, POLL_INTERVAL};
@@ -754,7 +1758,13 @@ pub fn tail_claude_output(
     log::trace!("Output file: {output_file:?}, PID: {pid}");
 
     // Create tailer starting from beginning (we want all content)
-    let mut tailer = NdjsonTailer::new_from_start(output_file)?;
+    // Capped so a burst of output can't delay cancellation checks
+    #[cfg(feature = "watched-tail")]
+    let mut tailer =
+        NdjsonTailer::new_watched(output_file)?.with_max_lines_per_poll(MAX_LINES_PER_POLL);
+    #[cfg(not(feature = "watched-tail"))]
+    let mut tailer =
+        NdjsonTailer::new_from_start(output_file)?.with_max_lines_per_poll(MAX_LINES_PER_POLL);
 
     let mut full_content = String::new();
     let mut claude_session_id = String::new();
@@ -763,20 +1773,57 @@ pub fn tail_claude_output(
     let mut completed = false;
     let mut cancelled = false;
     let mut usage: Option<UsageData> = None;
+    let mut run_duration_ms: Option<u64> = None;
+    let mut running_usage = UsageAccumulator::default();
+    let mut chunks = ChunkCoalescer::new(CHUNK_FLUSH_INTERVAL);
+    let mut resolved_model: Option<String> = None;
+    let mut tool_timer = ToolTimer::default();
+    let seq = EventSeq::default();
+    let mut parse_failures = ParseFailures::default();
+    let mut heartbeat = StartupHeartbeat::default();
 
     // Timeout configuration:
-    // - Startup timeout: Wait up to 120 seconds for first Claude output (API connection time)
-    // - Dead process timeout: After receiving output, wait 2 seconds for more if process seems dead
-    //   (Reduced from 10s since registry check now provides faster cancellation detection)
-    let startup_timeout = Duration::from_secs(120);
-    let dead_process_timeout = Duration::from_secs(2);
+    // - Startup timeout: Wait for first Claude output (API connection time), 120s unless configured
+    // - Dead process timeout: After receiving output, wait for more if process seems dead,
+    //   2s unless configured (registry check provides faster cancellation detection)
+    let startup_timeout = Duration::from_secs(resolve_startup_timeout_secs(timeouts.startup_secs));
+    let dead_process_timeout = Duration::from_secs(resolve_dead_process_timeout_secs(
+        timeouts.dead_process_secs,
+    ));
+    // Lines drained after the process exited, processed on the next iteration
+    let mut drained_lines: Vec<String> = Vec::new();
+    // Set once the unterminated final line has been queued; the loop exits after it
+    let mut final_pass = false;
+    // Warn only once if the run log disappears mid-run
+    let mut missing_log_warned = false;
+    let mut plan_writes = PlanModeWrites::default();
+    // Thinking stays in the run log but isn't shown for low-reasoning runs
+    let mut thinking_hidden = false;
     let started_at = Instant::now();
     let mut last_output_time = Instant::now();
     let mut received_claude_output = false; // Track if we've received any Claude output (not our metadata)
 
     loop {
         // Poll for new lines
-        let lines = tailer.poll()?;
+        let lines = if drained_lines.is_empty() {
+            match tailer.poll() {
+                Ok(lines) => lines,
+                // The log was deleted and can't be read anymore: keep what we have
+                Err(e) if tailer.file_removed() => {
+                    log::warn!("Run log {output_file:?} deleted and unreadable, finishing: {e}");
+                    emit_missing_log_warning(app, session_id, worktree_id, &mut missing_log_warned);
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+        } else {
+            std::mem::take(&mut drained_lines)
+        };
+
+        if lines.is_empty() && !missing_log_warned && tailer.file_removed() {
+            log::warn!("Run log {output_file:?} was deleted while tailing");
+            emit_missing_log_warning(app, session_id, worktree_id, &mut missing_log_warned);
+        }
 
         if !lines.is_empty() {
             last_output_time = Instant::now();
@@ -790,6 +1837,10 @@ pub fn tail_claude_output(
 
             // Skip metadata header (our own, not Claude output)
             if line.contains("\"_run_meta\"") {
+                plan_writes.observe_run_meta(&line);
+                if let Ok(meta) = serde_json::from_str::<serde_json::Value>(&line) {
+                    thinking_hidden = super::run_log::run_meta_hides_thinking(&meta);
+                }
                 continue;
             }
 
@@ -804,6 +1855,11 @@ pub fn tail_claude_output(
                 Ok(m) => m,
                 Err(e) => {
                     log::trace!("Failed to parse line: {e}");
+                    if let Some(warning) = parse_failures.record(session_id, worktree_id, &line) {
+                        if let Err(e) = app.emit_all("chat:stream_warning", &warning) {
+                            log::error!("Failed to emit stream warning: {e}");
+                        }
+                    }
                     continue;
                 }
             };
@@ -824,14 +1880,53 @@ pub fn tail_claude_output(
 
             let msg_type = msg.get("type").and_then(|v| v.as_str()).unwrap_or("");
 
+            // Flush buffered text before any other event so ordering is preserved
+            if msg_type != "assistant" {
+                if let Some(content) = chunks.flush() {
+                    emit_chunk(app, session_id, worktree_id, content, seq.next());
+                }
+            }
+
             match msg_type {
                 "assistant" => {
                     if let Some(message) = msg.get("message") {
+                        // Emit running usage so the UI can show consumption mid-run
+                        if let (Some(message_id), Some(usage_obj)) = (
+                            message.get("id").and_then(|v| v.as_str()),
+                            message.get("usage"),
+                        ) {
+                            if let Some(total) =
+                                running_usage.record(message_id, parse_usage(usage_obj))
+                            {
+                                let event = UsageUpdateEvent {
+                                    session_id: session_id.to_string(),
+                                    worktree_id: worktree_id.to_string(),
+                                    usage: total,
+                                };
+                                if let Err(e) = app.emit_all("chat:usage_update", &event) {
+                                    log::error!("Failed to emit usage_update: {e}");
+                                }
+                            }
+                        }
+
                         if let Some(blocks) = message.get("content").and_then(|c| c.as_array()) {
                             for block in blocks {
                                 let block_type =
                                     block.get("type").and_then(|v| v.as_str()).unwrap_or("");
 
+                                // Tool and thinking blocks are boundaries: flush text first
+                                if block_type != "text" {
+                                    if let Some(content) = chunks.flush() {
+                                        emit_chunk(
+                                            app,
+                                            session_id,
+                                            worktree_id,
+                                            content,
+                                            seq.next(),
+                                        );
+                                    }
+                                }
+
                                 match block_type {
                                     "text" => {
                                         if let Some(text) =
@@ -847,14 +1942,15 @@ pub fn tail_claude_output(
                                                 text: text.to_string(),
                                             });
 
-                                            // Emit chunk event
-                                            let event = ChunkEvent {
-                                                session_id: session_id.to_string(),
-                                                worktree_id: worktree_id.to_string(),
-                                                content: text.to_string(),
-                                            };
-                                            if let Err(e) = app.emit_all("chat:chunk", &event) {
-                                                log::error!("Failed to emit chunk: {e}");
+                                            // Emit chunk event (coalesced with nearby text)
+                                            if let Some(content) = chunks.push(text) {
+                                                emit_chunk(
+                                                    app,
+                                                    session_id,
+                                                    worktree_id,
+                                                    content,
+                                                    seq.next(),
+                                                );
                                             }
                                         }
                                     }
@@ -874,17 +1970,30 @@ pub fn tail_claude_output(
                                             .cloned()
                                             .unwrap_or(serde_json::Value::Null);
 
+                                        if let Some(warning) =
+                                            plan_writes.check_tool(session_id, worktree_id, &name)
+                                        {
+                                            if let Err(e) =
+                                                app.emit_all("chat:stream_warning", &warning)
+                                            {
+                                                log::error!("Failed to emit stream_warning: {e}");
+                                            }
+                                        }
+
                                         tool_calls.push(ToolCall {
                                             id: id.clone(),
                                             name: name.clone(),
                                             input: input.clone(),
                                             output: None,
                                             parent_tool_use_id: current_parent_tool_use_id.clone(),
+                                            is_error: false,
+                                            duration_ms: None,
                                         });
 
                                         content_blocks.push(ContentBlock::ToolUse {
                                             tool_call_id: id.clone(),
                                         });
+                                        tool_timer.start(&id);
 
                                         // Emit tool_use event
                                         let event = ToolUseEvent {
@@ -894,6 +2003,7 @@ pub fn tail_claude_output(
                                             name: name.clone(),
                                             input: input.clone(),
                                             parent_tool_use_id: current_parent_tool_use_id.clone(),
+                                            seq: seq.next(),
                                         };
                                         if let Err(e) = app.emit_all("chat:tool_use", &event) {
                                             log::error!("Failed to emit tool_use: {e}");
@@ -928,10 +2038,14 @@ pub fn tail_claude_output(
                                             }
 
                                             // Emit done event so frontend knows streaming is complete
-                                            let done_event = DoneEvent {
-                                                session_id: session_id.to_string(),
-                                                worktree_id: worktree_id.to_string(),
-                                            };
+                                            let done_event = DoneEvent::new(
+                                                session_id,
+                                                worktree_id,
+                                                seq.next(),
+                                                None,
+                                                None,
+                                                &claude_session_id,
+                                            );
                                             if let Err(e) = app.emit_all("chat:done", &done_event) {
                                                 log::error!("Failed to emit done event: {e}");
                                             }
@@ -944,10 +2058,11 @@ pub fn tail_claude_output(
                                                 content_blocks,
                                                 cancelled: false,
                                                 usage: None, // No usage for partial responses
+                                                resolved_model,
                                             });
                                         }
                                     }
-                                    "thinking" => {
+                                    "thinking" if !thinking_hidden => {
                                         if let Some(thinking) =
                                             block.get("thinking").and_then(|v| v.as_str())
                                         {
@@ -959,6 +2074,7 @@ pub fn tail_claude_output(
                                                 session_id: session_id.to_string(),
                                                 worktree_id: worktree_id.to_string(),
                                                 content: thinking.to_string(),
+                                                seq: seq.next(),
                                             };
                                             if let Err(e) = app.emit_all("chat:thinking", &event) {
                                                 log::error!("Failed to emit thinking: {e}");
@@ -984,38 +2100,16 @@ pub fn tail_claude_output(
                                         .get("tool_use_id")
                                         .and_then(|v| v.as_str())
                                         .unwrap_or("");
-                                    // Content can be a string OR an array of content blocks
-                                    let output = block
-                                        .get("content")
-                                        .map(|v| {
-                                            if let Some(s) = v.as_str() {
-                                                s.to_string()
-                                            } else if let Some(arr) = v.as_array() {
-                                                arr.iter()
-                                                    .filter_map(|item| {
-                                                        if item.get("type").and_then(|t| t.as_str())
-                                                            == Some("text")
-                                                        {
-                                                            item.get("text")
-                                                                .and_then(|t| t.as_str())
-                                                                .map(|s| s.to_string())
-                                                        } else {
-                                                            None
-                                                        }
-                                                    })
-                                                    .collect::<Vec<_>>()
-                                                    .join("\n")
-                                            } else {
-                                                String::new()
-                                            }
-                                        })
-                                        .unwrap_or_default();
+                                    let (output, is_error) = parse_tool_result(block);
+                                    let duration_ms = tool_timer.finish(tool_id);
 
                                     // Update matching tool call's output
                                     if let Some(tc) =
                                         tool_calls.iter_mut().find(|t| t.id == tool_id)
                                     {
                                         tc.output = Some(output.clone());
+                                        tc.is_error = is_error;
+                                        tc.duration_ms = duration_ms;
                                     }
 
                                     // Emit tool_result event
@@ -1024,6 +2118,9 @@ pub fn tail_claude_output(
                                         worktree_id: worktree_id.to_string(),
                                         tool_use_id: tool_id.to_string(),
                                         output,
+                                        is_error,
+                                        duration_ms,
+                                        seq: seq.next(),
                                     };
                                     if let Err(e) = app.emit_all("chat:tool_result", &event) {
                                         log::error!("Failed to emit tool_result: {e}");
@@ -1041,26 +2138,11 @@ pub fn tail_claude_output(
                         }
                     }
 
+                    run_duration_ms = msg.get("duration_ms").and_then(|v| v.as_u64());
+
                     // Extract token usage data
                     if let Some(usage_obj) = msg.get("usage") {
-                        usage = Some(UsageData {
-                            input_tokens: usage_obj
-                                .get("input_tokens")
-                                .and_then(|v| v.as_u64())
-                                .unwrap_or(0),
-                            output_tokens: usage_obj
-                                .get("output_tokens")
-                                .and_then(|v| v.as_u64())
-                                .unwrap_or(0),
-                            cache_read_input_tokens: usage_obj
-                                .get("cache_read_input_tokens")
-                                .and_then(|v| v.as_u64())
-                                .unwrap_or(0),
-                            cache_creation_input_tokens: usage_obj
-                                .get("cache_creation_input_tokens")
-                                .and_then(|v| v.as_u64())
-                                .unwrap_or(0),
-                        });
+                        usage = Some(parse_usage(usage_obj));
                         log::trace!(
                             "Token usage: input={}, output={}, cache_read={}, cache_create={}",
                             usage.as_ref().map(|u| u.input_tokens).unwrap_or(0),
@@ -1133,7 +2215,13 @@ pub fn tail_claude_output(
                 }
                 "system" => {
                     let subtype = msg.get("subtype").and_then(|v| v.as_str()).unwrap_or("");
-                    if subtype == "compact_boundary" {
+                    if subtype == "init" {
+                        // Init message reports the model the CLI resolved for this run
+                        if let Some(model) = msg.get("model").and_then(|v| v.as_str()) {
+                            log::trace!("Claude CLI resolved model: {model}");
+                            resolved_model = Some(model.to_string());
+                        }
+                    } else if subtype == "compact_boundary" {
                         log::trace!("Detected compact_boundary system message");
 
                         // Signal UI that compaction is in progress
@@ -1166,8 +2254,20 @@ pub fn tail_claude_output(
             }
         }
 
+        // Emit buffered text that has waited long enough
+        if let Some(content) = chunks.flush_if_due() {
+            emit_chunk(app, session_id, worktree_id, content, seq.next());
+        }
+
+        if final_pass {
+            break;
+        }
+
         // Check if completed
         if completed {
+            if queue_final_line(&mut tailer, &mut drained_lines, &mut final_pass) {
+                continue;
+            }
             break;
         }
 
@@ -1177,6 +2277,9 @@ pub fn tail_claude_output(
         if !super::registry::is_process_running(session_id) {
             log::trace!("Session {session_id} cancelled externally, stopping tail");
             cancelled = true;
+            if queue_final_line(&mut tailer, &mut drained_lines, &mut final_pass) {
+                continue;
+            }
             break;
         }
 
@@ -1186,6 +2289,17 @@ pub fn tail_claude_output(
         if received_claude_output {
             // After receiving output, use shorter timeout for detecting dead process
             if !process_alive && last_output_time.elapsed() > dead_process_timeout {
+                // The last line may still have been flushing when the process exited
+                drained_lines = drain_after_exit(&mut tailer)?;
+                if !drained_lines.is_empty() {
+                    log::trace!(
+                        "Drained {} line(s) after process {pid} exited",
+                        drained_lines.len()
+                    );
+                    last_output_time = Instant::now();
+                    continue;
+                }
+
                 log::trace!(
                     "Process {pid} is no longer running and no new output after receiving content"
                 );
@@ -1198,43 +2312,67 @@ pub fn tail_claude_output(
 
             if elapsed > startup_timeout {
                 log::warn!(
-                    "Startup timeout ({:?}) exceeded waiting for Claude output, process_alive: {process_alive}",
-                    startup_timeout
+                    "Startup timeout ({}s) exceeded waiting for Claude output, process_alive: {process_alive}",
+                    startup_timeout.as_secs()
                 );
                 cancelled = true;
+                if queue_final_line(&mut tailer, &mut drained_lines, &mut final_pass) {
+                    continue;
+                }
                 break;
             }
 
-            // Log progress every 10 seconds during startup (only log once per 10-second mark)
-            // Use subsec_millis to only log in the first 100ms of each 10-second window
-            let secs = elapsed.as_secs();
-            if secs > 0 && secs % 10 == 0 && elapsed.subsec_millis() < 100 {
+            // Report startup progress immediately, then every 10 seconds
+            if let Some(secs) = heartbeat.due(elapsed) {
                 log::trace!(
                     "Waiting for Claude output... {secs}s elapsed, process_alive: {process_alive}"
                 );
+                let progress = StreamProgressEvent {
+                    session_id: session_id.to_string(),
+                    worktree_id: worktree_id.to_string(),
+                    elapsed_secs: secs,
+                    process_alive,
+                };
+                if let Err(e) = app.emit_all("chat:stream_progress", &progress) {
+                    log::error!("Failed to emit stream progress: {e}");
+                }
             }
         }
 
-        // Sleep before next poll
-        std::thread::sleep(POLL_INTERVAL);
+        // Sleep before next poll (watched tailers already wait inside poll()),
+        // unless the last poll left lines unread
+        if !tailer.is_watched() && !tailer.last_poll_capped() {
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    // Never drop buffered text, even when the run was cancelled
+    if let Some(content) = chunks.flush() {
+        emit_chunk(app, session_id, worktree_id, content, seq.next());
     }
 
     // Emit done event only if not cancelled
     // (cancel_process already emitted chat:cancelled, avoid double event)
     if !cancelled {
-        let done_event = DoneEvent {
-            session_id: session_id.to_string(),
-            worktree_id: worktree_id.to_string(),
-        };
+        let done_event = DoneEvent::new(
+            session_id,
+            worktree_id,
+            seq.next(),
+            usage.clone(),
+            run_duration_ms,
+            &claude_session_id,
+        );
         if let Err(e) = app.emit_all("chat:done", &done_event) {
             log::error!("Failed to emit done event: {e}");
         }
     }
 
     log::trace!(
-        "Tailing complete: {} chars, {} tool calls, cancelled: {cancelled}",
+        "Tailing complete: {} chars, {} tool calls, {} unparseable lines, {} stderr lines, cancelled: {cancelled}",
         full_content.len(),
-        tool_calls.len()
+        tool_calls.len(),
+        parse_failures.count,
+        parse_failures.stderr_lines
     );
 
     Ok(ClaudeResponse {
@@ -1244,5 +2382,597 @@ pub fn tail_claude_output(
         content_blocks,
         cancelled,
         usage,
+        resolved_model,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_events_follow_log_order() {
+        let lines: Vec<String> = [
+            r#"{"_run_meta":true,"run_id":"r1"}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"thinking","thinking":"Look first"}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Checking."},{"type":"tool_use","id":"t1","name":"Read","input":{"file_path":"a.rs"}}]}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1","content":"fn main() {}"}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Done."}]}}"#,
+            r#"{"type":"result","result":"Done.","usage":{"input_tokens":1,"output_tokens":1}}"#,
+            "stderr noise",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        assert_eq!(
+            replay_events(&lines),
+            vec![
+                ReplayEvent::Thinking("Look first".to_string()),
+                ReplayEvent::Chunk("Checking.".to_string()),
+                ReplayEvent::ToolUse {
+                    id: "t1".to_string(),
+                    name: "Read".to_string(),
+                    input: serde_json::json!({"file_path": "a.rs"}),
+                    parent_tool_use_id: None,
+                },
+                ReplayEvent::ToolResult {
+                    tool_use_id: "t1".to_string(),
+                    output: "fn main() {}".to_string(),
+                    is_error: false,
+                },
+                ReplayEvent::Chunk("Done.".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replay_skips_hidden_thinking() {
+        let lines = vec![
+            r#"{"_run_meta":true,"run_id":"r1","thinking_hidden":true}"#.to_string(),
+            r#"{"type":"assistant","message":{"content":[{"type":"thinking","thinking":"Plan it"},{"type":"text","text":"Done."}]}}"#.to_string(),
+        ];
+        assert_eq!(
+            replay_events(&lines),
+            vec![ReplayEvent::Chunk("Done.".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_replay_delay() {
+        assert_eq!(replay_delay(1.0), std::time::Duration::ZERO);
+        assert_eq!(replay_delay(0.5), std::time::Duration::ZERO);
+        assert_eq!(replay_delay(3.0), std::time::Duration::from_millis(100));
+        assert_eq!(replay_delay(1e30), std::time::Duration::from_millis(950));
+        assert_eq!(replay_delay(f32::NAN), std::time::Duration::ZERO);
+    }
+
+    #[test]
+    fn test_plan_mode_write_tool_warns_once() {
+        let mut writes = PlanModeWrites::default();
+        writes.observe_run_meta(r#"{"_run_meta":true,"permission_mode":"plan"}"#);
+
+        let warning = writes.check_tool("s1", "w1", "Edit").unwrap();
+        assert!(warning.message.contains("Edit in plan mode"));
+        assert_eq!(warning.session_id, "s1");
+        // Once per tool, and read-only tools are fine
+        assert!(writes.check_tool("s1", "w1", "Edit").is_none());
+        assert!(writes.check_tool("s1", "w1", "Read").is_none());
+        assert!(writes.check_tool("s1", "w1", "Write").is_some());
+
+        // Writes are expected outside plan mode
+        let mut writes = PlanModeWrites::default();
+        writes.observe_run_meta(r#"{"_run_meta":true,"permission_mode":"acceptEdits"}"#);
+        assert!(writes.check_tool("s1", "w1", "Edit").is_none());
+    }
+
+    #[test]
+    fn test_effort_for_model() {
+        let max = EffortLevel::Max;
+        // Supported: kept as is
+        assert_eq!(effort_for_model(&max, Some("opus")), &EffortLevel::Max);
+        assert_eq!(
+            effort_for_model(&max, Some("claude-opus-4-6")),
+            &EffortLevel::Max
+        );
+        // Not supported or unknown: downgraded
+        assert_eq!(effort_for_model(&max, Some("sonnet")), &EffortLevel::High);
+        assert_eq!(
+            effort_for_model(&max, Some("claude-opus-4-5-20251101")),
+            &EffortLevel::High
+        );
+        assert_eq!(
+            effort_for_model(&max, Some("some-new-model")),
+            &EffortLevel::High
+        );
+        assert_eq!(effort_for_model(&max, None), &EffortLevel::High);
+        // Other levels pass through for every model
+        let low = EffortLevel::Low;
+        assert_eq!(effort_for_model(&low, Some("haiku")), &EffortLevel::Low);
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(
+            strip_ansi("\x1b[1;32m   Compiling\x1b[0m jean v0.1.0"),
+            "   Compiling jean v0.1.0"
+        );
+        assert_eq!(strip_ansi("\x1b[2K\x1b[1Gdone\n"), "done\n");
+        assert_eq!(
+            strip_ansi("\x1b]8;;https://example.com\x07link\x1b]8;;\x1b\\ text"),
+            "link text"
+        );
+        assert_eq!(strip_ansi("\x1b(Bplain"), "plain");
+        // Text without escapes (including unicode) passes through
+        assert_eq!(strip_ansi("ok ✓ [1/2]"), "ok ✓ [1/2]");
+
+        let block = serde_json::json!({
+            "type": "tool_result",
+            "content": "\x1b[31merror\x1b[0m: build failed",
+            "is_error": true,
+        });
+        assert_eq!(
+            parse_tool_result(&block),
+            ("error: build failed".to_string(), true)
+        );
+    }
+
+    #[test]
+    fn test_done_event_carries_result_metadata() {
+        let usage = UsageData {
+            input_tokens: 120,
+            output_tokens: 45,
+            ..Default::default()
+        };
+        let event = DoneEvent::new("s1", "w1", 7, Some(usage), Some(3200), "claude-abc");
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["usage"]["input_tokens"], 120);
+        assert_eq!(json["usage"]["output_tokens"], 45);
+        assert_eq!(json["duration_ms"], 3200);
+        assert_eq!(json["claude_session_id"], "claude-abc");
+
+        // Partial responses omit the fields entirely
+        let json = serde_json::to_value(DoneEvent::new("s1", "w1", 8, None, None, "")).unwrap();
+        assert_eq!(json["seq"], 8);
+        assert!(json.get("usage").is_none());
+        assert!(json.get("duration_ms").is_none());
+        assert!(json.get("claude_session_id").is_none());
+    }
+
+    #[test]
+    fn test_plan_mode_git_tools_are_read_only() {
+        let write_subcommands = [
+            "commit", "push", "pull", "fetch", "checkout", "reset", "merge", "rebase", "add",
+            "stash", "tag", "clean",
+        ];
+        for tool in PLAN_MODE_GIT_READ_TOOLS {
+            assert!(tool.starts_with("Bash(git "), "unexpected tool: {tool}");
+            let subcommand = tool
+                .trim_start_matches("Bash(git ")
+                .split([':', ' ', ')'])
+                .next()
+                .unwrap_or("");
+            assert!(
+                !write_subcommands.contains(&subcommand),
+                "write subcommand allowed in plan mode: {tool}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_plan_mode_git_tools_reject_output_files() {
+        // Mirrors the CLI's matching: `:*` is a prefix match, anything else is exact
+        let allowed = |command: &str| {
+            PLAN_MODE_GIT_READ_TOOLS.iter().any(|tool| {
+                let rule = tool.trim_start_matches("Bash(").trim_end_matches(')');
+                match rule.strip_suffix(":*") {
+                    Some(prefix) => command.starts_with(prefix),
+                    None => command == rule,
+                }
+            })
+        };
+
+        assert!(allowed("git diff --stat"));
+        assert!(allowed("git log --oneline"));
+        assert!(allowed("git status --short"));
+        for command in [
+            "git diff --output=/tmp/x",
+            "git log --output=/tmp/x",
+            "git show --output=/tmp/x HEAD",
+            "git diff --stat --output=/tmp/x",
+        ] {
+            assert!(
+                !allowed(command),
+                "plan mode allows writing a file: {command}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_policy_adjustments_only_in_non_plan_modes() {
+        let effort = EffortLevel::High;
+        let adjustments =
+            policy_adjustments("s", "w", Some("build"), None, Some(&effort), None, true);
+        assert_eq!(adjustments.len(), 1);
+        assert_eq!(adjustments[0].field, "effort_level");
+        assert_eq!(adjustments[0].requested, "high");
+        assert_eq!(adjustments[0].effective, "off");
+
+        assert!(
+            policy_adjustments("s", "w", Some("plan"), None, Some(&effort), None, true).is_empty()
+        );
+        assert!(
+            policy_adjustments("s", "w", Some("yolo"), None, Some(&effort), None, false).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_policy_adjustments_skip_settings_already_off() {
+        let thinking = ThinkingLevel::Megathink;
+        let adjustments =
+            policy_adjustments("s", "w", Some("yolo"), Some(&thinking), None, None, true);
+        assert_eq!(adjustments.len(), 1);
+        assert_eq!(adjustments[0].field, "thinking_level");
+        assert_eq!(adjustments[0].requested, "megathink");
+
+        let off = ThinkingLevel::Off;
+        assert!(
+            policy_adjustments("s", "w", Some("yolo"), Some(&off), None, None, true).is_empty()
+        );
+        let effort_off = EffortLevel::Off;
+        assert!(
+            policy_adjustments("s", "w", Some("build"), None, Some(&effort_off), None, true)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_policy_adjustments_report_max_effort_downgrade() {
+        let max = EffortLevel::Max;
+        let adjustments = policy_adjustments(
+            "s",
+            "w",
+            Some("plan"),
+            None,
+            Some(&max),
+            Some("sonnet"),
+            false,
+        );
+        assert_eq!(adjustments.len(), 1);
+        assert_eq!(adjustments[0].requested, "max");
+        assert_eq!(adjustments[0].effective, "high");
+        assert!(policy_adjustments(
+            "s",
+            "w",
+            Some("plan"),
+            None,
+            Some(&max),
+            Some("opus"),
+            false
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_effective_thinking_matches_reported_adjustments() {
+        let high = EffortLevel::High;
+        let ultrathink = ThinkingLevel::Ultrathink;
+        for mode in ["plan", "build", "yolo"] {
+            for disable in [false, true] {
+                let (effective, reason) =
+                    effective_thinking(Some(mode), Some(&ultrathink), None, None, disable);
+                let reported = policy_adjustments(
+                    "s",
+                    "w",
+                    Some(mode),
+                    Some(&ultrathink),
+                    None,
+                    None,
+                    disable,
+                );
+                assert_eq!(reason.is_some(), !reported.is_empty());
+                assert_eq!(
+                    effective == EffectiveThinking::Level(Some(&ultrathink)),
+                    reported.is_empty()
+                );
+
+                let (effective, _) =
+                    effective_thinking(Some(mode), None, Some(&high), None, disable);
+                let reported =
+                    policy_adjustments("s", "w", Some(mode), None, Some(&high), None, disable);
+                assert_eq!(
+                    effective == EffectiveThinking::Effort(&high),
+                    reported.is_empty()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_merge_extra_env_overrides_built_ins() {
+        let mut env_vars = vec![
+            ("JEAN_MODEL".to_string(), "opus".to_string()),
+            ("JEAN_SESSION_ID".to_string(), "s".to_string()),
+        ];
+        let extra = vec![
+            ("HTTP_PROXY".to_string(), "http://proxy:8080".to_string()),
+            ("JEAN_MODEL".to_string(), "custom".to_string()),
+        ];
+        merge_extra_env(&mut env_vars, &extra);
+
+        let get = |key: &str| {
+            env_vars
+                .iter()
+                .filter(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(get("HTTP_PROXY"), vec!["http://proxy:8080"]);
+        assert_eq!(get("JEAN_MODEL"), vec!["custom"]);
+        assert_eq!(get("JEAN_SESSION_ID"), vec!["s"]);
+    }
+
+    #[test]
+    fn test_validate_extra_env() {
+        let env = |key: &str| vec![(key.to_string(), "v".to_string())];
+        assert!(validate_extra_env(&env("HTTPS_PROXY")).is_ok());
+        assert!(validate_extra_env(&env("")).is_err());
+        assert!(validate_extra_env(&env("  ")).is_err());
+        assert!(validate_extra_env(&env("A=B")).is_err());
+    }
+
+    #[test]
+    fn test_run_permission_policy() {
+        assert_eq!(permission_mode(None), "plan");
+        assert_eq!(permission_mode(Some("build")), "acceptEdits");
+        assert_eq!(permission_mode(Some("yolo")), "bypassPermissions");
+
+        let web_tools = vec!["WebFetch".to_string(), "WebSearch".to_string()];
+        assert!(web_access_allowed(permission_mode(Some("yolo")), &[]));
+        assert!(!web_access_allowed(permission_mode(Some("build")), &[]));
+        assert!(!web_access_allowed(permission_mode(Some("plan")), &[]));
+        assert!(web_access_allowed("plan", &web_tools));
+    }
+
+    #[test]
+    fn test_resolve_dead_process_timeout_secs() {
+        assert_eq!(resolve_dead_process_timeout_secs(None), 2);
+        assert_eq!(resolve_dead_process_timeout_secs(Some(10)), 10);
+        assert_eq!(resolve_dead_process_timeout_secs(Some(0)), 1);
+        assert_eq!(resolve_dead_process_timeout_secs(Some(600)), 60);
+    }
+
+    #[test]
+    fn test_drain_after_exit_picks_up_late_final_line() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.jsonl");
+        let mut file = std::fs::File::create(&path).unwrap();
+        writeln!(file, r#"{{"type":"assistant"}}"#).unwrap();
+
+        let mut tailer = super::super::tail::NdjsonTailer::new_from_start(&path).unwrap();
+        assert_eq!(tailer.poll().unwrap().len(), 1);
+
+        // Process has exited, but its final line lands just afterwards (no newline yet)
+        write!(file, r#"{{"type":"result","result":"done"}}"#).unwrap();
+        file.flush().unwrap();
+
+        let drained = drain_after_exit(&mut tailer).unwrap();
+        assert_eq!(
+            drained,
+            vec![r#"{"type":"result","result":"done"}"#.to_string()]
+        );
+        assert!(drain_after_exit(&mut tailer).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_queue_final_line_runs_unterminated_result_once() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.jsonl");
+        let mut file = std::fs::File::create(&path).unwrap();
+        writeln!(file, r#"{{"type":"assistant"}}"#).unwrap();
+        write!(file, r#"{{"type":"result","usage":{{"output_tokens":5}}}}"#).unwrap();
+        file.flush().unwrap();
+
+        let mut tailer = super::super::tail::NdjsonTailer::new_from_start(&path).unwrap();
+        assert_eq!(tailer.poll().unwrap().len(), 1);
+
+        let mut drained = Vec::new();
+        let mut final_pass = false;
+        assert!(queue_final_line(&mut tailer, &mut drained, &mut final_pass));
+        assert!(final_pass);
+        let event: serde_json::Value = serde_json::from_str(&drained[0]).unwrap();
+        assert_eq!(event["usage"]["output_tokens"], 5);
+
+        // Only one final pass, even if more data shows up
+        write!(file, "trailing").unwrap();
+        file.flush().unwrap();
+        let queued = queue_final_line(&mut tailer, &mut drained, &mut final_pass);
+        assert!(!queued);
+        assert_eq!(drained.len(), 1);
+    }
+
+    #[test]
+    fn test_resolve_startup_timeout_secs() {
+        assert_eq!(resolve_startup_timeout_secs(None), 120);
+        assert_eq!(resolve_startup_timeout_secs(Some(300)), 300);
+        assert_eq!(resolve_startup_timeout_secs(Some(1)), 10);
+        assert_eq!(resolve_startup_timeout_secs(Some(5000)), 900);
+    }
+
+    #[test]
+    fn test_parse_tool_result() {
+        let block = serde_json::json!({
+            "type": "tool_result",
+            "tool_use_id": "toolu_1",
+            "content": "Exit code 1\nerror: no such file",
+            "is_error": true
+        });
+        assert_eq!(
+            parse_tool_result(&block),
+            ("Exit code 1\nerror: no such file".to_string(), true)
+        );
+
+        let block = serde_json::json!({
+            "type": "tool_result",
+            "tool_use_id": "toolu_2",
+            "content": [
+                {"type": "text", "text": "line one"},
+                {"type": "image", "source": {}},
+                {"type": "text", "text": "line two"}
+            ]
+        });
+        assert_eq!(
+            parse_tool_result(&block),
+            ("line one\nline two".to_string(), false)
+        );
+    }
+
+    #[test]
+    fn test_parse_failures_rate_limits_warnings() {
+        let mut failures = ParseFailures::default();
+        let warnings: Vec<_> = (0..5)
+            .map(|i| failures.record("s", "w", &format!("{{\"type\":\"assistant\",{i}")))
+            .collect();
+
+        assert_eq!(warnings.iter().filter(|w| w.is_some()).count(), 3);
+        let third = warnings[2].as_ref().unwrap();
+        assert_eq!(third.unparseable_count, 3);
+        assert!(third.message.contains("3 unparseable lines so far"));
+        assert_eq!(third.line_preview, "{\"type\":\"assistant\",2");
+        assert_eq!(failures.count, 5);
+    }
+
+    #[test]
+    fn test_parse_failures_skips_repeated_identical_lines() {
+        let mut failures = ParseFailures::default();
+        let lines = ["{\"a\"", "{\"a\" ", "{\"a\"", "{\"b\"", "{\"b\""];
+        let warnings: Vec<_> = lines
+            .iter()
+            .filter_map(|line| failures.record("s", "w", line))
+            .collect();
+
+        // Repeats are counted but only distinct consecutive lines are warned about
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].line_preview, "{\"a\"");
+        assert_eq!(warnings[1].line_preview, "{\"b\"");
+        assert_eq!(warnings[1].unparseable_count, 4);
+        assert_eq!(failures.count, 5);
+    }
+
+    #[test]
+    fn test_parse_failures_counts_stderr_separately() {
+        let mut failures = ParseFailures::default();
+        assert!(failures.record("s", "w", "proxy warning").is_none());
+        assert!(failures.record("s", "w", "  Error: spawn ENOENT").is_none());
+        assert_eq!(failures.stderr_lines, 2);
+        assert_eq!(failures.count, 0);
+
+        let warning = failures.record("s", "w", "{\"type\":\"assis").unwrap();
+        assert_eq!(warning.unparseable_count, 1);
+    }
+
+    #[test]
+    fn test_line_preview_truncates_long_lines() {
+        let long = "é".repeat(500);
+        let preview = line_preview(&long);
+        assert_eq!(preview.chars().count(), LINE_PREVIEW_MAX_CHARS + 1);
+        assert!(preview.ends_with('…'));
+        assert_eq!(line_preview("  short  "), "short");
+    }
+
+    #[test]
+    fn test_startup_heartbeat_cadence() {
+        use std::time::Duration;
+
+        let mut heartbeat = StartupHeartbeat::default();
+        assert_eq!(heartbeat.due(Duration::from_millis(0)), Some(0));
+        assert_eq!(heartbeat.due(Duration::from_millis(300)), None);
+        assert_eq!(heartbeat.due(Duration::from_secs(9)), None);
+        assert_eq!(heartbeat.due(Duration::from_millis(10_050)), Some(10));
+        assert_eq!(heartbeat.due(Duration::from_secs(11)), None);
+        // A slow poll that skips a window still emits once, on the next opportunity
+        assert_eq!(heartbeat.due(Duration::from_secs(35)), Some(35));
+        assert_eq!(heartbeat.due(Duration::from_secs(39)), None);
+        assert_eq!(heartbeat.due(Duration::from_secs(40)), Some(40));
+    }
+
+    #[test]
+    fn test_event_seq_is_monotonic_per_run() {
+        let seq = EventSeq::default();
+        assert_eq!(seq.next(), 0);
+        assert_eq!(seq.next(), 1);
+        assert_eq!(seq.next(), 2);
+
+        // Each run gets its own counter
+        assert_eq!(EventSeq::default().next(), 0);
+    }
+
+    #[test]
+    fn test_tool_timer_measures_start_to_result() {
+        let mut timer = ToolTimer::default();
+        timer.start("tool-1");
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        // A repeated tool_use for the same id keeps the original start
+        timer.start("tool-1");
+
+        let duration = timer.finish("tool-1").unwrap();
+        assert!(duration >= 20, "duration was {duration}ms");
+
+        // Already finished, or never started: unknown
+        assert_eq!(timer.finish("tool-1"), None);
+        assert_eq!(timer.finish("tool-2"), None);
+    }
+
+    #[test]
+    fn test_usage_accumulator_dedupes_repeated_messages() {
+        let usage = |input, output| UsageData {
+            input_tokens: input,
+            output_tokens: output,
+            ..Default::default()
+        };
+        let mut acc = UsageAccumulator::default();
+
+        assert_eq!(acc.record("msg_1", usage(100, 5)), Some(usage(100, 5)));
+        // Same usage repeated on another content block of the same message
+        assert_eq!(acc.record("msg_1", usage(100, 5)), None);
+        // Later update for the same message replaces (not adds to) its usage
+        assert_eq!(acc.record("msg_1", usage(100, 20)), Some(usage(100, 20)));
+        assert_eq!(acc.record("msg_2", usage(150, 10)), Some(usage(250, 30)));
+    }
+
+    #[test]
+    fn test_chunk_coalescer_preserves_content() {
+        let deltas: Vec<String> = (0..500).map(|i| format!("tok{i} ")).collect();
+        let expected: String = deltas.concat();
+
+        for interval in [
+            std::time::Duration::ZERO,
+            std::time::Duration::from_millis(1),
+            std::time::Duration::from_secs(3600),
+        ] {
+            let mut chunks = ChunkCoalescer::new(interval);
+            let mut emitted = Vec::new();
+            for delta in &deltas {
+                emitted.extend(chunks.push(delta));
+            }
+            emitted.extend(chunks.flush());
+
+            assert_eq!(emitted.concat(), expected);
+            if interval > std::time::Duration::from_secs(1) {
+                // Only the first push and the final flush emit
+                assert_eq!(emitted.len(), 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_chunk_coalescer_flush_when_empty() {
+        let mut chunks = ChunkCoalescer::new(CHUNK_FLUSH_INTERVAL);
+        assert_eq!(chunks.flush(), None);
+        assert_eq!(chunks.flush_if_due(), None);
+    }
+}
@@ -3,7 +3,7 @@ use std::path::PathBuf;
 use std::process::Stdio;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 use uuid::Uuid;
 
 use super::naming::{spawn_naming_task, NamingRequest};
@@ -15,7 +15,8 @@ use super::storage::{
 };
 use super::types::{
     AllSessionsEntry, AllSessionsResponse, ChatMessage, ClaudeContext, EffortLevel, MessageRole,
-    RunStatus, Session, SessionDigest, ThinkingLevel, WorktreeSessions,
+    RunEntry, RunRequestOptions, RunStatus, Session, SessionDigest, ThinkingLevel,
+    WorktreeSessions,
 };
 use crate::claude_cli::get_cli_binary_path;
 use crate::http_server::EmitExt;
@@ -197,20 +198,13 @@ pub async fn create_session(
 pub async fn rename_session(
     app: AppHandle,
     worktree_id: String,
-    worktree_path: String,
     session_id: String,
     new_name: String,
 ) -> Result<(), String> {
     log::trace!("Renaming session {session_id} to: {new_name}");
 
-    with_sessions_mut(&app, &worktree_path, &worktree_id, |sessions| {
-        if let Some(session) = sessions.find_session_mut(&session_id) {
-            session.name = new_name;
-            Ok(())
-        } else {
-            Err(format!("Session not found: {session_id}"))
-        }
-    })
+    let new_name = super::storage::normalize_session_name(&new_name)?;
+    super::storage::rename_session_metadata(&app, &worktree_id, &session_id, &new_name)
 }
 
 /// Regenerate session name using AI based on the first user message
@@ -903,6 +897,57 @@ pub async fn send_chat_message(
     mcp_config: Option<String>,
     chrome_enabled: Option<bool>,
     custom_profile_name: Option<String>,
+    labels: Option<Vec<String>>,
+    resume_from_run_id: Option<String>,
+) -> Result<ChatMessage, String> {
+    send_chat_message_inner(
+        app,
+        session_id,
+        worktree_id,
+        worktree_path,
+        message,
+        model,
+        execution_mode,
+        thinking_level,
+        effort_level,
+        disable_thinking_for_mode,
+        parallel_execution_prompt,
+        ai_language,
+        allowed_tools,
+        mcp_config,
+        chrome_enabled,
+        custom_profile_name,
+        labels,
+        resume_from_run_id,
+        None,
+    )
+    .await
+}
+
+/// Shared implementation of send_chat_message.
+/// `resume_from_run_id` branches from an earlier run's Claude session instead of the latest.
+/// `retried_from` links the new run to a crashed run it replaces (see retry_run).
+#[allow(clippy::too_many_arguments)]
+async fn send_chat_message_inner(
+    app: tauri::AppHandle,
+    session_id: String,
+    worktree_id: String,
+    worktree_path: String,
+    message: String,
+    model: Option<String>,
+    execution_mode: Option<String>,
+    thinking_level: Option<ThinkingLevel>,
+    effort_level: Option<EffortLevel>,
+    disable_thinking_for_mode: Option<bool>,
+    parallel_execution_prompt: Option<String>,
+    ai_language: Option<String>,
+    allowed_tools: Option<Vec<String>>,
+    mcp_config: Option<String>,
+    chrome_enabled: Option<bool>,
+    custom_profile_name: Option<String>,
+    labels: Option<Vec<String>>,
+    resume_from_run_id: Option<String>,
+    retried_from: Option<String>,
 ) -> Result<ChatMessage, String> {
     log::trace!("Sending chat message for session: {session_id}, worktree: {worktree_id}, model: {model:?}, execution_mode: {execution_mode:?}, thinking: {thinking_level:?}, effort: {effort_level:?}, disable_thinking_for_mode: {disable_thinking_for_mode:?}, allowed_tools: {allowed_tools:?}");
 
@@ -1072,9 +1117,71 @@ pub async fn send_chat_message(
     let context = ClaudeContext::new(worktree_path.clone());
 
     // Get the Claude session ID for resumption
-    let claude_session_id = sessions
-        .find_session(&session_id)
-        .and_then(|s| s.claude_session_id.clone());
+    // (branching from an earlier run resumes that run's Claude session instead)
+    let claude_session_id = match &resume_from_run_id {
+        Some(run_id) => Some(run_log::claude_session_id_for_run(
+            &app,
+            &session_id,
+            run_id,
+        )?),
+        None => sessions
+            .find_session(&session_id)
+            .and_then(|s| s.claude_session_id.clone()),
+    };
+
+    // Recorded on the run so a retry can send it the same way
+    let request_options = RunRequestOptions {
+        disable_thinking_for_mode,
+        parallel_execution_prompt: parallel_execution_prompt.clone(),
+        ai_language: ai_language.clone(),
+        allowed_tools: allowed_tools.clone(),
+        mcp_config: mcp_config.clone(),
+        chrome_enabled,
+        custom_profile_name: custom_profile_name.clone(),
+    };
+
+    let prefs = crate::load_preferences(app.clone()).await.ok();
+
+    // Inject WebFetch/WebSearch in plan mode if preference is enabled
+    let mut final_allowed_tools = allowed_tools.unwrap_or_default();
+    if execution_mode.as_deref() == Some("plan")
+        && prefs
+            .as_ref()
+            .is_some_and(|p| p.allow_web_tools_in_plan_mode)
+    {
+        final_allowed_tools.push("WebFetch".to_string());
+        final_allowed_tools.push("WebSearch".to_string());
+    }
+
+    // Refuse oversized messages before a run is recorded for them
+    let max_input_bytes =
+        run_log::max_input_bytes(prefs.as_ref().and_then(|p| p.claude_max_input_kb));
+    run_log::check_input_size(&message, max_input_bytes)?;
+
+    // One process per session: a second spawn would corrupt the shared output
+    // file, so refuse before a run is recorded for this message
+    if let Err(error_msg) = super::registry::ensure_session_idle(&session_id) {
+        log::warn!("{error_msg}");
+        super::claude::emit_chat_error(&app, &session_id, &worktree_id, &error_msg);
+        return Err(error_msg);
+    }
+
+    // Effective permissions, recorded on the run for debugging
+    let permission_mode = super::claude::permission_mode(execution_mode.as_deref());
+    let web_access = super::claude::web_access_allowed(permission_mode, &final_allowed_tools);
+
+    let thinking_level_name = thinking_level
+        .as_ref()
+        .map(|t| format!("{t:?}").to_lowercase());
+    let effort_value = effort_level.as_ref().and_then(|e| e.effort_value());
+    // Low-reasoning runs keep their thinking in the log but out of the UI
+    let thinking_hidden = run_log::thinking_hidden_for(
+        thinking_level_name.as_deref(),
+        effort_value,
+        prefs
+            .as_ref()
+            .and_then(|p| p.claude_min_thinking_display_level.as_deref()),
+    );
 
     // Start NDJSON run log for crash recovery
     let mut run_log_writer = run_log::start_run(
@@ -1087,14 +1194,16 @@ pub async fn send_chat_message(
         &message,
         model.as_deref(),
         execution_mode.as_deref(),
-        thinking_level
-            .as_ref()
-            .map(|t| format!("{t:?}").to_lowercase())
-            .as_deref(),
-        effort_level
-            .as_ref()
-            .and_then(|e| e.effort_value())
-            .or(None),
+        thinking_level_name.as_deref(),
+        effort_value,
+        labels.as_deref().unwrap_or_default(),
+        Some(&worktree_path),
+        retried_from.as_deref(),
+        resume_from_run_id.as_deref(),
+        Some(permission_mode),
+        Some(web_access),
+        thinking_hidden,
+        request_options,
     )?;
 
     // Get file paths for detached execution
@@ -1103,7 +1212,7 @@ pub async fn send_chat_message(
     let run_id = run_log_writer.run_id().to_string();
 
     // Write input file with the user message
-    run_log::write_input_file(&app, &session_id, &run_id, &message)?;
+    run_log::write_input_file(&app, &session_id, &run_id, &message, max_input_bytes)?;
 
     // Use passed parameter for thinking override (computed by frontend based on preference + manual override)
     let disable_thinking_in_non_plan_modes = disable_thinking_for_mode.unwrap_or(false);
@@ -1114,22 +1223,25 @@ pub async fn send_chat_message(
     // Use passed parameter for Chrome browser integration (default false - beta)
     let chrome = chrome_enabled.unwrap_or(false);
 
-    // Inject WebFetch/WebSearch in plan mode if preference is enabled
-    let mut final_allowed_tools = allowed_tools.unwrap_or_default();
-    if execution_mode.as_deref() == Some("plan") {
-        if let Ok(prefs) = crate::load_preferences(app.clone()).await {
-            if prefs.allow_web_tools_in_plan_mode {
-                final_allowed_tools.push("WebFetch".to_string());
-                final_allowed_tools.push("WebSearch".to_string());
-            }
-        }
-    }
     let allowed_tools_for_cli = if final_allowed_tools.is_empty() {
         None
     } else {
         Some(final_allowed_tools)
     };
 
+    // Configurable tailer timeouts (large prompts / slow filesystems)
+    let timeouts = prefs
+        .as_ref()
+        .map(super::claude::TailTimeouts::from_preferences)
+        .unwrap_or_default();
+
+    // User-configured env vars for the CLI (proxies, tokens for MCP servers/hooks)
+    let mut extra_env: Vec<(String, String)> = prefs
+        .as_ref()
+        .map(|prefs| prefs.claude_extra_env.clone().into_iter().collect())
+        .unwrap_or_default();
+    extra_env.sort();
+
     // Execute Claude CLI in detached mode
     // If resume fails with "session not found", retry without the session ID
     let mut claude_session_id_for_call = claude_session_id.clone();
@@ -1155,6 +1267,8 @@ pub async fn send_chat_message(
             mcp_config.as_deref(),
             chrome,
             custom_profile_name.as_deref(),
+            &extra_env,
+            timeouts,
         ) {
             Ok((pid, response)) => {
                 log::trace!("execute_claude_detached succeeded (PID: {pid})");
@@ -1250,6 +1364,10 @@ pub async fn send_chat_message(
             effort_level: None,
             recovered: false,
             usage: None,
+            working_dir: None,
+            git_branch: None,
+            retried_from: None,
+            compacted_runs: None,
         });
     }
 
@@ -1265,12 +1383,16 @@ pub async fn send_chat_message(
         content_blocks: claude_response.content_blocks,
         cancelled: claude_response.cancelled,
         plan_approved: false,
-        model: None,
+        model: claude_response.resolved_model.clone(),
         execution_mode: None,
         thinking_level: None,
         effort_level: None,
         recovered: false,
         usage: claude_response.usage.clone(),
+        working_dir: None,
+        git_branch: None,
+        retried_from: None,
+        compacted_runs: None,
     };
     // Note: Assistant message is stored in NDJSON, not sessions JSON.
     // Messages are loaded from NDJSON on demand via load_session_messages().
@@ -1286,9 +1408,12 @@ pub async fn send_chat_message(
         } else {
             Some(claude_session_id_for_log.as_str())
         };
-        if let Err(e) =
-            run_log_writer.complete(&assistant_msg_id, claude_sid, claude_response.usage)
-        {
+        if let Err(e) = run_log_writer.complete(
+            &assistant_msg_id,
+            claude_sid,
+            claude_response.usage,
+            claude_response.resolved_model.as_deref(),
+        ) {
             log::warn!("Failed to complete run log: {e}");
         }
     }
@@ -1427,6 +1552,33 @@ pub async fn cancel_chat_message(
     cancel_process(&app, &session_id, &worktree_id)
 }
 
+/// Cancel a specific run of a session by run ID
+/// Other runs in the same session are not affected
+///
+/// Only runs that have been spawned have an ID. Messages waiting behind a running
+/// run are held by the frontend queue (`messageQueues` in the chat store) and
+/// removed there with `removeQueuedMessage`; they never reach the backend.
+#[tauri::command]
+pub async fn cancel_run(
+    app: AppHandle,
+    session_id: String,
+    run_id: String,
+) -> Result<bool, String> {
+    log::trace!("Cancel run requested for session: {session_id}, run: {run_id}");
+    super::registry::cancel_run_process(&app, &session_id, &run_id)
+}
+
+/// Cancel all running Claude chat requests across every session
+/// Returns the number of sessions stopped (e.g., before updating the CLI)
+#[tauri::command]
+pub async fn cancel_all_sessions(app: AppHandle) -> Result<usize, String> {
+    log::trace!("Cancel all sessions requested");
+    // Waits for every process to exit, so keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || super::registry::cancel_all_processes(&app))
+        .await
+        .map_err(|e| format!("Cancel task failed: {e}"))
+}
+
 /// Check if any sessions have running Claude processes
 /// Used for quit confirmation dialog to prevent accidental closure during active sessions
 #[tauri::command]
@@ -1681,10 +1833,7 @@ pub async fn delete_pasted_image(app: AppHandle, path: String) -> Result<(), Str
 
     // Validate that the path is within allowed directories
     let path_str = file_path.to_string_lossy();
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(&app)?;
     let app_data_str = app_data_dir.to_string_lossy();
 
     // Check if path is in old .jean/images/ or new app data pasted-images/
@@ -1779,10 +1928,7 @@ pub async fn delete_pasted_text(app: AppHandle, path: String) -> Result<(), Stri
 
     // Validate that the path is within allowed directories
     let path_str = file_path.to_string_lossy();
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(&app)?;
     let app_data_str = app_data_dir.to_string_lossy();
 
     // Check if path is in old .jean/pastes/ or new app data pasted-texts/
@@ -1819,10 +1965,7 @@ pub async fn read_pasted_text(app: AppHandle, path: String) -> Result<ReadTextRe
 
     // Validate that the path is within allowed directories
     let path_str = file_path.to_string_lossy();
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(&app)?;
     let app_data_str = app_data_dir.to_string_lossy();
 
     // Check if path is in old .jean/pastes/ or new app data pasted-texts/
@@ -2665,10 +2808,7 @@ pub async fn get_session_debug_info(
     session_id: String,
 ) -> Result<SessionDebugInfo, String> {
     // Get app data directory
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(&app)?;
 
     let app_data_str = app_data_dir.to_str().unwrap_or("unknown").to_string();
 
@@ -2743,6 +2883,8 @@ pub async fn get_session_debug_info(
                     status: run.status.clone(),
                     user_message_preview: preview,
                     usage: run.usage.clone(),
+                    permission_mode: run.permission_mode.clone(),
+                    web_access: run.web_access,
                 });
             }
         }
@@ -2796,13 +2938,10 @@ pub async fn resume_session(
     session_id: String,
     worktree_id: String,
 ) -> Result<ResumeSessionResponse, String> {
-    use super::run_log::RunLogWriter;
-    use super::storage::save_metadata;
-
-    log::trace!("Attempting to resume session: {session_id}");
+    log::trace!("Attempting to resume session: {session_id} (worktree: {worktree_id})");
 
     // Load the metadata to find resumable runs
-    let mut metadata = match load_metadata(&app, &session_id)? {
+    let metadata = match load_metadata(&app, &session_id)? {
         Some(m) => m,
         None => {
             log::trace!("No metadata found for session: {session_id}");
@@ -2829,107 +2968,154 @@ pub async fn resume_session(
         });
     }
 
-    let run_count = resumable_runs.len();
     log::trace!(
         "Found {} resumable run(s) for session: {session_id}",
-        run_count
+        resumable_runs.len()
     );
 
-    // Get session directory for output files
-    let session_dir = get_session_dir(&app, &session_id)?;
-
     // Process each resumable run
+    let mut run_count = 0;
     for run in resumable_runs {
-        let run_id = run.run_id.clone();
-        let pid = run.pid.unwrap(); // Safe because we filtered for Some above
-        let output_file = session_dir.join(format!("{run_id}.jsonl"));
+        match resume_detached_run(&app, &session_id, &run.run_id) {
+            Ok(()) => run_count += 1,
+            Err(e) => log::warn!("Failed to resume run {}: {e}", run.run_id),
+        }
+    }
 
-        log::trace!(
-            "Resuming run: {run_id}, PID: {pid}, output: {:?}",
-            output_file
-        );
+    Ok(ResumeSessionResponse {
+        resumed: run_count > 0,
+        run_count,
+    })
+}
 
-        // Mark the run as Running again (from Resumable)
-        if let Some(metadata_run) = metadata.find_run_mut(&run_id) {
-            metadata_run.status = RunStatus::Running;
-        }
-        save_metadata(&app, &metadata)?;
-
-        // Clone values for the async task
-        let app_clone = app.clone();
-        let session_id_clone = session_id.clone();
-        let worktree_id_clone = worktree_id.clone();
-        let run_id_clone = run_id.clone();
-
-        // Spawn a task to tail the output file
-        tauri::async_runtime::spawn(async move {
-            log::trace!("Starting tail task for run: {run_id_clone}, session: {session_id_clone}");
-
-            // Tail the output file
-            let result = super::claude::tail_claude_output(
-                &app_clone,
-                &session_id_clone,
-                &worktree_id_clone,
-                &output_file,
-                pid,
-            );
+/// Re-attach a tailer to a resumable detached run so its events flow again
+///
+/// Marks the run as Running, re-registers its PID so it can be cancelled, and
+/// tails the output file from the start on a background task. Claude writes
+/// whole messages per line, so re-reading already-seen output emits each one
+/// once and the frontend replaces the partial streaming state.
+pub fn resume_detached_run(app: &AppHandle, session_id: &str, run_id: &str) -> Result<(), String> {
+    use super::run_log::RunLogWriter;
 
-            match result {
-                Ok(response) => {
-                    log::trace!(
-                        "Resume completed for run: {run_id_clone}, session_id: {:?}",
-                        response.session_id
-                    );
+    let metadata = load_metadata(app, session_id)?
+        .ok_or_else(|| format!("No metadata found for session: {session_id}"))?;
+    let worktree_id = metadata.worktree_id.clone();
 
-                    // Create a RunLogWriter to update the manifest
-                    if let Ok(mut writer) =
-                        RunLogWriter::resume(&app_clone, &session_id_clone, &run_id_clone)
-                    {
-                        // Mark as completed
-                        let assistant_message_id = uuid::Uuid::new_v4().to_string();
-                        let claude_session_id = if response.session_id.is_empty() {
-                            None
-                        } else {
-                            Some(response.session_id.as_str())
-                        };
-                        if let Err(e) = writer.complete(
-                            &assistant_message_id,
-                            claude_session_id,
-                            response.usage.clone(),
-                        ) {
-                            log::error!("Failed to mark run as completed: {e}");
-                        }
+    let run = metadata
+        .find_run(run_id)
+        .ok_or_else(|| format!("Run not found: {run_id}"))?;
+    if run.status != RunStatus::Resumable {
+        return Err(format!("Run {run_id} is not resumable ({:?})", run.status));
+    }
+    let pid = run
+        .pid
+        .ok_or_else(|| format!("Run {run_id} has no PID to resume"))?;
 
-                        // Clean up input file if it exists
-                        if let Err(e) = super::run_log::delete_input_file(
-                            &app_clone,
-                            &session_id_clone,
-                            &run_id_clone,
-                        ) {
-                            log::trace!("Could not delete input file (may not exist): {e}");
-                        }
+    // Only one tailer per session: another run may already be streaming
+    super::registry::ensure_session_idle(session_id)?;
+
+    let output_file = run_log::get_run_log_path(app, session_id, run_id)?;
+
+    log::trace!(
+        "Resuming run: {run_id}, PID: {pid}, output: {:?}",
+        output_file
+    );
+
+    // Register so cancel_process can find it and the tailer doesn't treat it as cancelled
+    let registration = super::registry::ProcessRegistration::register(session_id, pid)?;
+
+    // Mark the run as Running again (from Resumable), re-checked under the lock
+    super::storage::with_metadata_mut(
+        app,
+        session_id,
+        &worktree_id,
+        &metadata.name,
+        metadata.order,
+        |current| match current.find_run_mut(run_id) {
+            Some(run) if run.status == RunStatus::Resumable => {
+                run.status = RunStatus::Running;
+                Ok(())
+            }
+            Some(run) => Err(format!("Run {run_id} is not resumable ({:?})", run.status)),
+            None => Err(format!("Run not found: {run_id}")),
+        },
+    )?;
+
+    // Clone values for the async task
+    let app_clone = app.clone();
+    let session_id_clone = session_id.to_string();
+    let run_id_clone = run_id.to_string();
+
+    // Spawn a task to tail the output file
+    tauri::async_runtime::spawn(async move {
+        log::trace!("Starting tail task for run: {run_id_clone}, session: {session_id_clone}");
+
+        let timeouts = super::claude::TailTimeouts::load(&app_clone).await;
+
+        // Tail the output file
+        let result = super::claude::tail_claude_output(
+            &app_clone,
+            &session_id_clone,
+            &worktree_id,
+            &output_file,
+            pid,
+            timeouts,
+        );
+        drop(registration);
+
+        match result {
+            Ok(response) => {
+                log::trace!(
+                    "Resume completed for run: {run_id_clone}, session_id: {:?}",
+                    response.session_id
+                );
+
+                // Create a RunLogWriter to update the manifest
+                if let Ok(mut writer) =
+                    RunLogWriter::resume(&app_clone, &session_id_clone, &run_id_clone)
+                {
+                    // Mark as completed
+                    let assistant_message_id = uuid::Uuid::new_v4().to_string();
+                    let claude_session_id = if response.session_id.is_empty() {
+                        None
+                    } else {
+                        Some(response.session_id.as_str())
+                    };
+                    if let Err(e) = writer.complete(
+                        &assistant_message_id,
+                        claude_session_id,
+                        response.usage.clone(),
+                        response.resolved_model.as_deref(),
+                    ) {
+                        log::error!("Failed to mark run as completed: {e}");
+                    }
+
+                    // Clean up input file if it exists
+                    if let Err(e) = super::run_log::delete_input_file(
+                        &app_clone,
+                        &session_id_clone,
+                        &run_id_clone,
+                    ) {
+                        log::trace!("Could not delete input file (may not exist): {e}");
                     }
                 }
-                Err(e) => {
-                    log::error!("Resume failed for run: {run_id_clone}, error: {e}");
+            }
+            Err(e) => {
+                log::error!("Resume failed for run: {run_id_clone}, error: {e}");
 
-                    // Mark as crashed
-                    if let Ok(mut writer) =
-                        RunLogWriter::resume(&app_clone, &session_id_clone, &run_id_clone)
-                    {
-                        if let Err(e) = writer.crash() {
-                            log::error!("Failed to mark run as crashed: {e}");
-                        }
+                // Mark as crashed
+                if let Ok(mut writer) =
+                    RunLogWriter::resume(&app_clone, &session_id_clone, &run_id_clone)
+                {
+                    if let Err(e) = writer.crash() {
+                        log::error!("Failed to mark run as crashed: {e}");
                     }
                 }
             }
-        });
-    }
+        }
+    });
 
-    Ok(ResumeSessionResponse {
-        resumed: true,
-        run_count,
-    })
+    Ok(())
 }
 
 /// Check for resumable sessions on startup and return their info.
@@ -2952,6 +3138,296 @@ pub async fn check_resumable_sessions(
     Ok(resumable)
 }
 
+/// Retry a crashed run by re-sending its user message as a fresh run.
+///
+/// Reuses the crashed run's model, execution mode, thinking/effort level,
+/// labels and recorded send options, and resumes the Claude session it
+/// captured (if any) without changing the session's own. The crashed run stays
+/// in history; the new run records it in `retried_from`. Returns once the retry
+/// is dispatched; progress and failures stream via chat:* events.
+#[tauri::command]
+pub async fn retry_run(app: AppHandle, session_id: String, run_id: String) -> Result<(), String> {
+    log::trace!("Retrying run {run_id} for session: {session_id}");
+
+    let metadata = load_metadata(&app, &session_id)?
+        .ok_or_else(|| format!("Session not found: {session_id}"))?;
+    let run = metadata
+        .find_run(&run_id)
+        .cloned()
+        .ok_or_else(|| format!("Run not found: {run_id}"))?;
+
+    if run.status != RunStatus::Crashed {
+        return Err(format!(
+            "Only crashed runs can be retried (run {run_id} is {:?})",
+            run.status
+        ));
+    }
+    if super::registry::is_process_running(&session_id) {
+        return Err(format!(
+            "Session {session_id} already has a running process"
+        ));
+    }
+
+    let worktree_id = metadata.worktree_id.clone();
+    let worktree_path = load_projects_data(&app)
+        .ok()
+        .and_then(|data| data.find_worktree(&worktree_id).map(|w| w.path.clone()))
+        .or_else(|| run.working_dir.clone())
+        .ok_or_else(|| format!("Worktree not found for session: {session_id}"))?;
+
+    let request = retry_request(&run);
+
+    tauri::async_runtime::spawn(async move {
+        let options = request.options;
+        if let Err(e) = send_chat_message_inner(
+            app.clone(),
+            session_id.clone(),
+            worktree_id.clone(),
+            worktree_path,
+            request.message,
+            request.model,
+            request.execution_mode,
+            request.thinking_level,
+            request.effort_level,
+            options.disable_thinking_for_mode,
+            options.parallel_execution_prompt,
+            options.ai_language,
+            options.allowed_tools,
+            options.mcp_config,
+            options.chrome_enabled,
+            options.custom_profile_name,
+            request.labels,
+            request.resume_from_run_id,
+            Some(run_id),
+        )
+        .await
+        {
+            log::error!("Retry of crashed run failed: {e}");
+            super::claude::emit_chat_error(&app, &session_id, &worktree_id, &e);
+        }
+    });
+
+    Ok(())
+}
+
+/// What `retry_run` sends to re-run a crashed run
+#[derive(Debug, PartialEq)]
+struct RetryRequest {
+    message: String,
+    model: Option<String>,
+    execution_mode: Option<String>,
+    thinking_level: Option<ThinkingLevel>,
+    effort_level: Option<EffortLevel>,
+    labels: Option<Vec<String>>,
+    options: RunRequestOptions,
+    /// Set when the crashed run captured a Claude session: the retry continues
+    /// that conversation without changing the session's stored one
+    resume_from_run_id: Option<String>,
+}
+
+/// Rebuild the send arguments of a crashed run from its RunEntry
+fn retry_request(run: &RunEntry) -> RetryRequest {
+    let parse = |value: &Option<String>| {
+        value
+            .as_deref()
+            .and_then(|v| serde_json::from_value(serde_json::Value::String(v.to_string())).ok())
+    };
+    RetryRequest {
+        message: run.user_message.clone(),
+        model: run.model.clone(),
+        execution_mode: run.execution_mode.clone(),
+        thinking_level: parse(&run.thinking_level),
+        effort_level: parse(&run.effort_level),
+        labels: (!run.labels.is_empty()).then(|| run.labels.clone()),
+        options: run.request_options.clone().unwrap_or_default(),
+        resume_from_run_id: run.claude_session_id.is_some().then(|| run.run_id.clone()),
+    }
+}
+
+/// Delete old run logs by age and/or total size across all sessions.
+///
+/// Active (running or resumable) runs are never pruned.
+#[tauri::command]
+pub async fn prune_run_logs(
+    app: AppHandle,
+    max_age_days: Option<u64>,
+    max_total_bytes: Option<u64>,
+) -> Result<super::run_log::PruneReport, String> {
+    log::trace!(
+        "Pruning run logs (max_age_days: {max_age_days:?}, max_total_bytes: {max_total_bytes:?})"
+    );
+    super::run_log::prune_run_logs(&app, max_age_days, max_total_bytes)
+}
+
+/// On-disk size of each session as `(session_id, bytes)`, largest first
+#[tauri::command]
+pub async fn session_disk_usage(app: AppHandle) -> Result<Vec<(String, u64)>, String> {
+    log::trace!("Computing session disk usage");
+    super::storage::session_disk_usage(&app)
+}
+
+/// Search all sessions' run logs for text (case-insensitive).
+///
+/// Matches user messages, assistant text, thinking, and tool inputs/outputs.
+/// `max_hits_per_session` defaults to 20.
+#[tauri::command]
+pub async fn search_sessions(
+    app: AppHandle,
+    query: String,
+    max_hits_per_session: Option<usize>,
+) -> Result<Vec<super::run_log::SearchHit>, String> {
+    log::trace!("Searching sessions for: {query}");
+    super::run_log::search_sessions(&app, &query, max_hits_per_session.unwrap_or(20).max(1))
+}
+
+/// Rebuild the search index over all finished runs.
+///
+/// Resumes a rebuild that was interrupted; otherwise starts from scratch.
+/// Runs are also indexed automatically as they complete.
+#[tauri::command]
+pub async fn rebuild_search_index(
+    app: AppHandle,
+) -> Result<super::search_index::SearchIndexStats, String> {
+    log::trace!("Rebuilding search index");
+    super::search_index::rebuild_search_index(&app)
+}
+
+/// List runs tagged with a label across all sessions (newest first)
+#[tauri::command]
+pub async fn list_runs_by_label(
+    app: AppHandle,
+    label: String,
+) -> Result<Vec<super::run_log::LabeledRun>, String> {
+    log::trace!("Listing runs with label: {label}");
+    super::run_log::find_runs_by_label(&app, &label)
+}
+
+/// Export a session (metadata plus raw run logs) as a JSON document for backup.
+///
+/// Runs whose log is missing or unreadable are skipped and listed in the report.
+#[tauri::command]
+pub async fn export_session_json(
+    app: AppHandle,
+    session_id: String,
+) -> Result<super::run_log::ExportReport, String> {
+    log::trace!("Exporting session as JSON: {session_id}");
+    super::run_log::export_session_json(&app, &session_id)
+}
+
+/// Import a session exported by `export_session_json`; returns the new session id.
+#[tauri::command]
+pub async fn import_session_json(app: AppHandle, json: String) -> Result<String, String> {
+    log::trace!("Importing session from JSON ({} bytes)", json.len());
+    super::run_log::import_session_json(&app, &json)
+}
+
+/// Replay a finished run's streaming events (ending with `chat:done`).
+/// `speed` 1.0 is immediate; higher values (up to 20) replay more slowly.
+#[tauri::command]
+pub async fn replay_run(
+    app: AppHandle,
+    session_id: String,
+    run_id: String,
+    speed: f32,
+) -> Result<(), String> {
+    log::trace!("Replaying run {run_id} of session {session_id} at speed {speed}");
+    tauri::async_runtime::spawn_blocking(move || {
+        super::run_log::replay_run(&app, &session_id, &run_id, speed)
+    })
+    .await
+    .map_err(|e| format!("Replay task failed: {e}"))?
+}
+
+/// Rebuild a session's metadata from its run logs if it's missing or corrupt.
+///
+/// Healthy sessions are left untouched; the report says whether a rebuild
+/// happened and which runs were recovered.
+#[tauri::command]
+pub async fn repair_session(
+    app: AppHandle,
+    session_id: String,
+) -> Result<super::run_log::RepairReport, String> {
+    log::trace!("Repairing session: {session_id}");
+    super::run_log::repair_session(&app, &session_id)
+}
+
+/// Compact a session's history: all but the newest `keep_last` runs are
+/// replaced by a single summary run and their log files deleted.
+///
+/// The report lists runs left out of the summary because their log was
+/// missing or unreadable.
+#[tauri::command]
+pub async fn compact_session(
+    app: AppHandle,
+    session_id: String,
+    keep_last: usize,
+) -> Result<super::run_log::CompactReport, String> {
+    log::trace!("Compacting session {session_id} (keeping last {keep_last} runs)");
+    super::run_log::compact_session(&app, &session_id, keep_last)
+}
+
+/// List all sessions with summary stats (most recently active first).
+///
+/// Only reads session metadata, so it stays cheap with many large sessions.
+#[tauri::command]
+pub async fn list_sessions_summary(
+    app: AppHandle,
+) -> Result<Vec<super::run_log::SessionSummary>, String> {
+    log::trace!("Listing session summaries");
+    super::run_log::list_session_summaries(&app)
+}
+
+/// Find runs with identical content across sessions (e.g., after re-importing).
+///
+/// Returns groups of duplicate runs keyed by content hash, oldest run first.
+#[tauri::command]
+pub async fn find_duplicate_runs(
+    app: AppHandle,
+) -> Result<Vec<super::run_log::DuplicateRunGroup>, String> {
+    log::trace!("Finding duplicate runs");
+    super::run_log::find_duplicate_runs(&app)
+}
+
+/// Read a run's raw stream-json events and its `_run_meta` header (for debugging).
+///
+/// `limit` caps the number of events returned (first N in file order).
+#[tauri::command]
+pub async fn get_run_raw_events(
+    app: AppHandle,
+    session_id: String,
+    run_id: String,
+    limit: Option<usize>,
+) -> Result<super::run_log::RunRawEvents, String> {
+    log::trace!("Reading raw events for run {run_id} in session: {session_id}");
+    super::run_log::read_run_raw_events(&app, &session_id, &run_id, limit)
+}
+
+/// Get the Claude session ids recorded for a session and each of its runs.
+///
+/// Lets users resume a conversation with the Claude CLI directly, and helps
+/// debug which run established which Claude session.
+#[tauri::command]
+pub async fn get_session_agent_ids(
+    app: AppHandle,
+    session_id: String,
+) -> Result<super::run_log::AgentIds, String> {
+    log::trace!("Getting agent ids for session: {session_id}");
+    super::run_log::get_session_agent_ids(&app, &session_id)
+}
+
+/// Preview what startup run recovery would do, without changing any metadata.
+///
+/// Returns every incomplete run with whether it would be marked resumable
+/// (process alive) or crashed (process gone). Useful for diagnosing sessions
+/// that unexpectedly come back as crashed.
+#[tauri::command]
+pub async fn preview_run_recovery(
+    app: AppHandle,
+) -> Result<Vec<super::run_log::RecoveredRun>, String> {
+    log::trace!("Previewing run recovery");
+    super::run_log::preview_incomplete_runs(&app)
+}
+
 // ============================================================================
 // Session Digest Commands (for context recall after switching)
 // ============================================================================
@@ -3380,6 +3856,60 @@ pub async fn check_mcp_health(app: AppHandle) -> Result<McpHealthResult, String>
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_retry_request_reuses_run_settings() {
+        let mut run: RunEntry = serde_json::from_value(serde_json::json!({
+            "run_id": "run-1",
+            "user_message_id": "msg-1",
+            "user_message": "Fix the build",
+            "model": "opus",
+            "execution_mode": "build",
+            "thinking_level": "megathink",
+            "effort_level": "max",
+            "labels": ["ticket-1"],
+            "started_at": 1,
+            "status": "crashed",
+            "claude_session_id": "claude-1",
+            "request_options": {
+                "ai_language": "French",
+                "allowed_tools": ["Read"],
+                "mcp_config": "{\"mcpServers\":{}}",
+                "chrome_enabled": true,
+                "custom_profile_name": "OpenRouter",
+                "disable_thinking_for_mode": true,
+                "parallel_execution_prompt": "Use sub-agents"
+            }
+        }))
+        .unwrap();
+
+        let request = retry_request(&run);
+        assert_eq!(request.message, "Fix the build");
+        assert_eq!(request.model.as_deref(), Some("opus"));
+        assert_eq!(request.execution_mode.as_deref(), Some("build"));
+        assert_eq!(request.thinking_level, Some(ThinkingLevel::Megathink));
+        assert_eq!(request.effort_level, Some(EffortLevel::Max));
+        assert_eq!(request.labels, Some(vec!["ticket-1".to_string()]));
+        assert_eq!(request.options.ai_language.as_deref(), Some("French"));
+        assert_eq!(
+            request.options.allowed_tools,
+            Some(vec!["Read".to_string()])
+        );
+        assert_eq!(
+            request.options.custom_profile_name.as_deref(),
+            Some("OpenRouter")
+        );
+        assert_eq!(request.options.chrome_enabled, Some(true));
+        assert_eq!(request.options.disable_thinking_for_mode, Some(true));
+        assert_eq!(request.resume_from_run_id.as_deref(), Some("run-1"));
+
+        // Older runs without recorded options and no captured Claude session
+        run.request_options = None;
+        run.claude_session_id = None;
+        let request = retry_request(&run);
+        assert_eq!(request.options, RunRequestOptions::default());
+        assert_eq!(request.resume_from_run_id, None);
+    }
+
     #[test]
     fn test_extract_text_from_stream_json_text_only() {
         let output =
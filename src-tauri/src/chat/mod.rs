@@ -4,9 +4,12 @@ pub mod detached;
 mod naming;
 pub mod registry;
 pub mod run_log;
+pub mod run_stream;
+pub mod search_index;
 pub mod storage;
 pub mod tail;
 pub mod types;
 
+pub use claude::parse_usage;
 pub use commands::*;
 pub use storage::{preserve_base_sessions, restore_base_sessions, with_sessions_mut};
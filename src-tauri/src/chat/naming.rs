@@ -14,7 +14,7 @@ use serde::{Deserialize, Serialize};
 use std::io::Write;
 use std::path::PathBuf;
 use std::process::Stdio;
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
 /// Request for combined naming (session + branch)
 #[derive(Debug, Clone)]
@@ -371,7 +371,7 @@ fn generate_names(app: &AppHandle, request: &NamingRequest) -> Result<NamingOutp
         // Add directories for Claude to read attachments
         // In dev mode: full directory access (useful for debugging)
         // In prod mode: only specific directories (security)
-        if let Ok(app_data_dir) = app.path().app_data_dir() {
+        if let Ok(app_data_dir) = crate::platform::resolve_app_data_dir(app) {
             if cfg!(debug_assertions) {
                 cmd.arg("--add-dir").arg(&app_data_dir);
                 log::trace!("Added full app data directory to naming scope: {app_data_dir:?}");
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 use once_cell::sync::Lazy;
 use tauri::AppHandle;
@@ -7,6 +9,7 @@ use tauri::AppHandle;
 use super::claude::CancelledEvent;
 use super::run_log;
 use super::storage;
+use super::types::RunStatus;
 use crate::http_server::EmitExt;
 
 /// Global registry of running Claude process PIDs by session_id
@@ -26,7 +29,37 @@ pub fn register_process(session_id: String, pid: u32) {
     registry.insert(session_id, pid);
 }
 
-/// Remove a process from the registry (called after completion or cancellation)
+/// Error for a session that already has a running process
+fn session_running_error(session_id: &str) -> String {
+    format!("Session {session_id} is already running. Wait for it to finish or cancel it first.")
+}
+
+/// Fail if a session already has a running process (checked before spawning)
+pub fn ensure_session_idle(session_id: &str) -> Result<(), String> {
+    if is_process_running(session_id) {
+        return Err(session_running_error(session_id));
+    }
+    Ok(())
+}
+
+/// Register a process unless the session already has one
+/// The check and insert happen under one lock, so two racing spawns can't both register
+pub fn try_register_process(session_id: String, pid: u32) -> Result<(), String> {
+    let mut registry = PROCESS_REGISTRY.lock().unwrap();
+    if let Some(existing) = registry.get(&session_id) {
+        log::warn!(
+            "Refusing to register pid={pid}: session {session_id} already has pid={existing}"
+        );
+        return Err(session_running_error(&session_id));
+    }
+    log::trace!("Registering Claude process pid={pid} for session: {session_id}");
+    registry.insert(session_id, pid);
+    Ok(())
+}
+
+/// Remove a process from the registry regardless of which process it holds
+/// (spawn paths use [`ProcessRegistration`], which checks the PID)
+#[allow(dead_code)]
 pub fn unregister_process(session_id: &str) {
     let mut registry = PROCESS_REGISTRY.lock().unwrap();
     if let Some(pid) = registry.remove(session_id) {
@@ -34,6 +67,41 @@ pub fn unregister_process(session_id: &str) {
     }
 }
 
+/// Registry entry that is removed when dropped, so early returns and panics
+/// between registering and normal cleanup can't leave a stale entry behind.
+///
+/// Only removes the entry if it still holds this process: after a cancel the
+/// session may already have a new run registered.
+pub struct ProcessRegistration {
+    session_id: String,
+    pid: u32,
+}
+
+impl ProcessRegistration {
+    /// Register `pid` for `session_id` (see [`try_register_process`])
+    pub fn register(session_id: &str, pid: u32) -> Result<Self, String> {
+        try_register_process(session_id.to_string(), pid)?;
+        Ok(Self {
+            session_id: session_id.to_string(),
+            pid,
+        })
+    }
+}
+
+impl Drop for ProcessRegistration {
+    fn drop(&mut self) {
+        let mut registry = PROCESS_REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+        if registry.get(&self.session_id) == Some(&self.pid) {
+            registry.remove(&self.session_id);
+            log::trace!(
+                "Unregistered Claude process {} for session: {}",
+                self.pid,
+                self.session_id
+            );
+        }
+    }
+}
+
 /// Check if a session has a running process
 #[allow(dead_code)]
 pub fn is_process_running(session_id: &str) -> bool {
@@ -52,11 +120,24 @@ pub fn get_running_sessions() -> Vec<String> {
 /// spawned by Claude CLI are also terminated. This is safe because:
 /// 1. Claude is spawned with process_group(0), creating a NEW group separate from Jean
 /// 2. We guard against dangerous PIDs (0, 1) that could affect system processes
+///
+/// The process is terminated in the background; use `cancel_process_and_wait`
+/// when the caller must not continue until it has exited.
 pub fn cancel_process(
     app: &AppHandle,
     session_id: &str,
     worktree_id: &str,
 ) -> Result<bool, String> {
+    Ok(cancel_process_and_wait(app, session_id, worktree_id)?.is_some())
+}
+
+/// Cancel a session's process like `cancel_process`, returning a handle to join
+/// once the process has exited (None if no process was running)
+fn cancel_process_and_wait(
+    app: &AppHandle,
+    session_id: &str,
+    worktree_id: &str,
+) -> Result<Option<JoinHandle<()>>, String> {
     let mut registry = PROCESS_REGISTRY.lock().unwrap();
     log::trace!("cancel_process called for session: {session_id}");
     log::trace!("Registry state: {:?}", registry.iter().collect::<Vec<_>>());
@@ -70,32 +151,7 @@ pub fn cancel_process(
 
         log::trace!("Cancelling Claude process group {pid} for session: {session_id}");
 
-        // Kill the entire process tree to ensure child processes are also terminated
-        // Uses platform-specific implementation from the platform module
-        use crate::platform::{is_process_alive, kill_process, kill_process_tree};
-
-        log::trace!("Killing process tree for pid={pid}");
-
-        // First, check if the process exists
-        if !is_process_alive(pid) {
-            log::warn!("Process {pid} check failed (may have exited)");
-        } else {
-            log::trace!("Process {pid} exists, proceeding with kill");
-        }
-
-        // Kill the process tree (process group on Unix, taskkill /T on Windows)
-        if let Err(e) = kill_process_tree(pid) {
-            log::error!("Failed to kill process tree for pid={pid}: {e}");
-        } else {
-            log::trace!("Successfully sent kill to process tree pid={pid}");
-        }
-
-        // Also try killing the process directly as fallback
-        if let Err(e) = kill_process(pid) {
-            log::trace!("Direct kill of pid={pid} failed (may be redundant): {e}");
-        } else {
-            log::trace!("Direct kill of pid={pid} succeeded");
-        }
+        let terminated = terminate_process_gracefully(pid);
 
         // Update manifest SYNCHRONOUSLY before emitting event
         // This ensures any frontend refetch sees "Cancelled" status, not "Running"
@@ -108,37 +164,214 @@ pub fn cancel_process(
             session_id: session_id.to_string(),
             worktree_id: worktree_id.to_string(),
             undo_send: false, // Process was running, may have partial content
+            run_id: None,
         };
         if let Err(e) = app.emit_all("chat:cancelled", &event) {
             log::error!("Failed to emit chat:cancelled event: {e}");
         }
 
-        Ok(true)
+        Ok(Some(terminated))
     } else {
         log::trace!("No running process found for session: {session_id}");
-        Ok(false)
+        Ok(None)
+    }
+}
+
+/// Cancel a specific run of a session, leaving other runs untouched
+/// Returns true if the run was cancelled, false if it had no process to stop
+///
+/// Kills the run's process (see `pid_to_cancel`), then marks only that run as
+/// cancelled. Queued messages live in the frontend queue and are never spawned
+/// until the running run finishes, so there is nothing to dequeue here.
+pub fn cancel_run_process(app: &AppHandle, session_id: &str, run_id: &str) -> Result<bool, String> {
+    let metadata = storage::load_metadata(app, session_id)?
+        .ok_or_else(|| format!("Session not found: {session_id}"))?;
+    let run = metadata
+        .find_run(run_id)
+        .ok_or_else(|| format!("Run not found: {run_id}"))?;
+
+    if !matches!(run.status, RunStatus::Running | RunStatus::Resumable) {
+        log::trace!(
+            "Run {run_id} already finished ({:?}), nothing to cancel",
+            run.status
+        );
+        return Ok(false);
+    }
+
+    // Only take the session's registry entry if it belongs to this run
+    let pid = {
+        let mut registry = PROCESS_REGISTRY.lock().unwrap();
+        let registered = registry.get(session_id).copied();
+        let pid = pid_to_cancel(registered, run.pid, crate::platform::is_process_alive);
+        if pid.is_some() && registered.is_some() {
+            registry.remove(session_id);
+        }
+        pid
+    };
+    let Some(pid) = pid else {
+        log::trace!("Run {run_id} has no live process to cancel for session: {session_id}");
+        return Ok(false);
+    };
+
+    // SAFETY: Never kill PID 0 (would kill our own process group) or PID 1 (init/launchd)
+    if pid == 0 || pid == 1 {
+        log::error!("Refusing to kill dangerous PID: {pid}");
+        return Err(format!("Invalid PID: {pid}"));
+    }
+    log::trace!("Cancelling run {run_id} (pid={pid}) for session: {session_id}");
+    terminate_process_gracefully(pid);
+
+    // Update metadata SYNCHRONOUSLY before emitting event (same as cancel_process)
+    run_log::mark_run_cancelled(app, session_id, run_id)?;
+
+    let event = CancelledEvent {
+        session_id: session_id.to_string(),
+        worktree_id: metadata.worktree_id.clone(),
+        undo_send: false,
+        run_id: Some(run_id.to_string()),
+    };
+    if let Err(e) = app.emit_all("chat:cancelled", &event) {
+        log::error!("Failed to emit chat:cancelled event: {e}");
+    }
+
+    Ok(true)
+}
+
+/// The pid to kill when cancelling a run.
+///
+/// While the session has a registered process, only that process is killed, and
+/// only if it is the run's recorded pid. A run that is not registered (e.g. a
+/// detached, resumable run after a restart) falls back to its recorded pid if
+/// that process is still alive.
+fn pid_to_cancel(
+    registered: Option<u32>,
+    recorded: Option<u32>,
+    is_alive: impl Fn(u32) -> bool,
+) -> Option<u32> {
+    match (registered, recorded) {
+        (Some(registered), Some(recorded)) if registered == recorded => Some(recorded),
+        (None, Some(recorded)) if is_alive(recorded) => Some(recorded),
+        _ => None,
+    }
+}
+
+/// How long a cancelled process gets to exit after an interrupt before it is killed
+pub const CANCEL_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+/// How often to check whether an interrupted process has exited
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Interrupt a Claude process, then kill its process tree if it outlives the grace period
+///
+/// SIGINT (CTRL_BREAK on Windows) lets the CLI flush its final output to the run log.
+/// Runs on a background thread so cancellation returns (and emits chat:cancelled)
+/// immediately; the tailer has already stopped because the registry entry is gone.
+/// Join the returned handle to wait until the process is gone.
+fn terminate_process_gracefully(pid: u32) -> JoinHandle<()> {
+    use crate::platform::{interrupt_process_tree, is_process_alive};
+
+    std::thread::spawn(move || {
+        if let Err(e) = interrupt_process_tree(pid) {
+            log::trace!("Interrupt of pid={pid} failed, killing immediately: {e}");
+            terminate_process(pid);
+            return;
+        }
+
+        if wait_for_exit(
+            pid,
+            CANCEL_GRACE_PERIOD,
+            CANCEL_POLL_INTERVAL,
+            is_process_alive,
+        ) {
+            log::trace!("Process {pid} exited after interrupt");
+        } else {
+            log::trace!("Process {pid} still alive after {CANCEL_GRACE_PERIOD:?}, killing");
+            terminate_process(pid);
+        }
+    })
+}
+
+/// Wait for the processes being terminated by `handles` to exit
+fn join_terminations(handles: Vec<JoinHandle<()>>) {
+    for handle in handles {
+        if handle.join().is_err() {
+            log::error!("Process termination thread panicked");
+        }
+    }
+}
+
+/// Poll until `is_alive` reports the process gone, up to `grace`.
+/// Returns true if the process exited in time.
+fn wait_for_exit(
+    pid: u32,
+    grace: Duration,
+    poll_interval: Duration,
+    is_alive: impl Fn(u32) -> bool,
+) -> bool {
+    let deadline = Instant::now() + grace;
+    loop {
+        if !is_alive(pid) {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(poll_interval);
+    }
+}
+
+/// Kill a Claude process and its whole process tree
+fn terminate_process(pid: u32) {
+    // Kill the entire process tree to ensure child processes are also terminated
+    // Uses platform-specific implementation from the platform module
+    use crate::platform::{is_process_alive, kill_process, kill_process_tree};
+
+    log::trace!("Killing process tree for pid={pid}");
+
+    // First, check if the process exists
+    if !is_process_alive(pid) {
+        log::warn!("Process {pid} check failed (may have exited)");
+    } else {
+        log::trace!("Process {pid} exists, proceeding with kill");
+    }
+
+    // Kill the process tree (process group on Unix, taskkill /T on Windows)
+    if let Err(e) = kill_process_tree(pid) {
+        log::error!("Failed to kill process tree for pid={pid}: {e}");
+    } else {
+        log::trace!("Successfully sent kill to process tree pid={pid}");
+    }
+
+    // Also try killing the process directly as fallback
+    if let Err(e) = kill_process(pid) {
+        log::trace!("Direct kill of pid={pid} failed (may be redundant): {e}");
+    } else {
+        log::trace!("Direct kill of pid={pid} succeeded");
     }
 }
 
 /// Cancel all running Claude processes for a given worktree
-/// Called before worktree deletion to clean up orphaned processes
+/// Called before worktree deletion to clean up orphaned processes; returns once
+/// they have exited so none is left running in the directory being removed
 pub fn cancel_processes_for_worktree(app: &AppHandle, worktree_id: &str) {
     log::trace!("Cancelling all Claude processes for worktree: {worktree_id}");
 
     // Load sessions for this worktree from app data directory
     match storage::load_sessions_by_id(app, worktree_id) {
         Ok(sessions) => {
-            let mut cancelled_count = 0;
+            let mut terminations = Vec::new();
             for session in &sessions.sessions {
-                if let Ok(true) = cancel_process(app, &session.id, worktree_id) {
-                    cancelled_count += 1;
+                if let Ok(Some(handle)) = cancel_process_and_wait(app, &session.id, worktree_id) {
+                    terminations.push(handle);
                 }
             }
-            if cancelled_count > 0 {
+            if !terminations.is_empty() {
                 log::trace!(
-                    "Cancelled {cancelled_count} Claude process(es) for worktree: {worktree_id}"
+                    "Cancelled {} Claude process(es) for worktree: {worktree_id}",
+                    terminations.len()
                 );
             }
+            join_terminations(terminations);
         }
         Err(e) => {
             // Not an error - worktree may have no sessions yet
@@ -146,3 +379,134 @@ pub fn cancel_processes_for_worktree(app: &AppHandle, worktree_id: &str) {
         }
     }
 }
+
+/// Cancel every running Claude process across all sessions
+/// Returns the number of sessions whose process was stopped, once they have all exited
+pub fn cancel_all_processes(app: &AppHandle) -> usize {
+    let sessions = get_running_sessions();
+    log::trace!(
+        "Cancelling all {} running Claude process(es)",
+        sessions.len()
+    );
+
+    let mut terminations = Vec::new();
+    for session_id in sessions {
+        // Registry only tracks pids; worktree_id comes from session metadata
+        let worktree_id = storage::load_metadata(app, &session_id)
+            .ok()
+            .flatten()
+            .map(|m| m.worktree_id)
+            .unwrap_or_default();
+        match cancel_process_and_wait(app, &session_id, &worktree_id) {
+            Ok(Some(handle)) => terminations.push(handle),
+            Ok(None) => {}
+            Err(e) => log::warn!("Failed to cancel process for session {session_id}: {e}"),
+        }
+    }
+    let cancelled_count = terminations.len();
+    join_terminations(terminations);
+    cancelled_count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_second_spawn_for_running_session_is_refused() {
+        let session_id = "test-registry-double-spawn";
+        register_process(session_id.to_string(), 4242);
+
+        assert!(ensure_session_idle(session_id)
+            .unwrap_err()
+            .contains("already running"));
+        assert!(try_register_process(session_id.to_string(), 4343).is_err());
+        assert_eq!(
+            PROCESS_REGISTRY.lock().unwrap().get(session_id).copied(),
+            Some(4242)
+        );
+
+        unregister_process(session_id);
+        assert!(ensure_session_idle(session_id).is_ok());
+        assert!(try_register_process(session_id.to_string(), 4343).is_ok());
+        unregister_process(session_id);
+    }
+
+    #[test]
+    fn test_pid_to_cancel_requires_recorded_and_registered_pid() {
+        let alive = |_: u32| true;
+        assert_eq!(pid_to_cancel(Some(4242), Some(4242), alive), Some(4242));
+        // Another run of the session owns the registry entry
+        assert_eq!(pid_to_cancel(Some(4343), Some(4242), alive), None);
+        // Not recorded yet
+        assert_eq!(pid_to_cancel(Some(4242), None, alive), None);
+        assert_eq!(pid_to_cancel(None, None, alive), None);
+    }
+
+    #[test]
+    fn test_pid_to_cancel_falls_back_to_live_resumable_pid() {
+        // Resumable run after a restart: not registered, but its process survived
+        assert_eq!(
+            pid_to_cancel(None, Some(4242), |pid| pid == 4242),
+            Some(4242)
+        );
+        // Its process already exited, so there is nothing to cancel
+        assert_eq!(pid_to_cancel(None, Some(4242), |_| false), None);
+    }
+
+    #[test]
+    fn test_registration_is_cleaned_up_on_early_return() {
+        let session_id = "test-registry-guard-early-return";
+        let setup = || -> Result<(), String> {
+            let _registration = ProcessRegistration::register(session_id, 5151)?;
+            assert!(is_process_running(session_id));
+            // e.g. opening the run log fails after registering
+            Err("Failed to open run log".to_string())
+        };
+
+        assert!(setup().is_err());
+        assert!(!is_process_running(session_id));
+    }
+
+    #[test]
+    fn test_registration_leaves_newer_process_registered() {
+        let session_id = "test-registry-guard-newer-process";
+        let registration = ProcessRegistration::register(session_id, 6161).unwrap();
+
+        // Cancelled and replaced by a new run before the old tailer finished
+        unregister_process(session_id);
+        register_process(session_id.to_string(), 6262);
+        drop(registration);
+
+        assert_eq!(
+            PROCESS_REGISTRY.lock().unwrap().get(session_id).copied(),
+            Some(6262)
+        );
+        unregister_process(session_id);
+    }
+
+    #[test]
+    fn test_wait_for_exit_returns_once_process_is_gone() {
+        let polls = Cell::new(0);
+        let exited = wait_for_exit(42, Duration::from_secs(5), Duration::from_millis(1), |_| {
+            polls.set(polls.get() + 1);
+            polls.get() < 3
+        });
+        assert!(exited);
+        assert_eq!(polls.get(), 3);
+    }
+
+    #[test]
+    fn test_wait_for_exit_gives_up_after_grace_period() {
+        let started = Instant::now();
+        let exited = wait_for_exit(
+            42,
+            Duration::from_millis(30),
+            Duration::from_millis(5),
+            |_| true,
+        );
+        assert!(!exited);
+        assert!(started.elapsed() >= Duration::from_millis(30));
+    }
+}
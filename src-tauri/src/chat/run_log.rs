@@ -4,17 +4,21 @@
 //! the raw Claude CLI output. Each run (Claude execution) gets its own file.
 
 use std::fs::{self, File, OpenOptions};
-use std::io::{BufRead, BufReader, Write};
-use std::path::PathBuf;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
+use crate::platform::silent_command;
+
 use super::storage::{
     get_session_dir, list_all_session_ids, load_metadata, save_metadata, with_metadata_mut,
 };
 use super::types::{
-    ChatMessage, ContentBlock, MessageRole, RunEntry, RunStatus, ToolCall, UsageData,
+    ChatMessage, ContentBlock, MessageRole, RunEntry, RunRequestOptions, RunStatus,
+    SessionMetadata, ToolCall, UsageData,
 };
 
 // ============================================================================
@@ -64,10 +68,12 @@ impl RunLogWriter {
         assistant_message_id: &str,
         claude_session_id: Option<&str>,
         usage: Option<UsageData>,
+        resolved_model: Option<&str>,
     ) -> Result<(), String> {
         let now = now_timestamp();
         let run_id = self.run_id.clone();
         let claude_sid = claude_session_id.map(|s| s.to_string());
+        let resolved_model = resolved_model.map(|s| s.to_string());
 
         with_metadata_mut(
             &self.app,
@@ -82,6 +88,7 @@ impl RunLogWriter {
                     run.assistant_message_id = Some(assistant_message_id.to_string());
                     run.claude_session_id = claude_sid.clone();
                     run.usage = usage.clone();
+                    run.resolved_model = resolved_model.clone();
                 }
 
                 // Update metadata's claude_session_id for resumption
@@ -94,6 +101,18 @@ impl RunLogWriter {
         )?;
 
         log::trace!("Run completed: {}", self.run_id);
+
+        // Index in the background so completion isn't delayed by large logs
+        let (app, session_id, run_id) = (
+            self.app.clone(),
+            self.session_id.clone(),
+            self.run_id.clone(),
+        );
+        std::thread::spawn(move || {
+            if let Err(e) = super::search_index::index_run(&app, &session_id, &run_id) {
+                log::warn!("Failed to index run {run_id} for search: {e}");
+            }
+        });
         Ok(())
     }
 
@@ -129,6 +148,7 @@ impl RunLogWriter {
     pub fn mark_crashed(&mut self) -> Result<(), String> {
         let now = now_timestamp();
         let run_id = self.run_id.clone();
+        let stderr_tail = self.stderr_tail();
 
         with_metadata_mut(
             &self.app,
@@ -141,6 +161,7 @@ impl RunLogWriter {
                     run.status = RunStatus::Crashed;
                     run.ended_at = Some(now);
                     run.recovered = true;
+                    run.stderr_tail = stderr_tail;
                 }
                 Ok(())
             },
@@ -225,6 +246,7 @@ impl RunLogWriter {
     pub fn crash(&mut self) -> Result<(), String> {
         let now = now_timestamp();
         let run_id = self.run_id.clone();
+        let stderr_tail = self.stderr_tail();
 
         with_metadata_mut(
             &self.app,
@@ -238,6 +260,7 @@ impl RunLogWriter {
                     run.ended_at = Some(now);
                     run.recovered = true;
                     run.assistant_message_id = Some(uuid::Uuid::new_v4().to_string());
+                    run.stderr_tail = stderr_tail;
                 }
                 Ok(())
             },
@@ -246,6 +269,136 @@ impl RunLogWriter {
         log::trace!("Run marked as crashed: {}", self.run_id);
         Ok(())
     }
+
+    /// Read the stderr tail from this run's output file (stderr is redirected into it)
+    fn stderr_tail(&self) -> Option<String> {
+        match read_run_log(&self.app, &self.session_id, &self.run_id) {
+            Ok(lines) => extract_stderr_tail(&lines, STDERR_TAIL_MAX_BYTES),
+            Err(e) => {
+                log::warn!("Failed to read stderr tail for run {}: {e}", self.run_id);
+                None
+            }
+        }
+    }
+}
+
+/// Maximum number of bytes of stderr kept on a crashed run
+const STDERR_TAIL_MAX_BYTES: usize = 4096;
+
+/// Extract the trailing stderr output from a run log.
+///
+/// The detached CLI writes stderr into the same file as its NDJSON stdout, so
+/// stderr is every non-empty line that doesn't parse as JSON. Keeps the last
+/// lines that fit within `max_bytes`, truncating the oldest kept line if needed.
+fn extract_stderr_tail(lines: &[String], max_bytes: usize) -> Option<String> {
+    let mut kept: Vec<&str> = Vec::new();
+    let mut total = 0;
+
+    for line in lines.iter().rev() {
+        let line = line.trim_end();
+        if line.trim().is_empty() || serde_json::from_str::<serde_json::Value>(line).is_ok() {
+            continue;
+        }
+
+        let needed = line.len() + usize::from(!kept.is_empty());
+        if total + needed > max_bytes {
+            let remaining = max_bytes.saturating_sub(total + usize::from(!kept.is_empty()));
+            if remaining > 0 {
+                let mut start = line.len() - remaining;
+                while !line.is_char_boundary(start) {
+                    start += 1;
+                }
+                kept.push(&line[start..]);
+            }
+            break;
+        }
+
+        total += needed;
+        kept.push(line);
+    }
+
+    if kept.is_empty() {
+        return None;
+    }
+    kept.reverse();
+    Some(kept.join("\n"))
+}
+
+/// Trim labels, dropping empty and duplicate entries (first occurrence wins)
+fn normalize_labels(labels: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for label in labels {
+        let label = label.trim();
+        if !label.is_empty() && !normalized.iter().any(|l| l == label) {
+            normalized.push(label.to_string());
+        }
+    }
+    normalized
+}
+
+/// Read the checked-out branch of a git working directory.
+/// Returns None for non-git directories and for a detached HEAD.
+fn current_git_branch(dir: &str) -> Option<String> {
+    silent_command("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .current_dir(dir)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|s| !s.is_empty() && s != "HEAD")
+}
+
+/// Claude session id captured by a run, for branching a new run from it
+pub fn claude_session_id_for_run(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    run_id: &str,
+) -> Result<String, String> {
+    let metadata = load_metadata(app, session_id)?
+        .ok_or_else(|| format!("Session not found: {session_id}"))?;
+    let run = metadata
+        .find_run(run_id)
+        .ok_or_else(|| format!("Cannot resume from run {run_id}: run not found"))?;
+    run.claude_session_id.clone().ok_or_else(|| {
+        format!("Cannot resume from run {run_id}: it has no captured Claude session id")
+    })
+}
+
+/// Rank of a thinking or effort level name, least reasoning first
+fn reasoning_rank(level: &str) -> Option<u8> {
+    match level {
+        "off" => Some(0),
+        "think" | "low" => Some(1),
+        "megathink" | "medium" => Some(2),
+        "ultrathink" | "high" => Some(3),
+        "max" => Some(4),
+        _ => None,
+    }
+}
+
+/// Whether a run's thinking should be kept out of the UI because its reasoning
+/// level is below `min_level`. The effort level wins when both are set, as it
+/// does for the CLI args; unknown levels always show thinking.
+pub fn thinking_hidden_for(
+    thinking_level: Option<&str>,
+    effort_level: Option<&str>,
+    min_level: Option<&str>,
+) -> bool {
+    let Some(min_rank) = min_level.and_then(reasoning_rank) else {
+        return false;
+    };
+    effort_level
+        .or(thinking_level)
+        .and_then(reasoning_rank)
+        .is_some_and(|rank| rank < min_rank)
+}
+
+/// The `thinking_hidden` flag of a run log's `_run_meta` header
+pub fn run_meta_hides_thinking(meta: &serde_json::Value) -> bool {
+    meta.get("thinking_hidden")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
 }
 
 /// Start a new run - creates JSONL file and updates metadata
@@ -262,9 +415,19 @@ pub fn start_run(
     execution_mode: Option<&str>,
     thinking_level: Option<&str>,
     effort_level: Option<&str>,
+    labels: &[String],
+    working_dir: Option<&str>,
+    retried_from: Option<&str>,
+    branched_from: Option<&str>,
+    permission_mode: Option<&str>,
+    web_access: Option<bool>,
+    thinking_hidden: bool,
+    request_options: RunRequestOptions,
 ) -> Result<RunLogWriter, String> {
     let run_id = Uuid::new_v4().to_string();
     let now = now_timestamp();
+    let labels = normalize_labels(labels);
+    let git_branch = working_dir.and_then(current_git_branch);
 
     // Ensure session directory exists
     let session_dir = get_session_dir(app, session_id)?;
@@ -289,6 +452,14 @@ pub fn start_run(
         "model": model,
         "execution_mode": execution_mode,
         "thinking_level": thinking_level,
+        "labels": labels,
+        "working_dir": working_dir,
+        "git_branch": git_branch,
+        "retried_from": retried_from,
+        "branched_from": branched_from,
+        "permission_mode": permission_mode,
+        "web_access": web_access,
+        "thinking_hidden": thinking_hidden,
         "started_at": now,
     });
     writeln!(file, "{meta}").map_err(|e| format!("Failed to write run log header: {e}"))?;
@@ -312,8 +483,21 @@ pub fn start_run(
         cancelled: false,
         recovered: false,
         claude_session_id: None,
-        pid: None,   // Set later via set_pid() after spawning detached process
-        usage: None, // Set on completion via complete()
+        pid: None,            // Set later via set_pid() after spawning detached process
+        usage: None,          // Set on completion via complete()
+        resolved_model: None, // Set on completion from the CLI's init message
+        labels,
+        content_hash: None, // Computed lazily by find_duplicate_runs
+        stderr_tail: None,
+        working_dir: working_dir.map(|s| s.to_string()),
+        git_branch,
+        retried_from: retried_from.map(|s| s.to_string()),
+        branched_from: branched_from.map(|s| s.to_string()),
+        compacted_runs: None,
+        permission_mode: permission_mode.map(|s| s.to_string()),
+        web_access,
+        thinking_hidden,
+        request_options: Some(request_options),
     };
 
     with_metadata_mut(
@@ -346,37 +530,79 @@ pub fn start_run(
     })
 }
 
+/// Build the stream-json user message for a detached Claude CLI run.
+///
+/// The message is sent as-is; the response language (`ai_language`) is set in
+/// the system prompt by `build_claude_args`.
+fn build_input_message(message: &str) -> serde_json::Value {
+    serde_json::json!({
+        "type": "user",
+        "message": {
+            "role": "user",
+            "content": message
+        }
+    })
+}
+
+/// Default cap on a message sent to a detached Claude CLI run (KB)
+const DEFAULT_MAX_INPUT_KB: u64 = 4096;
+
+/// Resolve the configured message size cap in bytes (None = default)
+pub fn max_input_bytes(configured_kb: Option<u64>) -> usize {
+    let kb = configured_kb
+        .filter(|kb| *kb > 0)
+        .unwrap_or(DEFAULT_MAX_INPUT_KB);
+    usize::try_from(kb.saturating_mul(1024)).unwrap_or(usize::MAX)
+}
+
+/// Reject messages over `max_bytes` instead of sending (or truncating) them.
+///
+/// Called before a run is started so an oversized message never leaves a run behind.
+pub fn check_input_size(message: &str, max_bytes: usize) -> Result<(), String> {
+    if message.len() <= max_bytes {
+        return Ok(());
+    }
+    Err(format!(
+        "Message is too large to send ({} KB, limit is {} KB). Attach large content as a file instead, or raise the limit in preferences.",
+        message.len().div_ceil(1024),
+        max_bytes / 1024
+    ))
+}
+
 /// Write the input file for a detached Claude CLI run.
 ///
 /// The input file contains the user message in stream-json format,
-/// which Claude CLI reads via stdin redirection.
+/// which Claude CLI reads via stdin redirection. The file is fully written,
+/// synced and closed before this returns, so the CLI never reads a partial file.
 pub fn write_input_file(
     app: &tauri::AppHandle,
     session_id: &str,
     run_id: &str,
     message: &str,
+    max_bytes: usize,
 ) -> Result<PathBuf, String> {
+    check_input_size(message, max_bytes)?;
+
     let session_dir = get_session_dir(app, session_id)?;
     let input_path = session_dir.join(format!("{run_id}.input.jsonl"));
 
     log::trace!("Writing input file at: {input_path:?}");
 
     // Create the stream-json input message format
-    let input_message = serde_json::json!({
-        "type": "user",
-        "message": {
-            "role": "user",
-            "content": message
-        }
-    });
+    let input_message = build_input_message(message);
 
-    let mut file =
+    let file =
         File::create(&input_path).map_err(|e| format!("Failed to create input file: {e}"))?;
+    let mut writer = std::io::BufWriter::new(file);
 
-    writeln!(file, "{input_message}").map_err(|e| format!("Failed to write input message: {e}"))?;
+    writeln!(writer, "{input_message}")
+        .map_err(|e| format!("Failed to write input message: {e}"))?;
 
-    file.flush()
+    let file = writer
+        .into_inner()
         .map_err(|e| format!("Failed to flush input file: {e}"))?;
+    file.sync_all()
+        .map_err(|e| format!("Failed to sync input file: {e}"))?;
 
     log::trace!("Input file written successfully");
 
@@ -422,22 +648,261 @@ pub fn read_run_log(
 ) -> Result<Vec<String>, String> {
     let path = get_run_log_path(app, session_id, run_id)?;
 
-    if !path.exists() {
+    // Completed runs may be stored compressed as <run_id>.jsonl.gz
+    if !path.exists() && !super::tail::gzip_sibling(&path).exists() {
         return Ok(vec![]);
     }
 
-    let file = File::open(&path).map_err(|e| format!("Failed to open run log: {e}"))?;
-
-    let reader = BufReader::new(file);
+    let reader = super::tail::open_ndjson_reader(&path)
+        .map_err(|e| format!("Failed to open run log: {e}"))?;
     let lines: Result<Vec<_>, _> = reader.lines().collect();
 
     lines.map_err(|e| format!("Failed to read run log: {e}"))
 }
 
+/// Read a run log that must exist (unlike `read_run_log`, a missing log is an error).
+///
+/// Used by whole-session operations, which skip and report such runs instead
+/// of aborting.
+fn read_existing_run_log(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    run_id: &str,
+) -> Result<Vec<String>, String> {
+    let path = get_run_log_path(app, session_id, run_id)?;
+    if !path.exists() && !super::tail::gzip_sibling(&path).exists() {
+        return Err("run log is missing".to_string());
+    }
+    read_run_log(app, session_id, run_id)
+}
+
+/// One line of a run log, parsed
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunLogEntry {
+    /// Jean's `_run_meta` header written by `start_run`
+    Meta(serde_json::Value),
+    /// A Claude CLI stream-json message
+    Message(serde_json::Value),
+    /// A non-JSON line (the detached CLI's stderr is redirected into the log)
+    Stderr(String),
+}
+
+/// Streaming reader over a run log, yielding one parsed entry per line.
+///
+/// Blank lines are skipped. Only I/O errors are yielded as `Err`; lines that
+/// aren't JSON come back as [`RunLogEntry::Stderr`]. Gzip-compressed logs are
+/// decoded transparently.
+pub struct RunLogReader {
+    lines: std::io::Lines<Box<dyn BufRead + Send>>,
+}
+
+impl RunLogReader {
+    /// Open the log for a run. A missing log yields no entries.
+    pub fn for_run(app: &tauri::AppHandle, session_id: &str, run_id: &str) -> Result<Self, String> {
+        let path = get_run_log_path(app, session_id, run_id)?;
+
+        // Completed runs may be stored compressed as <run_id>.jsonl.gz
+        if !path.exists() && !super::tail::gzip_sibling(&path).exists() {
+            return Ok(Self::from_reader(Box::new(std::io::empty())));
+        }
+
+        let reader = super::tail::open_ndjson_reader(&path)
+            .map_err(|e| format!("Failed to open run log: {e}"))?;
+        Ok(Self::from_reader(reader))
+    }
+
+    pub fn from_reader(reader: Box<dyn BufRead + Send>) -> Self {
+        Self {
+            lines: reader.lines(),
+        }
+    }
+}
+
+impl Iterator for RunLogReader {
+    type Item = Result<RunLogEntry, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(format!("Failed to read run log: {e}"))),
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let entry = match serde_json::from_str::<serde_json::Value>(&line) {
+                Ok(value) if value.get("_run_meta").and_then(|v| v.as_bool()) == Some(true) => {
+                    RunLogEntry::Meta(value)
+                }
+                Ok(value) => RunLogEntry::Message(value),
+                Err(_) => RunLogEntry::Stderr(line),
+            };
+            return Some(Ok(entry));
+        }
+    }
+}
+
+/// Raw contents of a run log, for developer tools
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RunRawEvents {
+    /// The `_run_meta` header (model, mode, started_at, ...), if present
+    pub meta: Option<serde_json::Value>,
+    /// Stream-json events in file order (non-JSON stderr lines are skipped)
+    pub events: Vec<serde_json::Value>,
+}
+
+fn collect_raw_events(reader: RunLogReader, limit: Option<usize>) -> Result<RunRawEvents, String> {
+    let limit = limit.unwrap_or(usize::MAX);
+    let mut raw = RunRawEvents {
+        meta: None,
+        events: Vec::new(),
+    };
+
+    for entry in reader {
+        match entry? {
+            RunLogEntry::Meta(meta) => raw.meta = Some(meta),
+            RunLogEntry::Message(event) => {
+                if raw.events.len() >= limit {
+                    break;
+                }
+                raw.events.push(event);
+            }
+            RunLogEntry::Stderr(_) => {}
+        }
+    }
+    Ok(raw)
+}
+
+/// Read a run's raw stream-json events, optionally limited to the first `limit`
+pub fn read_run_raw_events(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    run_id: &str,
+    limit: Option<usize>,
+) -> Result<RunRawEvents, String> {
+    let metadata = load_metadata(app, session_id)?
+        .ok_or_else(|| format!("Session not found: {session_id}"))?;
+    if metadata.find_run(run_id).is_none() {
+        return Err(format!("Run not found: {run_id}"));
+    }
+
+    collect_raw_events(RunLogReader::for_run(app, session_id, run_id)?, limit)
+}
+
+/// Re-emit a finished run's streaming events for playback.
+///
+/// `speed` 1.0 replays immediately; higher values (up to 20) pause longer
+/// between events.
+/// Refuses runs that are still live. Metadata is left untouched.
+pub fn replay_run(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    run_id: &str,
+    speed: f32,
+) -> Result<(), String> {
+    let max_speed = super::claude::MAX_REPLAY_SPEED;
+    if !(1.0..=max_speed).contains(&speed) {
+        return Err(format!(
+            "Invalid replay speed: {speed} (must be between 1.0 and {max_speed})"
+        ));
+    }
+
+    let metadata = load_metadata(app, session_id)?
+        .ok_or_else(|| format!("Session not found: {session_id}"))?;
+    let run = metadata
+        .find_run(run_id)
+        .ok_or_else(|| format!("Run not found: {run_id}"))?;
+    let is_last_run = metadata.runs.last().is_some_and(|r| r.run_id == run_id);
+    if matches!(run.status, RunStatus::Running | RunStatus::Resumable)
+        || (is_last_run && super::registry::is_process_running(session_id))
+    {
+        return Err(format!(
+            "Run {run_id} is still running and can't be replayed"
+        ));
+    }
+
+    let lines = read_run_log(app, session_id, run_id)?;
+    super::claude::replay_run_events(app, session_id, &metadata.worktree_id, &lines, speed);
+    Ok(())
+}
+
+/// Best-effort extraction of assistant text from a truncated stream-json line.
+///
+/// Returns the decoded value of every `"text"` field in an `assistant` message,
+/// including a final string cut off mid-way. Returns nothing for other message types.
+fn recover_truncated_text(line: &str) -> Vec<String> {
+    let line = line.trim();
+    if !line.starts_with('{') || !line.contains(r#""type":"assistant""#) {
+        return vec![];
+    }
+
+    const KEY: &str = r#""text":""#;
+    let mut texts = Vec::new();
+    let mut rest = line;
+    while let Some(pos) = rest.find(KEY) {
+        rest = &rest[pos + KEY.len()..];
+
+        // Scan to the closing quote, honoring escapes
+        let mut end = None;
+        let mut escaped = false;
+        let mut last_escape = None;
+        for (i, c) in rest.char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => {
+                    escaped = true;
+                    last_escape = Some(i);
+                }
+                '"' => {
+                    end = Some(i);
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let raw = match end {
+            Some(end) => &rest[..end],
+            None => {
+                // Truncated string: drop an escape sequence cut off mid-way
+                let mut raw = rest;
+                if let Some(bs) = last_escape {
+                    let tail = raw[bs..].as_bytes();
+                    let complete = match tail.get(1) {
+                        Some(b'u') => tail.len() >= 6,
+                        Some(_) => true,
+                        None => false,
+                    };
+                    if !complete {
+                        raw = &raw[..bs];
+                    }
+                }
+                raw
+            }
+        };
+        rest = &rest[raw.len()..];
+
+        if let Ok(text) = serde_json::from_str::<String>(&format!("\"{raw}\"")) {
+            if !text.is_empty() && text != "(no content)" {
+                texts.push(text);
+            }
+        }
+    }
+    texts
+}
+
 /// Parse JSONL lines and build a ChatMessage
 /// This replicates the parsing logic from execute_claude_streaming
-pub fn parse_run_to_message(lines: &[String], run: &RunEntry) -> Result<ChatMessage, String> {
+///
+/// Also returns how many non-empty lines failed to parse as JSON, so callers
+/// can surface corrupted logs or unknown event shapes.
+pub fn parse_run_to_message(
+    lines: &[String],
+    run: &RunEntry,
+) -> Result<(ChatMessage, usize), String> {
     let mut content = String::new();
+    let mut unparseable_lines = 0;
     let mut tool_calls: Vec<ToolCall> = Vec::new();
     let mut content_blocks: Vec<ContentBlock> = Vec::new();
 
@@ -448,7 +913,10 @@ pub fn parse_run_to_message(lines: &[String], run: &RunEntry) -> Result<ChatMess
 
         let msg: serde_json::Value = match serde_json::from_str(line) {
             Ok(m) => m,
-            Err(_) => continue,
+            Err(_) => {
+                unparseable_lines += 1;
+                continue;
+            }
         };
 
         // Skip metadata header line (has _run_meta: true)
@@ -513,11 +981,13 @@ pub fn parse_run_to_message(lines: &[String], run: &RunEntry) -> Result<ChatMess
                                         input,
                                         output: None,
                                         parent_tool_use_id: current_parent_tool_use_id.clone(),
+                                        is_error: false,
+                                        duration_ms: None,
                                     });
 
                                     content_blocks.push(ContentBlock::ToolUse { tool_call_id: id });
                                 }
-                                "thinking" => {
+                                "thinking" if !run.thinking_hidden => {
                                     if let Some(thinking) =
                                         block.get("thinking").and_then(|v| v.as_str())
                                     {
@@ -545,12 +1015,12 @@ pub fn parse_run_to_message(lines: &[String], run: &RunEntry) -> Result<ChatMess
                                     .get("tool_use_id")
                                     .and_then(|v| v.as_str())
                                     .unwrap_or("");
-                                let output =
-                                    block.get("content").and_then(|v| v.as_str()).unwrap_or("");
+                                let (output, is_error) = super::claude::parse_tool_result(block);
 
                                 // Update matching tool call's output
                                 if let Some(tc) = tool_calls.iter_mut().find(|t| t.id == tool_id) {
-                                    tc.output = Some(output.to_string());
+                                    tc.output = Some(output);
+                                    tc.is_error = is_error;
                                 }
                             }
                         }
@@ -569,7 +1039,20 @@ pub fn parse_run_to_message(lines: &[String], run: &RunEntry) -> Result<ChatMess
         }
     }
 
-    Ok(ChatMessage {
+    // A crash can leave the final line half-written (no closing brace/newline).
+    // Salvage any assistant text it contains rather than dropping it.
+    if run.status == RunStatus::Crashed {
+        if let Some(last) = lines.iter().rev().find(|l| !l.trim().is_empty()) {
+            if serde_json::from_str::<serde_json::Value>(last).is_err() {
+                for text in recover_truncated_text(last) {
+                    content.push_str(&text);
+                    content_blocks.push(ContentBlock::Text { text });
+                }
+            }
+        }
+    }
+
+    let message = ChatMessage {
         id: run
             .assistant_message_id
             .clone()
@@ -582,13 +1065,20 @@ pub fn parse_run_to_message(lines: &[String], run: &RunEntry) -> Result<ChatMess
         content_blocks,
         cancelled: run.cancelled,
         plan_approved: false,
-        model: None,
-        execution_mode: None,
-        thinking_level: None,
-        effort_level: None,
+        // Model that actually answered, falling back to the one requested
+        model: run.resolved_model.clone().or_else(|| run.model.clone()),
+        execution_mode: run.execution_mode.clone(),
+        thinking_level: run.thinking_level.clone(),
+        effort_level: run.effort_level.clone(),
         recovered: run.recovered,
         usage: run.usage.clone(), // Token usage from metadata
-    })
+        working_dir: None,
+        git_branch: None,
+        retried_from: None,
+        compacted_runs: run.compacted_runs,
+    };
+
+    Ok((message, unparseable_lines))
 }
 
 // ============================================================================
@@ -613,7 +1103,10 @@ pub fn load_session_messages(
         // These have Cancelled status but no assistant_message_id
         let is_undo_send = run.status == RunStatus::Cancelled && run.assistant_message_id.is_none();
 
-        if !is_undo_send {
+        // Compacted-history summaries have no user message of their own
+        let is_summary = run.compacted_runs.is_some();
+
+        if !is_undo_send && !is_summary {
             // Add user message
             messages.push(ChatMessage {
                 id: run.user_message_id.clone(),
@@ -631,6 +1124,10 @@ pub fn load_session_messages(
                 effort_level: run.effort_level.clone(),
                 recovered: false,
                 usage: None, // User messages don't have token usage
+                working_dir: run.working_dir.clone(),
+                git_branch: run.git_branch.clone(),
+                retried_from: run.retried_from.clone(),
+                compacted_runs: None,
             });
         }
 
@@ -639,8 +1136,14 @@ pub fn load_session_messages(
             let lines = read_run_log(app, session_id, &run.run_id)?;
 
             // Parse JSONL content (may only have metadata header if crashed early)
-            let mut assistant_msg = parse_run_to_message(&lines, run)?;
+            let (mut assistant_msg, unparseable_lines) = parse_run_to_message(&lines, run)?;
             assistant_msg.session_id = session_id.to_string();
+            if unparseable_lines > 0 {
+                log::debug!(
+                    "Run {} has {unparseable_lines} unparseable line(s) in its log",
+                    run.run_id
+                );
+            }
 
             // For crashed runs with no content (only metadata header), add placeholder
             if run.status == RunStatus::Crashed
@@ -692,6 +1195,48 @@ pub fn mark_running_run_cancelled(app: &tauri::AppHandle, session_id: &str) -> R
     Ok(())
 }
 
+/// Mark a single run as cancelled (called by cancel_run_process)
+/// Unlike mark_running_run_cancelled, other runs in the session are left untouched.
+pub fn mark_run_cancelled(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    run_id: &str,
+) -> Result<(), String> {
+    let metadata = load_metadata(app, session_id)?
+        .ok_or_else(|| format!("Metadata not found for session: {session_id}"))?;
+
+    let cancelled = with_metadata_mut(
+        app,
+        session_id,
+        &metadata.worktree_id,
+        &metadata.name,
+        metadata.order,
+        |current| Ok(cancel_run_in_metadata(current, run_id, now_timestamp())),
+    )?;
+    if cancelled {
+        log::trace!("Marked run {run_id} as cancelled for session {session_id}");
+    } else {
+        log::trace!("Run {run_id} in session {session_id} is not running, left as is");
+    }
+
+    Ok(())
+}
+
+/// Mark `run_id` cancelled if it is still running or resumable.
+/// Returns true if the run changed; no other run is touched.
+fn cancel_run_in_metadata(metadata: &mut SessionMetadata, run_id: &str, now: u64) -> bool {
+    match metadata.find_run_mut(run_id) {
+        Some(run) if matches!(run.status, RunStatus::Running | RunStatus::Resumable) => {
+            run.status = RunStatus::Cancelled;
+            run.ended_at = Some(now);
+            run.cancelled = true;
+            // Leave assistant_message_id as None (undo_send case)
+            true
+        }
+        _ => false,
+    }
+}
+
 // ============================================================================
 // Recovery Functions
 // ============================================================================
@@ -705,17 +1250,65 @@ pub struct RecoveredRun {
     pub user_message: String,
     /// True if the process is still running and can be resumed
     pub resumable: bool,
+    /// PID recorded for the run (used for the liveness check)
+    pub pid: Option<u32>,
+}
+
+/// What recovery does with an incomplete run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RecoveryAction {
+    /// Process is still running - mark as resumable so we can tail it
+    Resumable,
+    /// Process is dead - mark as crashed
+    Crashed,
+}
+
+/// Decide how recovery should treat a run (None if it doesn't need recovery)
+fn recovery_action(
+    status: &RunStatus,
+    pid: Option<u32>,
+    is_alive: impl Fn(u32) -> bool,
+) -> Option<RecoveryAction> {
+    if *status != RunStatus::Running {
+        return None;
+    }
+    if pid.map(is_alive).unwrap_or(false) {
+        Some(RecoveryAction::Resumable)
+    } else {
+        Some(RecoveryAction::Crashed)
+    }
 }
 
 /// Check for and recover incomplete runs across all sessions
 /// Called on app startup to handle crashed runs from previous session
 pub fn recover_incomplete_runs(app: &tauri::AppHandle) -> Result<Vec<RecoveredRun>, String> {
+    recover_incomplete_runs_inner(app, false)
+}
+
+/// Report what recover_incomplete_runs would do without writing any metadata
+///
+/// Uses the same pid liveness checks, so the result shows which runs would be
+/// marked resumable vs crashed if recovery ran right now.
+pub fn preview_incomplete_runs(app: &tauri::AppHandle) -> Result<Vec<RecoveredRun>, String> {
+    recover_incomplete_runs_inner(app, true)
+}
+
+fn recover_incomplete_runs_inner(
+    app: &tauri::AppHandle,
+    dry_run: bool,
+) -> Result<Vec<RecoveredRun>, String> {
     use super::detached::is_process_alive;
 
     let session_ids = list_all_session_ids(app)?;
     let mut recovered = Vec::new();
 
     for session_id in session_ids {
+        // A registered process is already being tailed (e.g., re-attached at
+        // startup), so its Running run must not be flipped back to Resumable
+        if super::registry::is_process_running(&session_id) {
+            continue;
+        }
+
         let mut metadata = match load_metadata(app, &session_id)? {
             Some(m) => m,
             None => continue,
@@ -724,22 +1317,26 @@ pub fn recover_incomplete_runs(app: &tauri::AppHandle) -> Result<Vec<RecoveredRu
         let mut modified = false;
 
         for run in &mut metadata.runs {
-            if run.status == RunStatus::Running {
-                // Check if the detached process is still running
-                let process_alive = run.pid.map(is_process_alive).unwrap_or(false);
+            let Some(action) = recovery_action(&run.status, run.pid, is_process_alive) else {
+                continue;
+            };
+
+            recovered.push(RecoveredRun {
+                session_id: session_id.clone(),
+                worktree_id: metadata.worktree_id.clone(),
+                run_id: run.run_id.clone(),
+                user_message: run.user_message.clone(),
+                resumable: action == RecoveryAction::Resumable,
+                pid: run.pid,
+            });
 
-                if process_alive {
-                    // Process is still running - mark as resumable so we can tail it
-                    run.status = RunStatus::Resumable;
-                    modified = true;
+            if dry_run {
+                continue;
+            }
 
-                    recovered.push(RecoveredRun {
-                        session_id: session_id.clone(),
-                        worktree_id: metadata.worktree_id.clone(),
-                        run_id: run.run_id.clone(),
-                        user_message: run.user_message.clone(),
-                        resumable: true,
-                    });
+            match action {
+                RecoveryAction::Resumable => {
+                    run.status = RunStatus::Resumable;
 
                     log::trace!(
                         "Found resumable run: {} in session {} (PID: {:?})",
@@ -747,21 +1344,15 @@ pub fn recover_incomplete_runs(app: &tauri::AppHandle) -> Result<Vec<RecoveredRu
                         session_id,
                         run.pid
                     );
-                } else {
-                    // Process is dead - mark as crashed
+                }
+                RecoveryAction::Crashed => {
                     run.status = RunStatus::Crashed;
                     run.ended_at = Some(now_timestamp());
                     run.recovered = true;
                     run.assistant_message_id = Some(Uuid::new_v4().to_string());
-                    modified = true;
-
-                    recovered.push(RecoveredRun {
-                        session_id: session_id.clone(),
-                        worktree_id: metadata.worktree_id.clone(),
-                        run_id: run.run_id.clone(),
-                        user_message: run.user_message.clone(),
-                        resumable: false,
-                    });
+                    run.stderr_tail = read_run_log(app, &session_id, &run.run_id)
+                        .ok()
+                        .and_then(|lines| extract_stderr_tail(&lines, STDERR_TAIL_MAX_BYTES));
 
                     log::trace!(
                         "Recovered crashed run: {} in session {} (user message: {})",
@@ -771,6 +1362,7 @@ pub fn recover_incomplete_runs(app: &tauri::AppHandle) -> Result<Vec<RecoveredRu
                     );
                 }
             }
+            modified = true;
         }
 
         if modified {
@@ -778,7 +1370,7 @@ pub fn recover_incomplete_runs(app: &tauri::AppHandle) -> Result<Vec<RecoveredRu
         }
     }
 
-    if !recovered.is_empty() {
+    if !recovered.is_empty() && !dry_run {
         log::trace!(
             "Recovered {} crashed run(s) from previous session",
             recovered.len()
@@ -788,43 +1380,537 @@ pub fn recover_incomplete_runs(app: &tauri::AppHandle) -> Result<Vec<RecoveredRu
     Ok(recovered)
 }
 
-/// Find all runs with status = Running (incomplete runs that need recovery)
-#[allow(dead_code)]
-pub fn find_incomplete_runs(
-    app: &tauri::AppHandle,
-    session_id: &str,
-) -> Result<Vec<RunEntry>, String> {
-    let metadata = match load_metadata(app, session_id)? {
-        Some(m) => m,
-        None => return Ok(vec![]),
-    };
-
-    let incomplete: Vec<RunEntry> = metadata
-        .runs
-        .into_iter()
-        .filter(|r| r.status == RunStatus::Running)
-        .collect();
+/// Characters of context kept on each side of a search match
+const SEARCH_SNIPPET_CONTEXT: usize = 60;
 
-    Ok(incomplete)
+/// A search match inside a session's run logs
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SearchHit {
+    pub session_id: String,
+    pub worktree_id: String,
+    pub session_name: String,
+    pub run_id: String,
+    /// "user" or "assistant"
+    pub role: String,
+    /// Where the match was found: "text", "thinking", "tool_input", or "tool_output"
+    pub block_type: String,
+    /// Tool name when the match is in a tool input/output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    /// Text around the match
+    pub snippet: String,
 }
 
-/// Mark a run as crashed and recovered
-#[allow(dead_code)]
-pub fn mark_run_as_crashed(
-    app: &tauri::AppHandle,
-    session_id: &str,
-    run_id: &str,
-) -> Result<(), String> {
-    let mut metadata = load_metadata(app, session_id)?
-        .ok_or_else(|| format!("Metadata not found for session: {session_id}"))?;
+/// Searchable text extracted from one NDJSON line: (role, block_type, tool_name, text)
+pub type SearchableField = (&'static str, &'static str, Option<String>, String);
+
+/// Extract the searchable text of one Claude CLI stream message
+pub fn searchable_fields(msg: &serde_json::Value) -> Vec<SearchableField> {
+    let mut fields = Vec::new();
+    let msg_type = msg.get("type").and_then(|v| v.as_str()).unwrap_or("");
+    let Some(blocks) = msg
+        .get("message")
+        .and_then(|m| m.get("content"))
+        .and_then(|c| c.as_array())
+    else {
+        return fields;
+    };
 
-    let now = now_timestamp();
+    for block in blocks {
+        let block_type = block.get("type").and_then(|v| v.as_str()).unwrap_or("");
+        match (msg_type, block_type) {
+            ("assistant", "text") => {
+                if let Some(text) = block.get("text").and_then(|v| v.as_str()) {
+                    fields.push(("assistant", "text", None, text.to_string()));
+                }
+            }
+            ("assistant", "thinking") => {
+                if let Some(thinking) = block.get("thinking").and_then(|v| v.as_str()) {
+                    fields.push(("assistant", "thinking", None, thinking.to_string()));
+                }
+            }
+            ("assistant", "tool_use") => {
+                let name = block.get("name").and_then(|v| v.as_str()).map(String::from);
+                if let Some(input) = block.get("input") {
+                    // Prefer the plain command for Bash so quotes aren't JSON-escaped
+                    let text = input
+                        .get("command")
+                        .and_then(|v| v.as_str())
+                        .map(String::from)
+                        .unwrap_or_else(|| input.to_string());
+                    fields.push(("assistant", "tool_input", name, text));
+                }
+            }
+            ("user", "tool_result") => {
+                let (output, _) = super::claude::parse_tool_result(block);
+                fields.push(("user", "tool_output", None, output));
+            }
+            _ => {}
+        }
+    }
+    fields
+}
 
-    if let Some(run) = metadata.find_run_mut(run_id) {
-        run.status = RunStatus::Crashed;
-        run.ended_at = Some(now);
-        run.recovered = true;
-        run.assistant_message_id = Some(Uuid::new_v4().to_string());
+/// Snippet of `text` around the first case-insensitive match of `query_lower`
+fn match_snippet(text: &str, query_lower: &str) -> Option<String> {
+    if query_lower.is_empty() {
+        return None;
+    }
+
+    // Lowercasing can change byte lengths (e.g. 'İ', 'ẞ'), so map every byte of
+    // the lowercased text back to the index of the original char it came from
+    let mut lower = String::with_capacity(text.len());
+    let mut source_char_at: Vec<usize> = Vec::with_capacity(text.len());
+    for (char_idx, c) in text.chars().enumerate() {
+        for lc in c.to_lowercase() {
+            lower.push(lc);
+            source_char_at.extend(std::iter::repeat_n(char_idx, lc.len_utf8()));
+        }
+    }
+    let byte_idx = lower.find(query_lower)?;
+
+    let match_start = source_char_at[byte_idx];
+    let match_end = source_char_at[byte_idx + query_lower.len() - 1] + 1;
+    let start = match_start.saturating_sub(SEARCH_SNIPPET_CONTEXT);
+    let end = match_end + SEARCH_SNIPPET_CONTEXT;
+
+    let total = text.chars().count();
+    let mut snippet: String = text
+        .chars()
+        .skip(start)
+        .take(end - start)
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if start > 0 {
+        snippet.insert_str(0, "…");
+    }
+    if end < total {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
+
+/// Case-insensitive full-text search across all sessions' run logs.
+///
+/// Matches user messages, assistant text and thinking, and tool inputs/outputs.
+/// Stops reading a session once `max_hits_per_session` hits are found. Run logs
+/// the search index rules out are skipped (it matches query words by prefix,
+/// see `search_index`); runs it doesn't cover are scanned.
+/// Sessions with more hits come first.
+pub fn search_sessions(
+    app: &tauri::AppHandle,
+    query: &str,
+    max_hits_per_session: usize,
+) -> Result<Vec<SearchHit>, String> {
+    let query_lower = query.trim().to_lowercase();
+    if query_lower.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let index = super::search_index::load_search_index(app);
+    let lookup = index
+        .as_ref()
+        .and_then(|index| Some((index.indexed_runs(), index.candidates(&query_lower)?)));
+    let mut hits = Vec::new();
+
+    for session_id in list_all_session_ids(app)? {
+        let metadata = match load_metadata(app, &session_id)? {
+            Some(m) => m,
+            None => continue,
+        };
+        let mut session_hits = 0;
+
+        'runs: for run in &metadata.runs {
+            let mut push_hit = |role: &str, block_type: &str, tool_name, snippet| {
+                hits.push(SearchHit {
+                    session_id: metadata.id.clone(),
+                    worktree_id: metadata.worktree_id.clone(),
+                    session_name: metadata.name.clone(),
+                    run_id: run.run_id.clone(),
+                    role: role.to_string(),
+                    block_type: block_type.to_string(),
+                    tool_name,
+                    snippet,
+                });
+                session_hits += 1;
+                session_hits >= max_hits_per_session
+            };
+
+            if let Some(snippet) = match_snippet(&run.user_message, &query_lower) {
+                if push_hit("user", "text", None, snippet) {
+                    break 'runs;
+                }
+            }
+
+            if let Some((indexed, candidates)) = &lookup {
+                let key = (session_id.as_str(), run.run_id.as_str());
+                if indexed.contains(&key) && !candidates.contains(&key) {
+                    continue;
+                }
+            }
+
+            let reader = match RunLogReader::for_run(app, &session_id, &run.run_id) {
+                Ok(r) => r,
+                Err(e) => {
+                    log::warn!("Skipping run {} in search: {e}", run.run_id);
+                    continue;
+                }
+            };
+
+            for entry in reader.map_while(Result::ok) {
+                let RunLogEntry::Message(msg) = entry else {
+                    continue;
+                };
+                for (role, block_type, tool_name, text) in searchable_fields(&msg) {
+                    if let Some(snippet) = match_snippet(&text, &query_lower) {
+                        if push_hit(role, block_type, tool_name, snippet) {
+                            break 'runs;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    rank_hits_by_session(&mut hits);
+    Ok(hits)
+}
+
+/// Order hits so sessions with more hits come first, keeping each session's
+/// hits (and sessions with equal counts) in their original order
+fn rank_hits_by_session(hits: &mut [SearchHit]) {
+    let mut counts: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+    for hit in hits.iter() {
+        *counts.entry(hit.session_id.clone()).or_default() += 1;
+    }
+    hits.sort_by_key(|hit| std::cmp::Reverse(counts[&hit.session_id]));
+}
+
+/// Claude CLI session ids recorded for a session, for resuming it outside Jean
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AgentIds {
+    /// Latest Claude session id stored on the session (what the next run resumes)
+    pub claude_session_id: Option<String>,
+    /// Claude session id each run reported, oldest run first
+    pub runs: Vec<RunAgentId>,
+}
+
+/// The Claude session id a single run established or resumed
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RunAgentId {
+    pub run_id: String,
+    pub started_at: u64,
+    pub claude_session_id: Option<String>,
+}
+
+impl AgentIds {
+    fn from_metadata(metadata: &SessionMetadata) -> Self {
+        let runs: Vec<RunAgentId> = metadata
+            .runs
+            .iter()
+            .map(|run| RunAgentId {
+                run_id: run.run_id.clone(),
+                started_at: run.started_at,
+                claude_session_id: run.claude_session_id.clone(),
+            })
+            .collect();
+
+        // Fall back to the newest run-level id if the session id was never stored
+        let claude_session_id = metadata
+            .claude_session_id
+            .clone()
+            .or_else(|| runs.iter().rev().find_map(|r| r.claude_session_id.clone()));
+
+        Self {
+            claude_session_id,
+            runs,
+        }
+    }
+}
+
+/// Get the Claude session ids recorded for a session and its runs
+pub fn get_session_agent_ids(app: &tauri::AppHandle, session_id: &str) -> Result<AgentIds, String> {
+    let metadata = load_metadata(app, session_id)?
+        .ok_or_else(|| format!("Session not found: {session_id}"))?;
+    Ok(AgentIds::from_metadata(&metadata))
+}
+
+/// Summary of a session for session lists, built from metadata only
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SessionSummary {
+    pub id: String,
+    pub name: String,
+    pub worktree_id: String,
+    pub run_count: usize,
+    /// Latest run start/end time (session creation time if it has no runs)
+    pub last_activity_at: u64,
+    /// Input + output tokens across all runs
+    pub total_tokens: u64,
+    /// True if any run is running or resumable
+    pub is_active: bool,
+}
+
+impl SessionSummary {
+    fn from_metadata(metadata: &SessionMetadata) -> Self {
+        let last_activity_at = metadata
+            .runs
+            .iter()
+            .map(|run| {
+                run.ended_at
+                    .map_or(run.started_at, |ended| ended.max(run.started_at))
+            })
+            .max()
+            .unwrap_or(metadata.created_at);
+        let total_tokens = metadata
+            .runs
+            .iter()
+            .filter_map(|run| run.usage.as_ref())
+            .map(|usage| usage.input_tokens + usage.output_tokens)
+            .sum();
+        let is_active = metadata
+            .runs
+            .iter()
+            .any(|run| matches!(run.status, RunStatus::Running | RunStatus::Resumable));
+
+        Self {
+            id: metadata.id.clone(),
+            name: metadata.name.clone(),
+            worktree_id: metadata.worktree_id.clone(),
+            run_count: metadata.runs.len(),
+            last_activity_at,
+            total_tokens,
+            is_active,
+        }
+    }
+}
+
+/// Summarize every session (most recently active first).
+///
+/// Reads only metadata.json files; sessions whose metadata can't be loaded
+/// are skipped rather than failing the whole list.
+pub fn list_session_summaries(app: &tauri::AppHandle) -> Result<Vec<SessionSummary>, String> {
+    let mut summaries = Vec::new();
+
+    for session_id in list_all_session_ids(app)? {
+        match load_metadata(app, &session_id) {
+            Ok(Some(metadata)) => summaries.push(SessionSummary::from_metadata(&metadata)),
+            Ok(None) => {}
+            Err(e) => log::warn!("Skipping session {session_id} in summary list: {e}"),
+        }
+    }
+
+    summaries.sort_by(|a, b| b.last_activity_at.cmp(&a.last_activity_at));
+    Ok(summaries)
+}
+
+/// A run matched by label, with the session it belongs to
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct LabeledRun {
+    pub session_id: String,
+    pub worktree_id: String,
+    pub session_name: String,
+    pub run: RunEntry,
+}
+
+/// Find runs carrying the given label across all sessions, newest first
+pub fn find_runs_by_label(app: &tauri::AppHandle, label: &str) -> Result<Vec<LabeledRun>, String> {
+    let label = label.trim();
+    let mut matches = Vec::new();
+
+    for session_id in list_all_session_ids(app)? {
+        let metadata = match load_metadata(app, &session_id)? {
+            Some(m) => m,
+            None => continue,
+        };
+
+        for run in &metadata.runs {
+            if run.labels.iter().any(|l| l == label) {
+                matches.push(LabeledRun {
+                    session_id: metadata.id.clone(),
+                    worktree_id: metadata.worktree_id.clone(),
+                    session_name: metadata.name.clone(),
+                    run: run.clone(),
+                });
+            }
+        }
+    }
+
+    matches.sort_by(|a, b| b.run.started_at.cmp(&a.run.started_at));
+    Ok(matches)
+}
+
+/// Deterministic hash of a run's content for deduplication.
+///
+/// Covers the user message and the assistant's ordered content blocks
+/// (text, thinking, tool name + input). Timestamps and IDs are ignored so the
+/// same run imported on another machine hashes identically.
+pub fn run_content_hash(user_message: &str, message: &ChatMessage) -> String {
+    let mut hasher = Sha256::new();
+    let mut field = |tag: &str, value: &str| {
+        hasher.update(tag.as_bytes());
+        hasher.update([0]);
+        hasher.update(value.as_bytes());
+        hasher.update([0]);
+    };
+
+    field("user", user_message);
+
+    if message.content_blocks.is_empty() {
+        // Older messages without blocks: fall back to the flat content
+        field("text", &message.content);
+    }
+    for block in &message.content_blocks {
+        match block {
+            ContentBlock::Text { text } => field("text", text),
+            ContentBlock::Thinking { thinking } => field("thinking", thinking),
+            ContentBlock::ToolUse { tool_call_id } => {
+                if let Some(tool) = message.tool_calls.iter().find(|t| &t.id == tool_call_id) {
+                    field("tool", &tool.name);
+                    field("input", &tool.input.to_string());
+                }
+            }
+        }
+    }
+
+    format!("{:x}", hasher.finalize())
+}
+
+/// A run that shares its content hash with at least one other run
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DuplicateRun {
+    pub session_id: String,
+    pub worktree_id: String,
+    pub run_id: String,
+    pub started_at: u64,
+}
+
+/// Runs with identical content, oldest first
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DuplicateRunGroup {
+    pub content_hash: String,
+    pub runs: Vec<DuplicateRun>,
+}
+
+/// Group runs across all sessions by content hash, returning only duplicates.
+///
+/// Hashes are computed for finished runs that don't have one yet and cached
+/// on their RunEntry, so subsequent calls only read metadata.
+pub fn find_duplicate_runs(app: &tauri::AppHandle) -> Result<Vec<DuplicateRunGroup>, String> {
+    let mut groups: std::collections::HashMap<String, Vec<DuplicateRun>> =
+        std::collections::HashMap::new();
+
+    for session_id in list_all_session_ids(app)? {
+        let metadata = match load_metadata(app, &session_id)? {
+            Some(m) => m,
+            None => continue,
+        };
+
+        // Hashes computed from the snapshot, written back by run_id below
+        let mut new_hashes: Vec<(String, String)> = Vec::new();
+
+        for run in &metadata.runs {
+            if matches!(run.status, RunStatus::Running | RunStatus::Resumable) {
+                continue;
+            }
+
+            let hash = match &run.content_hash {
+                Some(hash) => hash.clone(),
+                None => {
+                    let lines = read_run_log(app, &session_id, &run.run_id)?;
+                    match parse_run_to_message(&lines, run) {
+                        Ok((message, _)) => {
+                            let hash = run_content_hash(&run.user_message, &message);
+                            new_hashes.push((run.run_id.clone(), hash.clone()));
+                            hash
+                        }
+                        Err(e) => {
+                            log::warn!("Failed to hash run {}: {e}", run.run_id);
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            groups.entry(hash).or_default().push(DuplicateRun {
+                session_id: session_id.clone(),
+                worktree_id: metadata.worktree_id.clone(),
+                run_id: run.run_id.clone(),
+                started_at: run.started_at,
+            });
+        }
+
+        if !new_hashes.is_empty() {
+            // Only touch content_hash, so runs started or finished meanwhile survive
+            with_metadata_mut(
+                app,
+                &session_id,
+                &metadata.worktree_id,
+                &metadata.name,
+                metadata.order,
+                |current| {
+                    for (run_id, hash) in new_hashes {
+                        if let Some(run) = current.find_run_mut(&run_id) {
+                            run.content_hash = Some(hash);
+                        }
+                    }
+                    Ok(())
+                },
+            )?;
+        }
+    }
+
+    let mut duplicates: Vec<DuplicateRunGroup> = groups
+        .into_iter()
+        .filter(|(_, runs)| runs.len() > 1)
+        .map(|(content_hash, mut runs)| {
+            runs.sort_by_key(|r| r.started_at);
+            DuplicateRunGroup { content_hash, runs }
+        })
+        .collect();
+    duplicates.sort_by_key(|g| g.runs[0].started_at);
+
+    Ok(duplicates)
+}
+
+/// Find all runs with status = Running (incomplete runs that need recovery)
+#[allow(dead_code)]
+pub fn find_incomplete_runs(
+    app: &tauri::AppHandle,
+    session_id: &str,
+) -> Result<Vec<RunEntry>, String> {
+    let metadata = match load_metadata(app, session_id)? {
+        Some(m) => m,
+        None => return Ok(vec![]),
+    };
+
+    let incomplete: Vec<RunEntry> = metadata
+        .runs
+        .into_iter()
+        .filter(|r| r.status == RunStatus::Running)
+        .collect();
+
+    Ok(incomplete)
+}
+
+/// Mark a run as crashed and recovered
+#[allow(dead_code)]
+pub fn mark_run_as_crashed(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    run_id: &str,
+) -> Result<(), String> {
+    let mut metadata = load_metadata(app, session_id)?
+        .ok_or_else(|| format!("Metadata not found for session: {session_id}"))?;
+
+    let now = now_timestamp();
+
+    if let Some(run) = metadata.find_run_mut(run_id) {
+        run.status = RunStatus::Crashed;
+        run.ended_at = Some(now);
+        run.recovered = true;
+        run.assistant_message_id = Some(Uuid::new_v4().to_string());
     }
 
     save_metadata(app, &metadata)?;
@@ -848,7 +1934,10 @@ pub fn delete_run_logs(app: &tauri::AppHandle, session_id: &str) -> Result<usize
             .flatten()
         {
             let path = entry.path();
-            if path.extension().is_some_and(|ext| ext == "jsonl") {
+            if path
+                .extension()
+                .is_some_and(|ext| ext == "jsonl" || ext == "gz")
+            {
                 fs::remove_file(&path).map_err(|e| format!("Failed to delete run log: {e}"))?;
                 deleted += 1;
             }
@@ -858,13 +1947,1615 @@ pub fn delete_run_logs(app: &tauri::AppHandle, session_id: &str) -> Result<usize
     Ok(deleted)
 }
 
+/// Result of pruning old run logs
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct PruneReport {
+    /// Number of runs whose logs were deleted
+    pub pruned_runs: usize,
+    /// Number of sessions whose metadata was updated
+    pub sessions_updated: usize,
+    /// Total bytes freed on disk
+    pub freed_bytes: u64,
+}
+
+/// A finished run considered for pruning
+#[derive(Debug, Clone)]
+struct PruneCandidate {
+    session_id: String,
+    run_id: String,
+    /// When the run ended (or started, if no end time was recorded)
+    finished_at: u64,
+    /// Combined size of the run's files on disk
+    bytes: u64,
+}
+
+/// Files belonging to a run: output log (plain or gzip) and input file
+fn run_files(session_dir: &std::path::Path, run_id: &str) -> [PathBuf; 3] {
+    [
+        session_dir.join(format!("{run_id}.jsonl")),
+        session_dir.join(format!("{run_id}.jsonl.gz")),
+        session_dir.join(format!("{run_id}.input.jsonl")),
+    ]
+}
+
+/// Choose which runs to prune (indices into `candidates`).
+///
+/// First everything older than `max_age_days`, then oldest-first until the
+/// remaining total is within `max_total_bytes`.
+fn select_runs_to_prune(
+    candidates: &[PruneCandidate],
+    now: u64,
+    max_age_days: Option<u64>,
+    max_total_bytes: Option<u64>,
+) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..candidates.len()).collect();
+    order.sort_by_key(|&i| candidates[i].finished_at);
+
+    let mut selected = vec![false; candidates.len()];
+
+    if let Some(days) = max_age_days {
+        let cutoff = now.saturating_sub(days.saturating_mul(24 * 60 * 60));
+        for &i in &order {
+            if candidates[i].finished_at < cutoff {
+                selected[i] = true;
+            }
+        }
+    }
+
+    if let Some(budget) = max_total_bytes {
+        let mut remaining: u64 = (0..candidates.len())
+            .filter(|&i| !selected[i])
+            .map(|i| candidates[i].bytes)
+            .sum();
+        for &i in &order {
+            if remaining <= budget {
+                break;
+            }
+            if !selected[i] {
+                selected[i] = true;
+                remaining -= candidates[i].bytes;
+            }
+        }
+    }
+
+    order.into_iter().filter(|&i| selected[i]).collect()
+}
+
+/// Delete old run logs across all sessions by age and/or total size.
+///
+/// Runs still `Running` or `Resumable` are never touched. Pruned runs are
+/// removed from their session metadata so messages load without them.
+pub fn prune_run_logs(
+    app: &tauri::AppHandle,
+    max_age_days: Option<u64>,
+    max_total_bytes: Option<u64>,
+) -> Result<PruneReport, String> {
+    let mut candidates = Vec::new();
+
+    for session_id in list_all_session_ids(app)? {
+        let metadata = match load_metadata(app, &session_id)? {
+            Some(m) => m,
+            None => continue,
+        };
+        let session_dir = get_session_dir(app, &session_id)?;
+
+        for run in &metadata.runs {
+            if matches!(run.status, RunStatus::Running | RunStatus::Resumable) {
+                continue;
+            }
+            let bytes = run_files(&session_dir, &run.run_id)
+                .iter()
+                .filter_map(|p| fs::metadata(p).ok())
+                .map(|m| m.len())
+                .sum();
+            candidates.push(PruneCandidate {
+                session_id: session_id.clone(),
+                run_id: run.run_id.clone(),
+                finished_at: run.ended_at.unwrap_or(run.started_at),
+                bytes,
+            });
+        }
+    }
+
+    let selected =
+        select_runs_to_prune(&candidates, now_timestamp(), max_age_days, max_total_bytes);
+
+    // Group selected runs by session so each metadata file is rewritten once
+    let mut by_session: std::collections::HashMap<&str, Vec<&str>> =
+        std::collections::HashMap::new();
+    for &i in &selected {
+        by_session
+            .entry(candidates[i].session_id.as_str())
+            .or_default()
+            .push(candidates[i].run_id.as_str());
+    }
+
+    let mut report = PruneReport::default();
+
+    for (session_id, run_ids) in by_session {
+        let Some(metadata) = load_metadata(app, session_id)? else {
+            continue;
+        };
+        let session_dir = get_session_dir(app, session_id)?;
+
+        let (pruned, freed) = with_metadata_mut(
+            app,
+            session_id,
+            &metadata.worktree_id,
+            &metadata.name,
+            metadata.order,
+            |metadata| {
+                let mut pruned = 0;
+                let mut freed = 0;
+                metadata.runs.retain(|run| {
+                    // Re-check status under the lock in case a run was resumed meanwhile
+                    let prunable = run_ids.contains(&run.run_id.as_str())
+                        && !matches!(run.status, RunStatus::Running | RunStatus::Resumable);
+                    if !prunable {
+                        return true;
+                    }
+                    for path in run_files(&session_dir, &run.run_id) {
+                        if let Ok(meta) = fs::metadata(&path) {
+                            if fs::remove_file(&path).is_ok() {
+                                freed += meta.len();
+                            }
+                        }
+                    }
+                    pruned += 1;
+                    false
+                });
+                Ok((pruned, freed))
+            },
+        )?;
+
+        if pruned > 0 {
+            report.pruned_runs += pruned;
+            report.sessions_updated += 1;
+            report.freed_bytes += freed;
+        }
+    }
+
+    log::trace!(
+        "Pruned {} run(s) across {} session(s), freed {} bytes",
+        report.pruned_runs,
+        report.sessions_updated,
+        report.freed_bytes
+    );
+
+    Ok(report)
+}
+
 // ============================================================================
-// Utility Functions
+// Metadata Repair
 // ============================================================================
 
-fn now_timestamp() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs()
+/// A run reconstructed from its log during repair
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RecoveredRun {
+    pub run_id: String,
+    pub status: RunStatus,
+}
+
+/// Result of repairing a session's metadata
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct RepairReport {
+    /// True if metadata was missing or unreadable and has been rebuilt
+    pub rebuilt: bool,
+    /// Runs recovered from their log headers, oldest first
+    pub recovered_runs: Vec<RecoveredRun>,
+    /// Log files skipped because they had no readable `_run_meta` header
+    pub skipped_files: Vec<String>,
+}
+
+/// Run ID for a run log file name (`<run_id>.jsonl` or `<run_id>.jsonl.gz`)
+fn run_id_from_log_name(name: &str) -> Option<&str> {
+    let run_id = name
+        .strip_suffix(".jsonl.gz")
+        .or_else(|| name.strip_suffix(".jsonl"))?;
+    // Input files (<run_id>.input.jsonl) aren't run logs
+    (!run_id.ends_with(".input") && !run_id.is_empty()).then_some(run_id)
+}
+
+/// Rebuild a run entry from its log: the `_run_meta` header supplies ids and
+/// settings, a `result` event marks it completed (otherwise crashed).
+fn recover_run(session_dir: &std::path::Path, run_id: &str) -> Option<(RunEntry, String)> {
+    let log_path = session_dir.join(format!("{run_id}.jsonl"));
+    let reader = super::tail::open_ndjson_reader(&log_path);
+    let mut entries = RunLogReader::from_reader(reader.ok()?);
+
+    let Some(Ok(RunLogEntry::Meta(meta))) = entries.next() else {
+        return None;
+    };
+    let text = |key: &str| meta.get(key).and_then(|v| v.as_str()).map(String::from);
+
+    let mut completed = false;
+    let mut claude_session_id = None;
+    let mut usage = None;
+    for entry in entries {
+        let Ok(RunLogEntry::Message(msg)) = entry else {
+            continue;
+        };
+        if let Some(sid) = msg.get("session_id").and_then(|v| v.as_str()) {
+            claude_session_id = Some(sid.to_string());
+        }
+        if msg.get("type").and_then(|v| v.as_str()) == Some("result") {
+            completed = true;
+            usage = msg.get("usage").map(super::claude::parse_usage);
+        }
+    }
+
+    // The user message only survives in the input file (deleted after completion)
+    let user_message = fs::read_to_string(session_dir.join(format!("{run_id}.input.jsonl")))
+        .ok()
+        .and_then(|input| serde_json::from_str::<serde_json::Value>(input.trim()).ok())
+        .and_then(|input| input["message"]["content"].as_str().map(String::from))
+        .unwrap_or_default();
+
+    // Output lines carry no timestamps; the log was last written when the run ended
+    let ended_at = fs::metadata(&log_path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|since_epoch| since_epoch.as_secs());
+
+    let run = RunEntry {
+        run_id: run_id.to_string(),
+        user_message_id: text("user_message_id").unwrap_or_else(|| Uuid::new_v4().to_string()),
+        user_message,
+        model: text("model"),
+        execution_mode: text("execution_mode"),
+        thinking_level: text("thinking_level"),
+        effort_level: None,
+        started_at: meta.get("started_at").and_then(|v| v.as_u64()).unwrap_or(0),
+        ended_at,
+        status: if completed {
+            RunStatus::Completed
+        } else {
+            RunStatus::Crashed
+        },
+        assistant_message_id: Some(Uuid::new_v4().to_string()),
+        cancelled: false,
+        recovered: !completed,
+        claude_session_id,
+        pid: None,
+        usage,
+        resolved_model: None,
+        labels: vec![],
+        content_hash: None,
+        stderr_tail: None,
+        working_dir: text("working_dir"),
+        git_branch: text("git_branch"),
+        retried_from: text("retried_from"),
+        branched_from: text("branched_from"),
+        compacted_runs: meta
+            .get("compacted_runs")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize),
+        permission_mode: text("permission_mode"),
+        web_access: meta.get("web_access").and_then(|v| v.as_bool()),
+        thinking_hidden: run_meta_hides_thinking(&meta),
+        request_options: None,
+    };
+    Some((run, text("worktree_id").unwrap_or_default()))
+}
+
+/// Reconstruct session metadata from the run logs in `session_dir`.
+///
+/// Name and tab order aren't stored in the logs; callers fill them in when known.
+fn rebuild_metadata(
+    session_id: &str,
+    session_dir: &std::path::Path,
+) -> Result<(SessionMetadata, RepairReport), String> {
+    let mut report = RepairReport {
+        rebuilt: true,
+        ..Default::default()
+    };
+    let mut runs = Vec::new();
+    let mut worktree_id = String::new();
+
+    let entries =
+        fs::read_dir(session_dir).map_err(|e| format!("Failed to read session directory: {e}"))?;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(run_id) = run_id_from_log_name(&name) else {
+            continue;
+        };
+        match recover_run(session_dir, run_id) {
+            Some((run, run_worktree_id)) => {
+                if worktree_id.is_empty() {
+                    worktree_id = run_worktree_id;
+                }
+                runs.push(run);
+            }
+            None => report.skipped_files.push(name),
+        }
+    }
+    runs.sort_by_key(|run| run.started_at);
+    report.skipped_files.sort();
+
+    let mut metadata = SessionMetadata::new(
+        session_id.to_string(),
+        worktree_id,
+        "Recovered Session".to_string(),
+        0,
+    );
+    if let Some(first) = runs.first() {
+        metadata.created_at = first.started_at;
+    }
+    metadata.claude_session_id = runs.iter().rev().find_map(|r| r.claude_session_id.clone());
+    report.recovered_runs = runs
+        .iter()
+        .map(|run| RecoveredRun {
+            run_id: run.run_id.clone(),
+            status: run.status.clone(),
+        })
+        .collect();
+    metadata.runs = runs;
+
+    Ok((metadata, report))
+}
+
+/// Check a session's metadata and rebuild it from the run logs if it's
+/// missing or unreadable (e.g., partially written during a crash).
+///
+/// A copy of the unreadable file is kept as `metadata.json.corrupt` for inspection.
+/// Healthy metadata is left untouched (`rebuilt: false`).
+pub fn repair_session(app: &tauri::AppHandle, session_id: &str) -> Result<RepairReport, String> {
+    let load_error = match load_metadata(app, session_id) {
+        Ok(Some(_)) => return Ok(RepairReport::default()),
+        Ok(None) => None,
+        Err(e) => Some(e),
+    };
+
+    let session_dir = get_session_dir(app, session_id)?;
+    let (mut metadata, report) = rebuild_metadata(session_id, &session_dir)?;
+    if metadata.runs.is_empty() {
+        return Err(match load_error {
+            Some(e) => {
+                format!("Cannot repair session {session_id}: no run logs to rebuild from ({e})")
+            }
+            None => format!("Session not found: {session_id}"),
+        });
+    }
+
+    // Keep the tab name and position if the worktree index still lists the session
+    if let Ok(index) = super::storage::load_index(app, &metadata.worktree_id) {
+        if let Some(entry) = index.sessions.iter().find(|s| s.id == session_id) {
+            metadata.name = entry.name.clone();
+            metadata.order = entry.order;
+        }
+    }
+
+    if let Some(e) = &load_error {
+        log::warn!("Rebuilding unreadable metadata for session {session_id}: {e}");
+        let path = super::storage::get_metadata_path(app, session_id)?;
+        backup_unreadable_metadata(&path)?;
+    }
+    save_metadata(app, &metadata)?;
+
+    log::trace!(
+        "Repaired session {session_id}: {} run(s) recovered, {} file(s) skipped",
+        report.recovered_runs.len(),
+        report.skipped_files.len()
+    );
+    Ok(report)
+}
+
+/// Copy an unreadable metadata file aside before it is overwritten.
+///
+/// A copy (not a rename) is used, so the session keeps its original file if
+/// saving the rebuilt metadata fails afterwards.
+fn backup_unreadable_metadata(path: &Path) -> Result<(), String> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let backup = path.with_extension("json.corrupt");
+    fs::copy(path, &backup)
+        .map_err(|e| format!("Failed to back up unreadable metadata to {backup:?}: {e}"))?;
+    Ok(())
+}
+
+// ============================================================================
+// Session Export / Import
+// ============================================================================
+
+/// Format version of exported session documents
+const SESSION_EXPORT_VERSION: u32 = 1;
+
+/// Self-contained session backup: metadata plus every run's raw log
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SessionExport {
+    pub version: u32,
+    pub metadata: SessionMetadata,
+    /// Raw JSONL lines of each run's log, keyed by run id
+    pub run_logs: std::collections::BTreeMap<String, Vec<String>>,
+}
+
+/// Result of exporting a session
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ExportReport {
+    /// The exported session document
+    pub json: String,
+    /// Runs left out of `run_logs` because their log was missing or unreadable
+    pub skipped_runs: Vec<String>,
+}
+
+/// Export a session as a JSON document that `import_session_json` reads back.
+///
+/// Runs whose log is missing or unreadable keep their metadata but are left
+/// out of `run_logs`, and are listed in the report.
+pub fn export_session_json(
+    app: &tauri::AppHandle,
+    session_id: &str,
+) -> Result<ExportReport, String> {
+    let metadata = load_metadata(app, session_id)?
+        .ok_or_else(|| format!("Session not found: {session_id}"))?;
+
+    let mut run_logs = std::collections::BTreeMap::new();
+    let mut skipped_runs = Vec::new();
+    for run in &metadata.runs {
+        match read_existing_run_log(app, session_id, &run.run_id) {
+            Ok(lines) => {
+                run_logs.insert(run.run_id.clone(), lines);
+            }
+            Err(e) => {
+                log::warn!("Skipping run {} in export of {session_id}: {e}", run.run_id);
+                skipped_runs.push(run.run_id.clone());
+            }
+        }
+    }
+
+    let export = SessionExport {
+        version: SESSION_EXPORT_VERSION,
+        metadata,
+        run_logs,
+    };
+    let json = serde_json::to_string_pretty(&export)
+        .map_err(|e| format!("Failed to serialize session: {e}"))?;
+    Ok(ExportReport { json, skipped_runs })
+}
+
+/// Give an exported session fresh ids so it can live alongside the original.
+///
+/// Session, run, user and assistant message ids are all replaced, as are the
+/// matching fields in each log's `_run_meta` header and run cross-references
+/// (retried_from, branched_from). Imported runs never have a live process, and
+/// Claude session ids are dropped so the copy never resumes the original's
+/// CLI conversation.
+fn rekey_export(export: SessionExport, new_session_id: &str) -> SessionExport {
+    let SessionExport {
+        version,
+        mut metadata,
+        mut run_logs,
+    } = export;
+
+    let run_ids: std::collections::HashMap<String, String> = metadata
+        .runs
+        .iter()
+        .map(|run| (run.run_id.clone(), Uuid::new_v4().to_string()))
+        .collect();
+    let remap = |id: &Option<String>| id.as_ref().map(|id| run_ids.get(id).unwrap_or(id).clone());
+
+    let mut new_logs = std::collections::BTreeMap::new();
+    for run in &mut metadata.runs {
+        let old_run_id = std::mem::take(&mut run.run_id);
+        run.run_id = run_ids[&old_run_id].clone();
+        run.user_message_id = Uuid::new_v4().to_string();
+        if run.assistant_message_id.is_some() {
+            run.assistant_message_id = Some(Uuid::new_v4().to_string());
+        }
+        run.retried_from = remap(&run.retried_from);
+        run.branched_from = remap(&run.branched_from);
+        run.pid = None;
+        // Resuming would append to the original session's CLI transcript
+        run.claude_session_id = None;
+        if matches!(run.status, RunStatus::Running | RunStatus::Resumable) {
+            run.status = RunStatus::Crashed;
+            run.recovered = true;
+        }
+
+        let mut lines = run_logs.remove(&old_run_id).unwrap_or_default();
+        if let Some(first) = lines.first_mut() {
+            if let Ok(mut meta) = serde_json::from_str::<serde_json::Value>(first) {
+                if meta.get("_run_meta").and_then(|v| v.as_bool()) == Some(true) {
+                    meta["run_id"] = run.run_id.clone().into();
+                    meta["session_id"] = new_session_id.into();
+                    meta["user_message_id"] = run.user_message_id.clone().into();
+                    meta["retried_from"] = run.retried_from.clone().into();
+                    meta["branched_from"] = run.branched_from.clone().into();
+                    *first = meta.to_string();
+                }
+            }
+        }
+        new_logs.insert(run.run_id.clone(), lines);
+    }
+    metadata.id = new_session_id.to_string();
+    metadata.claude_session_id = None;
+
+    SessionExport {
+        version,
+        metadata,
+        run_logs: new_logs,
+    }
+}
+
+/// Import a session exported by `export_session_json` as a new session.
+///
+/// Returns the new session id. The session keeps its original worktree and is
+/// added to that worktree's tab list.
+pub fn import_session_json(app: &tauri::AppHandle, json: &str) -> Result<String, String> {
+    let export: SessionExport =
+        serde_json::from_str(json).map_err(|e| format!("Invalid session export: {e}"))?;
+    if export.version > SESSION_EXPORT_VERSION {
+        return Err(format!(
+            "Session export version {} is newer than this version of Jean supports ({SESSION_EXPORT_VERSION})",
+            export.version
+        ));
+    }
+
+    let new_session_id = Uuid::new_v4().to_string();
+    let mut export = rekey_export(export, &new_session_id);
+
+    // Files first: the tab is only added once the session it points to exists
+    let session_dir = get_session_dir(app, &new_session_id)?;
+    if let Err(e) = write_imported_session(&session_dir, &export) {
+        let _ = fs::remove_dir_all(&session_dir);
+        return Err(e);
+    }
+
+    // Append to the worktree's tabs (also fixes the order to the end)
+    let worktree_id = export.metadata.worktree_id.clone();
+    let message_count = export.metadata.runs.len() as u32 * 2;
+    let indexed = super::storage::with_index_mut(app, &worktree_id, |index| {
+        let order = index.sessions.len() as u32;
+        index.sessions.push(super::types::SessionIndexEntry {
+            id: new_session_id.clone(),
+            name: export.metadata.name.clone(),
+            order,
+            message_count,
+            archived_at: export.metadata.archived_at,
+        });
+        Ok(order)
+    });
+    let order = match indexed {
+        Ok(order) => order,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&session_dir);
+            return Err(e);
+        }
+    };
+    if order != export.metadata.order {
+        export.metadata.order = order;
+        save_metadata(app, &export.metadata)?;
+    }
+
+    log::trace!(
+        "Imported session {new_session_id} with {} run(s)",
+        export.metadata.runs.len()
+    );
+    Ok(new_session_id)
+}
+
+/// Write an imported session's run logs, then its metadata, into `session_dir`
+fn write_imported_session(session_dir: &Path, export: &SessionExport) -> Result<(), String> {
+    for (run_id, lines) in &export.run_logs {
+        let contents: String = lines.iter().map(|line| format!("{line}\n")).collect();
+        fs::write(session_dir.join(format!("{run_id}.jsonl")), contents)
+            .map_err(|e| format!("Failed to write imported run log: {e}"))?;
+    }
+    // A brand-new session id, so no other writer can hold its metadata lock
+    super::storage::write_metadata_file(&session_dir.join("metadata.json"), &export.metadata)
+}
+
+// ============================================================================
+// Session Compaction
+// ============================================================================
+
+/// Text of a compacted-history summary: each older turn as user/assistant pairs.
+/// Earlier summaries (no user message) are carried over as-is.
+fn compaction_summary(turns: &[(&RunEntry, String)]) -> String {
+    turns
+        .iter()
+        .map(|(run, assistant)| {
+            if run.compacted_runs.is_some() {
+                assistant.clone()
+            } else {
+                format!(
+                    "**User:** {}\n\n**Assistant:** {assistant}",
+                    run.user_message
+                )
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n---\n\n")
+}
+
+/// Synthetic run replacing `older` runs (oldest first, non-empty).
+///
+/// Keeps the newest Claude session id among them so resume and branching
+/// still find it, and sums their token usage so session totals don't drop.
+fn summary_run_entry(older: &[RunEntry]) -> RunEntry {
+    let first = &older[0];
+    let last = &older[older.len() - 1];
+    let usage = older.iter().filter_map(|run| run.usage.as_ref()).fold(
+        None,
+        |total: Option<UsageData>, u| {
+            let mut total = total.unwrap_or_default();
+            total.input_tokens += u.input_tokens;
+            total.output_tokens += u.output_tokens;
+            total.cache_read_input_tokens += u.cache_read_input_tokens;
+            total.cache_creation_input_tokens += u.cache_creation_input_tokens;
+            Some(total)
+        },
+    );
+
+    RunEntry {
+        run_id: Uuid::new_v4().to_string(),
+        user_message_id: Uuid::new_v4().to_string(),
+        user_message: String::new(),
+        model: None,
+        execution_mode: None,
+        thinking_level: None,
+        effort_level: None,
+        started_at: first.started_at,
+        ended_at: Some(last.ended_at.unwrap_or(last.started_at)),
+        status: RunStatus::Completed,
+        assistant_message_id: Some(Uuid::new_v4().to_string()),
+        cancelled: false,
+        recovered: false,
+        claude_session_id: older.iter().rev().find_map(|r| r.claude_session_id.clone()),
+        pid: None,
+        usage,
+        resolved_model: None,
+        labels: vec![],
+        content_hash: None,
+        stderr_tail: None,
+        working_dir: None,
+        git_branch: None,
+        retried_from: None,
+        branched_from: None,
+        compacted_runs: Some(older.iter().map(|r| r.compacted_runs.unwrap_or(1)).sum()),
+        permission_mode: None,
+        web_access: None,
+        thinking_hidden: false,
+        request_options: None,
+    }
+}
+
+/// Result of compacting a session
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct CompactReport {
+    /// Runs replaced by the summary run
+    pub compacted_runs: usize,
+    /// Compacted runs left out of the summary because their log was missing or unreadable
+    pub skipped_runs: Vec<String>,
+}
+
+/// Replace all but the newest `keep_last` runs with a single summary run.
+///
+/// The summary concatenates the older turns and is stored like any other run
+/// (so messages load the same way); the UI renders it as a collapsed
+/// "earlier conversation" block. Superseded run files are deleted. Runs with a
+/// missing or unreadable log are left out of the summary and reported.
+pub fn compact_session(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    keep_last: usize,
+) -> Result<CompactReport, String> {
+    let metadata = load_metadata(app, session_id)?
+        .ok_or_else(|| format!("Session not found: {session_id}"))?;
+
+    let is_active =
+        |run: &RunEntry| matches!(run.status, RunStatus::Running | RunStatus::Resumable);
+    if metadata.runs.iter().any(is_active) {
+        return Err("Cannot compact a session while a run is in progress".to_string());
+    }
+    if metadata.runs.len() <= keep_last {
+        return Ok(CompactReport::default());
+    }
+
+    let older = &metadata.runs[..metadata.runs.len() - keep_last];
+    let mut turns = Vec::new();
+    let mut skipped_runs = Vec::new();
+    for run in older {
+        // Instant-cancelled runs (undo_send) never had a turn to summarize
+        if run.status == RunStatus::Cancelled && run.assistant_message_id.is_none() {
+            continue;
+        }
+        let message = read_existing_run_log(app, session_id, &run.run_id)
+            .and_then(|lines| parse_run_to_message(&lines, run));
+        match message {
+            Ok((message, _)) => turns.push((run, message.content)),
+            Err(e) => {
+                log::warn!(
+                    "Skipping run {} in compaction of {session_id}: {e}",
+                    run.run_id
+                );
+                skipped_runs.push(run.run_id.clone());
+            }
+        }
+    }
+    let summary_text = compaction_summary(&turns);
+    let summary = summary_run_entry(older);
+
+    // Write the summary as a regular run log so it parses like any other run
+    let path = get_run_log_path(app, session_id, &summary.run_id)?;
+    let lines = [
+        serde_json::json!({
+            "_run_meta": true,
+            "run_id": summary.run_id,
+            "session_id": session_id,
+            "worktree_id": metadata.worktree_id,
+            "user_message_id": summary.user_message_id,
+            "compacted_runs": summary.compacted_runs,
+            "started_at": summary.started_at,
+        }),
+        serde_json::json!({
+            "type": "assistant",
+            "message": {"content": [{"type": "text", "text": summary_text}]},
+        }),
+        serde_json::json!({"type": "result", "subtype": "success", "result": summary_text}),
+    ];
+    let contents: String = lines.iter().map(|line| format!("{line}\n")).collect();
+    fs::write(&path, contents).map_err(|e| format!("Failed to write summary run log: {e}"))?;
+
+    let older_ids: Vec<String> = older.iter().map(|r| r.run_id.clone()).collect();
+    let result = with_metadata_mut(
+        app,
+        session_id,
+        &metadata.worktree_id,
+        &metadata.name,
+        metadata.order,
+        |metadata| {
+            // Re-check under the lock in case a run started meanwhile
+            if metadata.runs.iter().any(is_active) {
+                return Err("Cannot compact a session while a run is in progress".to_string());
+            }
+            metadata.runs.retain(|run| !older_ids.contains(&run.run_id));
+            metadata.runs.insert(0, summary.clone());
+            Ok(())
+        },
+    );
+    if let Err(e) = result {
+        let _ = fs::remove_file(&path);
+        return Err(e);
+    }
+
+    let session_dir = get_session_dir(app, session_id)?;
+    for run_id in &older_ids {
+        for path in run_files(&session_dir, run_id) {
+            if path.exists() {
+                if let Err(e) = fs::remove_file(&path) {
+                    log::warn!("Failed to delete compacted run file {path:?}: {e}");
+                }
+            }
+        }
+    }
+
+    log::trace!(
+        "Compacted {} run(s) of session {session_id} into {} ({} skipped)",
+        older_ids.len(),
+        summary.run_id,
+        skipped_runs.len()
+    );
+    Ok(CompactReport {
+        compacted_runs: older_ids.len(),
+        skipped_runs,
+    })
+}
+
+// ============================================================================
+// Utility Functions
+// ============================================================================
+
+fn now_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovery_action_by_pid_liveness() {
+        assert_eq!(
+            recovery_action(&RunStatus::Running, Some(42), |_| true),
+            Some(RecoveryAction::Resumable)
+        );
+        assert_eq!(
+            recovery_action(&RunStatus::Running, Some(42), |_| false),
+            Some(RecoveryAction::Crashed)
+        );
+        assert_eq!(
+            recovery_action(&RunStatus::Running, None, |_| true),
+            Some(RecoveryAction::Crashed)
+        );
+    }
+
+    #[test]
+    fn test_recovery_action_ignores_finished_runs() {
+        for status in [
+            RunStatus::Completed,
+            RunStatus::Cancelled,
+            RunStatus::Crashed,
+            RunStatus::Resumable,
+        ] {
+            assert_eq!(recovery_action(&status, Some(42), |_| true), None);
+        }
+    }
+
+    #[test]
+    fn test_normalize_labels() {
+        let labels = vec![
+            " ticket-123 ".to_string(),
+            "".to_string(),
+            "experiment-A".to_string(),
+            "ticket-123".to_string(),
+        ];
+        assert_eq!(
+            normalize_labels(&labels),
+            vec!["ticket-123".to_string(), "experiment-A".to_string()]
+        );
+    }
+
+    /// A run entry with only the fields every test needs
+    fn test_run(run_id: &str, status: RunStatus) -> RunEntry {
+        serde_json::from_value(serde_json::json!({
+            "run_id": run_id,
+            "user_message_id": "msg-1",
+            "user_message": "Explain the tailer",
+            "started_at": 1,
+            "status": status,
+        }))
+        .unwrap()
+    }
+
+    /// Metadata for session-1 in worktree-1 holding `runs`
+    fn test_metadata(runs: Vec<RunEntry>) -> SessionMetadata {
+        let mut metadata = SessionMetadata::new(
+            "session-1".to_string(),
+            "worktree-1".to_string(),
+            "Session 1".to_string(),
+            0,
+        );
+        metadata.runs = runs;
+        metadata
+    }
+
+    /// A run log header line for `run_id` with extra `fields`
+    fn run_meta_line(run_id: &str, fields: serde_json::Value) -> String {
+        let mut meta = serde_json::json!({"_run_meta": true, "run_id": run_id});
+        if let (Some(meta), Some(fields)) = (meta.as_object_mut(), fields.as_object()) {
+            meta.extend(fields.clone());
+        }
+        meta.to_string()
+    }
+
+    /// A stream-json assistant line with a single text block
+    fn assistant_text_line(text: &str) -> String {
+        serde_json::json!({
+            "type": "assistant",
+            "message": {"content": [{"type": "text", "text": text}]},
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_thinking_hidden_below_threshold() {
+        assert!(thinking_hidden_for(Some("think"), None, Some("high")));
+        assert!(!thinking_hidden_for(Some("ultrathink"), None, Some("high")));
+        // Effort wins over thinking level
+        assert!(thinking_hidden_for(
+            Some("ultrathink"),
+            Some("low"),
+            Some("medium")
+        ));
+        assert!(!thinking_hidden_for(None, Some("max"), Some("max")));
+        // No threshold, or unknown levels, always show thinking
+        assert!(!thinking_hidden_for(Some("think"), None, None));
+        assert!(!thinking_hidden_for(Some("think"), None, Some("extreme")));
+        assert!(!thinking_hidden_for(None, None, Some("high")));
+    }
+
+    #[test]
+    fn test_hidden_thinking_is_logged_but_not_rebuilt() {
+        let lines = vec![
+            r#"{"_run_meta":true,"run_id":"run-1","thinking_hidden":true}"#.to_string(),
+            r#"{"type":"assistant","message":{"content":[{"type":"thinking","thinking":"Plan it"},{"type":"text","text":"Done."}]}}"#.to_string(),
+        ];
+        let meta: serde_json::Value = serde_json::from_str(&lines[0]).unwrap();
+        let mut run = test_run("run-1", RunStatus::Completed);
+        run.thinking_hidden = run_meta_hides_thinking(&meta);
+        assert!(run.thinking_hidden);
+
+        let (message, _) = parse_run_to_message(&lines, &run).unwrap();
+        assert_eq!(message.content, "Done.");
+        assert!(!message
+            .content_blocks
+            .iter()
+            .any(|b| matches!(b, ContentBlock::Thinking { .. })));
+
+        // The same run log rebuilds its thinking when it isn't hidden
+        run.thinking_hidden = false;
+        let (message, _) = parse_run_to_message(&lines, &run).unwrap();
+        assert!(matches!(
+            &message.content_blocks[0],
+            ContentBlock::Thinking { thinking } if thinking == "Plan it"
+        ));
+    }
+
+    #[test]
+    fn test_parsed_assistant_message_echoes_run_settings() {
+        let mut run = test_run("run-1", RunStatus::Completed);
+        run.model = Some("opus".to_string());
+        run.execution_mode = Some("plan".to_string());
+        run.thinking_level = Some("think".to_string());
+
+        let (message, _) = parse_run_to_message(&[], &run).unwrap();
+        assert_eq!(message.model.as_deref(), Some("opus"));
+        assert_eq!(message.execution_mode.as_deref(), Some("plan"));
+        assert_eq!(message.thinking_level.as_deref(), Some("think"));
+
+        // The model the CLI reported takes precedence over the requested alias
+        run.resolved_model = Some("claude-opus-4-6".to_string());
+        let (message, _) = parse_run_to_message(&[], &run).unwrap();
+        assert_eq!(message.model.as_deref(), Some("claude-opus-4-6"));
+    }
+
+    #[test]
+    fn test_parse_crashed_run_recovers_truncated_final_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run-1.jsonl");
+        let complete = assistant_text_line("First part. ");
+        // Final line cut off mid-object, mid-escape, with no trailing newline
+        let truncated = r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Second \"part\" \u00e9t\u00"#;
+        fs::write(
+            &path,
+            format!("{{\"_run_meta\":true}}\n{complete}\n{truncated}"),
+        )
+        .unwrap();
+
+        let lines: Vec<String> = fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect();
+        let (msg, unparseable_lines) =
+            parse_run_to_message(&lines, &test_run("run-1", RunStatus::Crashed)).unwrap();
+
+        assert_eq!(msg.content, "First part. Second \"part\" ét");
+        assert_eq!(msg.content_blocks.len(), 2);
+        assert_eq!(unparseable_lines, 1);
+    }
+
+    #[test]
+    fn test_recover_truncated_text_edge_cases() {
+        assert!(
+            recover_truncated_text(r#"{"type":"user","message":{"content":[{"text":"hi"#)
+                .is_empty()
+        );
+        assert!(recover_truncated_text("Error: connection reset").is_empty());
+        assert_eq!(
+            recover_truncated_text(
+                r#"{"type":"assistant","message":{"content":[{"type":"text","text":"ends with \"#
+            ),
+            vec!["ends with ".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_check_input_size_boundary() {
+        assert_eq!(max_input_bytes(None), 4096 * 1024);
+        assert_eq!(max_input_bytes(Some(0)), 4096 * 1024);
+        assert_eq!(max_input_bytes(Some(1)), 1024);
+
+        let max = max_input_bytes(Some(1));
+        assert!(check_input_size(&"a".repeat(max), max).is_ok());
+
+        let err = check_input_size(&"a".repeat(max + 1), max).unwrap_err();
+        assert!(err.contains("too large"), "{err}");
+        assert!(err.contains("2 KB, limit is 1 KB"), "{err}");
+    }
+
+    #[test]
+    fn test_build_input_message_sends_message_verbatim() {
+        assert_eq!(
+            build_input_message("Fix the build"),
+            serde_json::json!({
+                "type": "user",
+                "message": {"role": "user", "content": "Fix the build"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_collect_raw_events() {
+        let log = concat!(
+            "{\"_run_meta\":true,\"model\":\"opus\"}\n",
+            "{\"type\":\"system\",\"subtype\":\"init\"}\n",
+            "warning: something on stderr\n",
+            "{\"type\":\"assistant\"}\n",
+            "{\"type\":\"result\"}\n",
+        );
+        let reader = || RunLogReader::from_reader(Box::new(std::io::Cursor::new(log)));
+
+        let all = collect_raw_events(reader(), None).unwrap();
+        assert_eq!(all.meta.unwrap()["model"], "opus");
+        let types: Vec<_> = all.events.iter().map(|e| e["type"].clone()).collect();
+        assert_eq!(types, ["system", "assistant", "result"]);
+
+        let limited = collect_raw_events(reader(), Some(2)).unwrap();
+        assert!(limited.meta.is_some());
+        assert_eq!(limited.events.len(), 2);
+    }
+
+    #[test]
+    fn test_agent_ids_from_metadata() {
+        let mut first = test_run("run-1", RunStatus::Crashed);
+        first.claude_session_id = Some("claude-a".to_string());
+        let second = test_run("run-2", RunStatus::Crashed);
+        let mut metadata = test_metadata(vec![first, second]);
+
+        // Session-level id missing: fall back to the newest run that reported one
+        let ids = AgentIds::from_metadata(&metadata);
+        assert_eq!(ids.claude_session_id.as_deref(), Some("claude-a"));
+        assert_eq!(ids.runs.len(), 2);
+        assert_eq!(ids.runs[1].run_id, "run-2");
+        assert_eq!(ids.runs[1].claude_session_id, None);
+
+        metadata.claude_session_id = Some("claude-b".to_string());
+        let ids = AgentIds::from_metadata(&metadata);
+        assert_eq!(ids.claude_session_id.as_deref(), Some("claude-b"));
+    }
+
+    #[test]
+    fn test_session_summary_from_metadata() {
+        let mut metadata = test_metadata(vec![]);
+        let empty = SessionSummary::from_metadata(&metadata);
+        assert_eq!(empty.run_count, 0);
+        assert_eq!(empty.last_activity_at, metadata.created_at);
+        assert!(!empty.is_active);
+
+        let mut first = test_run("run-1", RunStatus::Crashed);
+        first.started_at = 100;
+        first.ended_at = Some(150);
+        first.usage = Some(UsageData {
+            input_tokens: 10,
+            output_tokens: 5,
+            cache_read_input_tokens: 1000,
+            cache_creation_input_tokens: 0,
+        });
+        let mut second = test_run("run-2", RunStatus::Resumable);
+        second.started_at = 120;
+        metadata.runs = vec![first, second];
+
+        let summary = SessionSummary::from_metadata(&metadata);
+        assert_eq!(summary.id, "session-1");
+        assert_eq!(summary.worktree_id, "worktree-1");
+        assert_eq!(summary.run_count, 2);
+        assert_eq!(summary.last_activity_at, 150);
+        assert_eq!(summary.total_tokens, 15);
+        assert!(summary.is_active);
+    }
+
+    #[test]
+    fn test_summary_run_entry_preserves_resume_state() {
+        let mut first = test_run("run-1", RunStatus::Completed);
+        first.started_at = 10;
+        first.claude_session_id = Some("claude-a".to_string());
+        first.usage = Some(UsageData {
+            input_tokens: 10,
+            output_tokens: 5,
+            ..Default::default()
+        });
+        let mut earlier_summary = first.clone();
+        earlier_summary.run_id = "run-0".to_string();
+        earlier_summary.compacted_runs = Some(3);
+        let mut second = first.clone();
+        second.run_id = "run-2".to_string();
+        second.ended_at = Some(50);
+        second.claude_session_id = None;
+
+        let summary = summary_run_entry(&[earlier_summary, first, second]);
+        assert_eq!(summary.status, RunStatus::Completed);
+        assert_eq!(summary.compacted_runs, Some(5));
+        assert_eq!(summary.started_at, 10);
+        assert_eq!(summary.ended_at, Some(50));
+        assert_eq!(summary.claude_session_id.as_deref(), Some("claude-a"));
+        assert_eq!(summary.usage.as_ref().unwrap().input_tokens, 30);
+        assert!(summary.assistant_message_id.is_some());
+    }
+
+    #[test]
+    fn test_compaction_summary_and_parsed_message() {
+        let run = test_run("run-1", RunStatus::Completed);
+        let mut earlier = run.clone();
+        earlier.compacted_runs = Some(2);
+
+        let text = compaction_summary(&[
+            (&earlier, "Earlier summary".to_string()),
+            (&run, "It polls the file.".to_string()),
+        ]);
+        assert_eq!(
+            text,
+            "Earlier summary\n\n---\n\n**User:** Explain the tailer\n\n**Assistant:** It polls the file."
+        );
+
+        // The summary run's log parses back into a message marked as compacted
+        let summary = summary_run_entry(&[run]);
+        let lines = vec![assistant_text_line(&text)];
+        let (message, _) = parse_run_to_message(&lines, &summary).unwrap();
+        assert_eq!(message.content, text);
+        assert_eq!(message.compacted_runs, Some(1));
+        assert_eq!(message.id, summary.assistant_message_id.unwrap());
+    }
+
+    #[test]
+    fn test_rebuild_metadata_from_run_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        let header = |run_id: &str, started_at: u64| {
+            run_meta_line(
+                run_id,
+                serde_json::json!({
+                    "worktree_id": "worktree-1",
+                    "user_message_id": format!("msg-{run_id}"),
+                    "model": "opus",
+                    "execution_mode": "build",
+                    "started_at": started_at,
+                }),
+            )
+        };
+        // Completed run: has a result event
+        let result = serde_json::json!({
+            "type": "result",
+            "session_id": "claude-1",
+            "usage": {"input_tokens": 3, "output_tokens": 4},
+        });
+        fs::write(
+            dir.path().join("run-a.jsonl"),
+            format!("{}\n{result}\n", header("run-a", 10)),
+        )
+        .unwrap();
+        fs::File::options()
+            .write(true)
+            .open(dir.path().join("run-a.jsonl"))
+            .unwrap()
+            .set_modified(UNIX_EPOCH + std::time::Duration::from_secs(15))
+            .unwrap();
+        // Crashed run: header only; its input file still holds the user message
+        fs::write(
+            dir.path().join("run-b.jsonl"),
+            format!("{}\n", header("run-b", 20)),
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("run-b.input.jsonl"),
+            build_input_message("Fix the bug").to_string(),
+        )
+        .unwrap();
+        // No header: skipped
+        fs::write(dir.path().join("run-c.jsonl"), "garbage\n").unwrap();
+
+        let (metadata, report) = rebuild_metadata("session-1", dir.path()).unwrap();
+        assert!(report.rebuilt);
+        assert_eq!(report.skipped_files, vec!["run-c.jsonl".to_string()]);
+        assert_eq!(metadata.worktree_id, "worktree-1");
+        assert_eq!(metadata.created_at, 10);
+        assert_eq!(metadata.claude_session_id.as_deref(), Some("claude-1"));
+
+        let runs = &metadata.runs;
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].run_id, "run-a");
+        assert_eq!(runs[0].status, RunStatus::Completed);
+        assert_eq!(runs[0].user_message_id, "msg-run-a");
+        assert_eq!(runs[0].model.as_deref(), Some("opus"));
+        assert_eq!(runs[0].usage.as_ref().unwrap().output_tokens, 4);
+        // Ended when its log was last written
+        assert_eq!(runs[0].ended_at, Some(15));
+        assert_eq!(runs[1].status, RunStatus::Crashed);
+        assert_eq!(runs[1].user_message, "Fix the bug");
+        assert_eq!(report.recovered_runs.len(), 2);
+    }
+
+    #[test]
+    fn test_repair_replaces_corrupt_metadata_and_keeps_a_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let header = run_meta_line(
+            "run-a",
+            serde_json::json!({
+                "worktree_id": "worktree-1",
+                "user_message_id": "msg-a",
+                "started_at": 10,
+            }),
+        );
+        fs::write(dir.path().join("run-a.jsonl"), format!("{header}\n")).unwrap();
+        let path = dir.path().join("metadata.json");
+        fs::write(&path, "{\"id\": \"session-1\", trunc").unwrap();
+        assert!(super::super::storage::read_metadata_file(&path).is_err());
+
+        let (metadata, _) = rebuild_metadata("session-1", dir.path()).unwrap();
+        backup_unreadable_metadata(&path).unwrap();
+        super::super::storage::write_metadata_file(&path, &metadata).unwrap();
+
+        let repaired = super::super::storage::read_metadata_file(&path)
+            .unwrap()
+            .unwrap();
+        assert_eq!(repaired.runs.len(), 1);
+        assert_eq!(
+            fs::read_to_string(path.with_extension("json.corrupt")).unwrap(),
+            "{\"id\": \"session-1\", trunc"
+        );
+
+        // Deleted metadata: nothing to back up
+        fs::remove_file(&path).unwrap();
+        backup_unreadable_metadata(&path).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_run_id_from_log_name() {
+        assert_eq!(run_id_from_log_name("abc.jsonl"), Some("abc"));
+        assert_eq!(run_id_from_log_name("abc.jsonl.gz"), Some("abc"));
+        assert_eq!(run_id_from_log_name("abc.input.jsonl"), None);
+        assert_eq!(run_id_from_log_name("metadata.json"), None);
+    }
+
+    #[test]
+    fn test_cancel_run_only_changes_targeted_run() {
+        let mut metadata = test_metadata(vec![
+            test_run("run-1", RunStatus::Running),
+            test_run("run-2", RunStatus::Running),
+            test_run("run-3", RunStatus::Completed),
+        ]);
+
+        assert!(cancel_run_in_metadata(&mut metadata, "run-1", 99));
+        assert_eq!(metadata.runs[0].status, RunStatus::Cancelled);
+        assert_eq!(metadata.runs[0].ended_at, Some(99));
+        assert!(metadata.runs[0].cancelled);
+        assert_eq!(metadata.runs[1].status, RunStatus::Running);
+        assert!(!metadata.runs[1].cancelled);
+
+        // Finished and unknown runs are left alone
+        assert!(!cancel_run_in_metadata(&mut metadata, "run-3", 100));
+        assert_eq!(metadata.runs[2].status, RunStatus::Completed);
+        assert!(!cancel_run_in_metadata(&mut metadata, "missing", 100));
+    }
+
+    #[test]
+    fn test_rekey_export_round_trips_messages() {
+        let mut first = test_run("run-1", RunStatus::Completed);
+        first.assistant_message_id = Some("assistant-1".to_string());
+        first.claude_session_id = Some("claude-1".to_string());
+        let mut second = test_run("run-2", RunStatus::Resumable);
+        second.pid = Some(42);
+        second.retried_from = Some("run-1".to_string());
+        let mut metadata = test_metadata(vec![first, second]);
+        metadata.claude_session_id = Some("claude-2".to_string());
+
+        let log = |run: &RunEntry, text: &str| {
+            vec![
+                run_meta_line(
+                    &run.run_id,
+                    serde_json::json!({
+                        "session_id": "session-1",
+                        "user_message_id": run.user_message_id,
+                    }),
+                ),
+                assistant_text_line(text),
+            ]
+        };
+        let run_logs = [("run-1", "First answer"), ("run-2", "Second answer")]
+            .iter()
+            .zip(&metadata.runs)
+            .map(|((id, text), run)| (id.to_string(), log(run, text)))
+            .collect();
+        let original = SessionExport {
+            version: SESSION_EXPORT_VERSION,
+            metadata,
+            run_logs,
+        };
+
+        // Serialized and read back, as import does
+        let json = serde_json::to_string(&original).unwrap();
+        let parsed: SessionExport = serde_json::from_str(&json).unwrap();
+        let imported = rekey_export(parsed, "session-2");
+
+        assert_eq!(imported.metadata.id, "session-2");
+        let runs = &imported.metadata.runs;
+        assert!(runs
+            .iter()
+            .all(|r| r.run_id != "run-1" && r.run_id != "run-2"));
+        assert_eq!(runs[1].retried_from.as_ref(), Some(&runs[0].run_id));
+        assert_eq!(runs[1].status, RunStatus::Crashed);
+        assert_eq!(runs[1].pid, None);
+        assert_ne!(runs[0].assistant_message_id.as_deref(), Some("assistant-1"));
+        // Nothing in the copy can resume the original's CLI conversation
+        assert_eq!(imported.metadata.claude_session_id, None);
+        assert!(runs.iter().all(|r| r.claude_session_id.is_none()));
+
+        // Written to disk and read back, as import_session_json does
+        let dir = tempfile::tempdir().unwrap();
+        write_imported_session(dir.path(), &imported).unwrap();
+        let reloaded = super::super::storage::read_metadata_file(&dir.path().join("metadata.json"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(reloaded.id, "session-2");
+        assert_eq!(reloaded.runs.len(), 2);
+
+        for (old_run, new_run) in original.metadata.runs.iter().zip(&reloaded.runs) {
+            let new_lines: Vec<String> =
+                fs::read_to_string(dir.path().join(format!("{}.jsonl", new_run.run_id)))
+                    .unwrap()
+                    .lines()
+                    .map(String::from)
+                    .collect();
+            let header: serde_json::Value = serde_json::from_str(&new_lines[0]).unwrap();
+            assert_eq!(header["run_id"], new_run.run_id.as_str());
+            assert_eq!(header["session_id"], "session-2");
+            assert_eq!(header["user_message_id"], new_run.user_message_id.as_str());
+
+            let (before, _) =
+                parse_run_to_message(&original.run_logs[&old_run.run_id], old_run).unwrap();
+            let (after, _) = parse_run_to_message(&new_lines, new_run).unwrap();
+            assert_eq!(after.content, before.content);
+            assert_eq!(after.content_blocks.len(), before.content_blocks.len());
+        }
+    }
+
+    #[test]
+    fn test_current_git_branch() {
+        let plain = tempfile::tempdir().unwrap();
+        assert_eq!(current_git_branch(plain.path().to_str().unwrap()), None);
+
+        let repo = tempfile::tempdir().unwrap();
+        let dir = repo.path().to_str().unwrap();
+        let git = |args: &[&str]| {
+            silent_command("git")
+                .args(args)
+                .current_dir(dir)
+                .status()
+                .map(|s| s.success())
+                .unwrap_or(false)
+        };
+        if !git(&["init", "-q", "-b", "feature/runs"]) {
+            return; // git unavailable in this environment
+        }
+        assert!(git(&[
+            "-c",
+            "user.name=Jean",
+            "-c",
+            "user.email=jean@example.com",
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "init",
+        ]));
+        assert_eq!(current_git_branch(dir).as_deref(), Some("feature/runs"));
+    }
+
+    fn assistant_message(
+        content_blocks: Vec<ContentBlock>,
+        tool_calls: Vec<ToolCall>,
+    ) -> ChatMessage {
+        ChatMessage {
+            id: Uuid::new_v4().to_string(),
+            role: MessageRole::Assistant,
+            timestamp: now_timestamp(),
+            tool_calls,
+            content_blocks,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_run_content_hash_ignores_ids_and_timestamps() {
+        let tool = |id: &str| ToolCall {
+            id: id.to_string(),
+            name: "Read".to_string(),
+            input: serde_json::json!({"file_path": "src/main.rs"}),
+            output: None,
+            parent_tool_use_id: None,
+            is_error: false,
+            duration_ms: None,
+        };
+        let blocks = |id: &str| {
+            vec![
+                ContentBlock::Text {
+                    text: "Looking".to_string(),
+                },
+                ContentBlock::ToolUse {
+                    tool_call_id: id.to_string(),
+                },
+            ]
+        };
+
+        let a = assistant_message(blocks("tool-a"), vec![tool("tool-a")]);
+        let b = assistant_message(blocks("tool-b"), vec![tool("tool-b")]);
+        assert_eq!(run_content_hash("hi", &a), run_content_hash("hi", &b));
+        assert_ne!(run_content_hash("hi", &a), run_content_hash("hello", &a));
+    }
+
+    #[test]
+    fn test_run_content_hash_is_order_sensitive() {
+        let text = |t: &str| ContentBlock::Text {
+            text: t.to_string(),
+        };
+        let a = assistant_message(vec![text("one"), text("two")], vec![]);
+        let b = assistant_message(vec![text("two"), text("one")], vec![]);
+        assert_ne!(run_content_hash("hi", &a), run_content_hash("hi", &b));
+    }
+
+    #[test]
+    fn test_match_snippet_is_case_insensitive() {
+        let text = format!(
+            "{}Error: ENOENT src/Main.rs{}",
+            "a ".repeat(50),
+            " b".repeat(50)
+        );
+        let snippet = match_snippet(&text, "main.rs").unwrap();
+        assert!(snippet.contains("src/Main.rs"), "{snippet}");
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+
+        assert_eq!(
+            match_snippet("short text", "short").as_deref(),
+            Some("short text")
+        );
+        assert_eq!(match_snippet("short text", "missing"), None);
+    }
+
+    #[test]
+    fn test_rank_hits_by_session_puts_busiest_session_first() {
+        let hit = |session_id: &str, run_id: &str| SearchHit {
+            session_id: session_id.to_string(),
+            worktree_id: "w".to_string(),
+            session_name: "name".to_string(),
+            run_id: run_id.to_string(),
+            role: "assistant".to_string(),
+            block_type: "text".to_string(),
+            tool_name: None,
+            snippet: String::new(),
+        };
+        let mut hits = vec![hit("a", "1"), hit("b", "2"), hit("b", "3"), hit("c", "4")];
+        rank_hits_by_session(&mut hits);
+        let order: Vec<_> = hits.iter().map(|h| h.run_id.as_str()).collect();
+        assert_eq!(order, vec!["2", "3", "1", "4"]);
+    }
+
+    #[test]
+    fn test_match_snippet_keeps_original_text_when_lowercasing_resizes() {
+        // 'İ' grows and 'ẞ' shrinks when lowercased, so byte offsets differ
+        assert_eq!(match_snippet("İẞ", "ß").as_deref(), Some("İẞ"));
+        assert_eq!(
+            match_snippet("İstanbul ẞtraße Needle", "needle").as_deref(),
+            Some("İstanbul ẞtraße Needle")
+        );
+        let text = format!("{}İẞ Needle{}", "ẞ".repeat(100), "İ".repeat(100));
+        let snippet = match_snippet(&text, "i\u{307}ß needle").unwrap();
+        assert!(snippet.contains("İẞ Needle"), "{snippet}");
+        assert_eq!(match_snippet("İẞ", ""), None);
+    }
+
+    #[test]
+    fn test_searchable_fields_include_tool_inputs_and_outputs() {
+        let assistant = serde_json::json!({
+            "type": "assistant",
+            "message": {"content": [
+                {"type": "text", "text": "Running tests"},
+                {"type": "tool_use", "id": "t1", "name": "Bash", "input": {"command": "cargo test"}}
+            ]}
+        });
+        let fields = searchable_fields(&assistant);
+        assert_eq!(fields.len(), 2);
+        assert_eq!(
+            fields[1],
+            (
+                "assistant",
+                "tool_input",
+                Some("Bash".to_string()),
+                "cargo test".to_string()
+            )
+        );
+
+        let user = serde_json::json!({
+            "type": "user",
+            "message": {"content": [
+                {"type": "tool_result", "tool_use_id": "t1", "content": "test result: ok"}
+            ]}
+        });
+        assert_eq!(
+            searchable_fields(&user),
+            vec![("user", "tool_output", None, "test result: ok".to_string())]
+        );
+    }
+
+    fn candidate(run_id: &str, finished_at: u64, bytes: u64) -> PruneCandidate {
+        PruneCandidate {
+            session_id: "s".to_string(),
+            run_id: run_id.to_string(),
+            finished_at,
+            bytes,
+        }
+    }
+
+    #[test]
+    fn test_select_runs_to_prune_by_age() {
+        let day = 24 * 60 * 60;
+        let now = 100 * day;
+        let candidates = vec![
+            candidate("recent", now - day, 10),
+            candidate("old", now - 40 * day, 10),
+        ];
+
+        let selected = select_runs_to_prune(&candidates, now, Some(30), None);
+        assert_eq!(selected, vec![1]);
+        assert!(select_runs_to_prune(&candidates, now, None, None).is_empty());
+    }
+
+    #[test]
+    fn test_select_runs_to_prune_by_size_oldest_first() {
+        let candidates = vec![
+            candidate("newest", 300, 100),
+            candidate("oldest", 100, 100),
+            candidate("middle", 200, 100),
+        ];
+
+        // 300 bytes total, budget 150: drop the two oldest
+        let selected = select_runs_to_prune(&candidates, 1000, None, Some(150));
+        assert_eq!(selected, vec![1, 2]);
+
+        assert!(select_runs_to_prune(&candidates, 1000, None, Some(300)).is_empty());
+    }
+
+    #[test]
+    fn test_run_log_reader_classifies_lines() {
+        let log = concat!(
+            r#"{"_run_meta":true,"run_id":"r1"}"#,
+            "\n\n",
+            r#"{"type":"assistant"}"#,
+            "\nWarning: something on stderr\n",
+        );
+        let reader = RunLogReader::from_reader(Box::new(std::io::Cursor::new(log.as_bytes())));
+        let entries: Vec<RunLogEntry> = reader.map(Result::unwrap).collect();
+
+        assert_eq!(
+            entries,
+            vec![
+                RunLogEntry::Meta(serde_json::json!({"_run_meta": true, "run_id": "r1"})),
+                RunLogEntry::Message(serde_json::json!({"type": "assistant"})),
+                RunLogEntry::Stderr("Warning: something on stderr".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_stderr_tail_skips_json_lines() {
+        let lines: Vec<String> = vec![
+            r#"{"type":"system","subtype":"init"}"#.to_string(),
+            "Error: connection reset".to_string(),
+            "".to_string(),
+            r#"{"type":"assistant"}"#.to_string(),
+            "    at fetch (cli.js:1:1)".to_string(),
+        ];
+
+        assert_eq!(
+            extract_stderr_tail(&lines, 4096).as_deref(),
+            Some("Error: connection reset\n    at fetch (cli.js:1:1)")
+        );
+        assert_eq!(extract_stderr_tail(&lines[..1], 4096), None);
+    }
+
+    #[test]
+    fn test_extract_stderr_tail_caps_length_keeping_newest() {
+        let lines: Vec<String> = vec!["first line".to_string(), "second".to_string()];
+
+        assert_eq!(extract_stderr_tail(&lines, 6).as_deref(), Some("second"));
+        assert_eq!(
+            extract_stderr_tail(&lines, 11).as_deref(),
+            Some("line\nsecond")
+        );
+    }
 }
@@ -0,0 +1,209 @@
+//! Headless subscription to a run's events
+//!
+//! Tails a run log on a background thread and forwards each parsed Claude
+//! stream-json event over an async channel, without going through the Tauri
+//! event bus. Served to external integrations (debugging dashboards) over the
+//! HTTP server's `/ws/run-events` route, and used by tests that want to
+//! observe a run as it happens.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc;
+
+use super::run_log;
+use super::storage::load_metadata;
+use super::tail::{NdjsonTailer, POLL_INTERVAL};
+use super::types::RunStatus;
+
+/// How often an idle subscription re-checks whether the run has finished
+const FINISH_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Signals a subscription's tailer thread to stop
+#[derive(Clone, Default)]
+pub struct StopToken(Arc<AtomicBool>);
+
+impl StopToken {
+    pub fn stop(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A live subscription to a run's events.
+///
+/// `events` yields stream-json events in file order and closes when the run
+/// finishes, the log can't be read, or the subscription is stopped.
+/// Dropping the subscription stops its tailer thread.
+pub struct RunSubscription {
+    pub events: mpsc::UnboundedReceiver<serde_json::Value>,
+    stop: StopToken,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl RunSubscription {
+    /// Stop the subscription and wait for its tailer thread to exit
+    pub fn unsubscribe(mut self) {
+        self.stop.stop();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for RunSubscription {
+    fn drop(&mut self) {
+        self.stop.stop();
+    }
+}
+
+/// Subscribe to a run's events, starting from the beginning of its log.
+///
+/// Events already written are delivered first, then new ones as the CLI
+/// writes them, until the run is no longer running or resumable.
+pub fn subscribe_run_events(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    run_id: &str,
+) -> Result<RunSubscription, String> {
+    let path = run_log::get_run_log_path(app, session_id, run_id)?;
+    let tailer = NdjsonTailer::new_from_start(&path)?;
+
+    let app = app.clone();
+    let session_id = session_id.to_string();
+    let run_id = run_id.to_string();
+    let is_finished = move || match load_metadata(&app, &session_id) {
+        Ok(Some(metadata)) => metadata
+            .find_run(&run_id)
+            .is_none_or(|run| !matches!(run.status, RunStatus::Running | RunStatus::Resumable)),
+        _ => true,
+    };
+
+    Ok(spawn_forwarder(tailer, is_finished))
+}
+
+/// Forward events from `tailer` on a background thread until `is_finished`
+/// reports the run is over, the final `result` event is seen, or the
+/// subscription is stopped or its receiver dropped.
+fn spawn_forwarder<F>(mut tailer: NdjsonTailer, mut is_finished: F) -> RunSubscription
+where
+    F: FnMut() -> bool + Send + 'static,
+{
+    let (tx, events) = mpsc::unbounded_channel();
+    let stop = StopToken::default();
+    let thread_stop = stop.clone();
+
+    let handle = std::thread::spawn(move || {
+        let mut last_finish_check = Instant::now();
+        let mut finished = false;
+
+        while !thread_stop.is_stopped() {
+            let lines = match tailer.poll() {
+                Ok(lines) => lines,
+                Err(e) => {
+                    log::warn!("Run subscription stopped, failed to read log: {e}");
+                    return;
+                }
+            };
+            let idle = lines.is_empty();
+
+            for line in lines {
+                let event = match serde_json::from_str::<serde_json::Value>(&line) {
+                    Ok(event) => event,
+                    Err(_) => continue, // stderr redirected into the log
+                };
+                if event.get("_run_meta").and_then(|v| v.as_bool()) == Some(true) {
+                    continue;
+                }
+
+                let is_result = event.get("type").and_then(|v| v.as_str()) == Some("result");
+                if tx.send(event).is_err() {
+                    return; // Receiver dropped
+                }
+                if is_result {
+                    return;
+                }
+            }
+
+            if idle {
+                // A finished run gets one more pass to drain lines written before it ended
+                if finished {
+                    return;
+                }
+                if last_finish_check.elapsed() >= FINISH_CHECK_INTERVAL {
+                    finished = is_finished();
+                    last_finish_check = Instant::now();
+                }
+                if !tailer.is_watched() {
+                    std::thread::sleep(POLL_INTERVAL);
+                }
+            }
+        }
+    });
+
+    RunSubscription {
+        events,
+        stop,
+        handle: Some(handle),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_forwards_events_until_result() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.jsonl");
+        let mut file = std::fs::File::create(&path).unwrap();
+        writeln!(file, r#"{{"_run_meta":true,"run_id":"run-1"}}"#).unwrap();
+        writeln!(file, r#"{{"type":"system","subtype":"init"}}"#).unwrap();
+
+        let mut sub = spawn_forwarder(NdjsonTailer::new_from_start(&path).unwrap(), || false);
+        assert_eq!(sub.events.blocking_recv().unwrap()["type"], "system");
+
+        // Lines written after subscribing are forwarded too; stderr lines are skipped
+        writeln!(file, "warning: not json").unwrap();
+        writeln!(file, r#"{{"type":"assistant"}}"#).unwrap();
+        writeln!(file, r#"{{"type":"result"}}"#).unwrap();
+        writeln!(file, r#"{{"type":"ignored"}}"#).unwrap();
+        file.flush().unwrap();
+
+        assert_eq!(sub.events.blocking_recv().unwrap()["type"], "assistant");
+        assert_eq!(sub.events.blocking_recv().unwrap()["type"], "result");
+        assert!(sub.events.blocking_recv().is_none());
+    }
+
+    #[test]
+    fn test_stop_token_ends_subscription() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.jsonl");
+        std::fs::write(&path, "").unwrap();
+
+        let sub = spawn_forwarder(NdjsonTailer::new_from_start(&path).unwrap(), || false);
+        let token = sub.stop.clone();
+        token.stop();
+        assert!(token.is_stopped());
+
+        // Joins the tailer thread; would hang if the stop were ignored
+        sub.unsubscribe();
+    }
+
+    #[test]
+    fn test_finished_run_closes_channel() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.jsonl");
+        std::fs::write(&path, "{\"type\":\"assistant\"}\n").unwrap();
+
+        let mut sub = spawn_forwarder(NdjsonTailer::new_from_start(&path).unwrap(), || true);
+        assert_eq!(sub.events.blocking_recv().unwrap()["type"], "assistant");
+        assert!(sub.events.blocking_recv().is_none());
+    }
+}
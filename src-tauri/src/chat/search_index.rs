@@ -0,0 +1,332 @@
+//! On-disk inverted index over run logs for `search_sessions`
+//!
+//! Maps lowercased word tokens to the finished runs whose logs contain them,
+//! so a search only reads the runs that can match. Runs are added as they
+//! complete (`index_run`) or in bulk by `rebuild_search_index`, which saves
+//! its progress as it goes and resumes if it was interrupted.
+//!
+//! Only whole-word prefixes are looked up: in an indexed run, each word of the
+//! query must start one of the run's words ("pars" finds "parser", "bug" does
+//! not find "debugging"). Runs missing from the index (still running, or
+//! finished before the index existed) are scanned linearly by
+//! `search_sessions`, which matches any substring.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::run_log::{searchable_fields, RunLogEntry, RunLogReader};
+use super::storage::{get_sessions_dir, list_all_session_ids, load_metadata};
+use super::types::{RunEntry, RunStatus};
+
+/// Format version of the index file; other versions are ignored and rebuilt
+const SEARCH_INDEX_VERSION: u32 = 1;
+
+/// Runs indexed between saves during a rebuild
+const REBUILD_SAVE_INTERVAL: usize = 50;
+
+/// Serializes read-modify-write cycles on the index file
+static SEARCH_INDEX_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Inverted index from tokens to the runs containing them
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SearchIndex {
+    version: u32,
+    /// Set once a rebuild has indexed every finished run
+    complete: bool,
+    /// Indexed runs as (session_id, run_id); postings refer to them by position
+    runs: Vec<(String, String)>,
+    /// Lowercased token -> positions in `runs`
+    postings: BTreeMap<String, BTreeSet<u32>>,
+}
+
+/// Result of a search index rebuild
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchIndexStats {
+    /// Runs added by this rebuild
+    pub indexed_runs: usize,
+    /// Runs in the index after the rebuild
+    pub total_runs: usize,
+    /// Distinct tokens in the index
+    pub tokens: usize,
+}
+
+/// Split text into lowercased alphanumeric tokens (same lowercasing as `match_snippet`)
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            current.push(c);
+        } else if !current.is_empty() {
+            tokens.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+impl SearchIndex {
+    fn new() -> Self {
+        Self {
+            version: SEARCH_INDEX_VERSION,
+            ..Default::default()
+        }
+    }
+
+    /// Runs present in the index
+    pub fn indexed_runs(&self) -> HashSet<(&str, &str)> {
+        self.runs
+            .iter()
+            .map(|(session_id, run_id)| (session_id.as_str(), run_id.as_str()))
+            .collect()
+    }
+
+    /// Add a run given the searchable texts of its log
+    fn add_run<'t>(
+        &mut self,
+        session_id: &str,
+        run_id: &str,
+        texts: impl IntoIterator<Item = &'t str>,
+    ) {
+        let position = self.runs.len() as u32;
+        self.runs.push((session_id.to_string(), run_id.to_string()));
+        for text in texts {
+            for token in tokenize(text) {
+                self.postings.entry(token).or_default().insert(position);
+            }
+        }
+    }
+
+    /// Indexed runs whose logs may contain `query_lower`.
+    ///
+    /// Every query token must be a prefix of some token of the run, found with
+    /// a range lookup rather than a scan of the whole vocabulary (callers still
+    /// verify the match). Returns None when the query has no tokens to look up.
+    pub fn candidates(&self, query_lower: &str) -> Option<HashSet<(&str, &str)>> {
+        let tokens = tokenize(query_lower);
+        if tokens.is_empty() {
+            return None;
+        }
+
+        let mut matching: Option<BTreeSet<u32>> = None;
+        for token in &tokens {
+            let runs: BTreeSet<u32> = self
+                .postings
+                .range::<str, _>(token.as_str()..)
+                .take_while(|(indexed, _)| indexed.starts_with(token.as_str()))
+                .flat_map(|(_, runs)| runs.iter().copied())
+                .collect();
+            matching = Some(match matching {
+                Some(previous) => previous.intersection(&runs).copied().collect(),
+                None => runs,
+            });
+        }
+
+        Some(
+            matching
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|position| self.runs.get(position as usize))
+                .map(|(session_id, run_id)| (session_id.as_str(), run_id.as_str()))
+                .collect(),
+        )
+    }
+}
+
+/// Path of the index file: sessions/search-index.json
+fn get_search_index_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_sessions_dir(app)?.join("search-index.json"))
+}
+
+/// Read an index file; a missing, unreadable, or outdated index yields None
+fn read_index_file(path: &Path) -> Option<SearchIndex> {
+    let file = File::open(path).ok()?;
+    match serde_json::from_reader::<_, SearchIndex>(BufReader::new(file)) {
+        Ok(index) if index.version == SEARCH_INDEX_VERSION => Some(index),
+        Ok(index) => {
+            log::trace!("Ignoring search index version {}", index.version);
+            None
+        }
+        Err(e) => {
+            log::warn!("Ignoring unreadable search index {path:?}: {e}");
+            None
+        }
+    }
+}
+
+/// Atomically write an index file (temp file + sync + rename)
+fn write_index_file(path: &Path, index: &SearchIndex) -> Result<(), String> {
+    let temp_path = path.with_extension("tmp");
+    let file =
+        File::create(&temp_path).map_err(|e| format!("Failed to create search index: {e}"))?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer(&mut writer, index)
+        .map_err(|e| format!("Failed to write search index: {e}"))?;
+    let file = writer
+        .into_inner()
+        .map_err(|e| format!("Failed to flush search index: {e}"))?;
+    file.sync_all()
+        .map_err(|e| format!("Failed to sync search index: {e}"))?;
+    fs::rename(&temp_path, path).map_err(|e| format!("Failed to save search index: {e}"))
+}
+
+/// Load the search index, if one has been built
+pub fn load_search_index(app: &AppHandle) -> Option<SearchIndex> {
+    let _guard = SEARCH_INDEX_LOCK.lock().unwrap();
+    read_index_file(&get_search_index_path(app).ok()?)
+}
+
+/// Whether a run's log is final and can be indexed
+fn is_finished(run: &RunEntry) -> bool {
+    !matches!(run.status, RunStatus::Running | RunStatus::Resumable)
+}
+
+/// Read a run's log and add it to `index`
+fn add_run_log(
+    app: &AppHandle,
+    index: &mut SearchIndex,
+    session_id: &str,
+    run_id: &str,
+) -> Result<(), String> {
+    let mut texts = Vec::new();
+    for entry in RunLogReader::for_run(app, session_id, run_id)?.map_while(Result::ok) {
+        if let RunLogEntry::Message(msg) = entry {
+            texts.extend(
+                searchable_fields(&msg)
+                    .into_iter()
+                    .map(|(_, _, _, text)| text),
+            );
+        }
+    }
+    index.add_run(session_id, run_id, texts.iter().map(String::as_str));
+    Ok(())
+}
+
+/// Add a finished run to the index, creating the index if needed.
+/// Called when a run completes; failures only make searches slower.
+pub fn index_run(app: &AppHandle, session_id: &str, run_id: &str) -> Result<(), String> {
+    let _guard = SEARCH_INDEX_LOCK.lock().unwrap();
+    let path = get_search_index_path(app)?;
+    let mut index = read_index_file(&path).unwrap_or_else(SearchIndex::new);
+    if index.indexed_runs().contains(&(session_id, run_id)) {
+        return Ok(());
+    }
+
+    add_run_log(app, &mut index, session_id, run_id)?;
+    write_index_file(&path, &index)?;
+    log::trace!("Indexed run {run_id} of session {session_id} for search");
+    Ok(())
+}
+
+/// Index every finished run, starting over unless a previous rebuild was
+/// interrupted (its progress is saved every few runs and resumed).
+pub fn rebuild_search_index(app: &AppHandle) -> Result<SearchIndexStats, String> {
+    let _guard = SEARCH_INDEX_LOCK.lock().unwrap();
+    let path = get_search_index_path(app)?;
+    let mut index = match read_index_file(&path) {
+        Some(index) if !index.complete => {
+            log::trace!("Resuming search index rebuild ({} runs)", index.runs.len());
+            index
+        }
+        _ => SearchIndex::new(),
+    };
+    let mut indexed: HashSet<(String, String)> = index.runs.iter().cloned().collect();
+
+    let mut added = 0;
+    for session_id in list_all_session_ids(app)? {
+        let Some(metadata) = load_metadata(app, &session_id)? else {
+            continue;
+        };
+        for run in metadata.runs.iter().filter(|run| is_finished(run)) {
+            let key = (session_id.clone(), run.run_id.clone());
+            if indexed.contains(&key) {
+                continue;
+            }
+            if let Err(e) = add_run_log(app, &mut index, &session_id, &run.run_id) {
+                log::warn!("Skipping run {} in search index: {e}", run.run_id);
+                continue;
+            }
+            indexed.insert(key);
+            added += 1;
+            if added % REBUILD_SAVE_INTERVAL == 0 {
+                write_index_file(&path, &index)?;
+            }
+        }
+    }
+
+    index.complete = true;
+    write_index_file(&path, &index)?;
+    log::trace!(
+        "Rebuilt search index: {added} run(s) added, {} total",
+        index.runs.len()
+    );
+    Ok(SearchIndexStats {
+        indexed_runs: added,
+        total_runs: index.runs.len(),
+        tokens: index.postings.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_lowercases_and_splits_on_punctuation() {
+        assert_eq!(
+            tokenize("Fix the `parse_usage()` bug, ÄÖ!"),
+            vec!["fix", "the", "parse", "usage", "bug", "äö"]
+        );
+        assert!(tokenize(" -> ").is_empty());
+    }
+
+    #[test]
+    fn test_candidates_match_word_prefixes() {
+        let mut index = SearchIndex::new();
+        index.add_run("s1", "r1", ["Debugging the parser", "cargo test"]);
+        index.add_run("s1", "r2", ["Fix the bug"]);
+        index.add_run("s2", "r3", ["Unrelated"]);
+
+        let candidates = index.candidates("bug").unwrap();
+        assert_eq!(candidates, HashSet::from([("s1", "r2")]));
+        let candidates = index.candidates("debug").unwrap();
+        assert_eq!(candidates, HashSet::from([("s1", "r1")]));
+
+        // Every query token must be present in the run
+        let candidates = index.candidates("the pars").unwrap();
+        assert_eq!(candidates, HashSet::from([("s1", "r1")]));
+
+        assert!(index.candidates("missing").unwrap().is_empty());
+        assert!(index.candidates("->").is_none());
+    }
+
+    #[test]
+    fn test_index_file_round_trip_and_version_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("search-index.json");
+        assert!(read_index_file(&path).is_none());
+
+        let mut index = SearchIndex::new();
+        index.add_run("s1", "r1", ["hello world"]);
+        write_index_file(&path, &index).unwrap();
+        let loaded = read_index_file(&path).unwrap();
+        assert!(loaded.indexed_runs().contains(&("s1", "r1")));
+        assert!(!loaded.complete);
+
+        index.version = SEARCH_INDEX_VERSION + 1;
+        write_index_file(&path, &index).unwrap();
+        assert!(read_index_file(&path).is_none());
+
+        fs::write(&path, "{not json").unwrap();
+        assert!(read_index_file(&path).is_none());
+    }
+}
@@ -1,11 +1,11 @@
 use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::{BufReader, BufWriter};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 use once_cell::sync::Lazy;
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
 use super::types::{
     SavedContextsMetadata, Session, SessionIndexEntry, SessionMetadata, WorktreeIndex,
@@ -71,10 +71,7 @@ pub fn sanitize_filename(name: &str) -> String {
 /// Get the sessions base directory in app data (creates if not exists)
 /// Structure: sessions/
 pub fn get_sessions_dir(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(app)?;
 
     let sessions_dir = app_data_dir.join("sessions");
 
@@ -238,14 +235,17 @@ fn load_metadata_internal(
     app: &AppHandle,
     session_id: &str,
 ) -> Result<Option<SessionMetadata>, String> {
-    let path = get_metadata_path(app, session_id)?;
+    read_metadata_file(&get_metadata_path(app, session_id)?)
+}
 
+/// Read a metadata file, or None if it doesn't exist
+pub fn read_metadata_file(path: &Path) -> Result<Option<SessionMetadata>, String> {
     if !path.exists() {
         return Ok(None);
     }
 
     let file =
-        File::open(&path).map_err(|e| format!("Failed to open metadata file {path:?}: {e}"))?;
+        File::open(path).map_err(|e| format!("Failed to open metadata file {path:?}: {e}"))?;
 
     let reader = BufReader::new(file);
     let metadata: SessionMetadata = serde_json::from_reader(reader)
@@ -256,7 +256,13 @@ fn load_metadata_internal(
 
 /// Save session metadata (internal, no locking - atomic write)
 fn save_metadata_internal(app: &AppHandle, metadata: &SessionMetadata) -> Result<(), String> {
-    let path = get_metadata_path(app, &metadata.id)?;
+    write_metadata_file(&get_metadata_path(app, &metadata.id)?, metadata)?;
+    log::trace!("Saved metadata for session: {}", metadata.id);
+    Ok(())
+}
+
+/// Atomically write a metadata file (temp file + rename)
+pub fn write_metadata_file(path: &Path, metadata: &SessionMetadata) -> Result<(), String> {
     let temp_path = path.with_extension("tmp");
 
     let file = File::create(&temp_path)
@@ -266,9 +272,7 @@ fn save_metadata_internal(app: &AppHandle, metadata: &SessionMetadata) -> Result
     serde_json::to_writer_pretty(writer, metadata)
         .map_err(|e| format!("Failed to write metadata: {e}"))?;
 
-    fs::rename(&temp_path, &path).map_err(|e| format!("Failed to rename metadata file: {e}"))?;
-
-    log::trace!("Saved metadata for session: {}", metadata.id);
+    fs::rename(&temp_path, path).map_err(|e| format!("Failed to rename metadata file: {e}"))?;
     Ok(())
 }
 
@@ -286,6 +290,80 @@ pub fn save_metadata(app: &AppHandle, metadata: &SessionMetadata) -> Result<(),
     save_metadata_internal(app, metadata)
 }
 
+/// Longest session name accepted from the user (in characters)
+const MAX_SESSION_NAME_CHARS: usize = 100;
+
+/// Trim a user-supplied session name and reject empty or overlong ones
+pub fn normalize_session_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Session name cannot be empty".to_string());
+    }
+    if name.chars().count() > MAX_SESSION_NAME_CHARS {
+        return Err(format!(
+            "Session name is too long (max {MAX_SESSION_NAME_CHARS} characters)"
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// Rename the session whose metadata lives at `path`
+/// Sessions that never ran have no metadata file yet; one is created from
+/// their index entry so the new name is kept.
+fn rename_metadata_file(
+    path: &Path,
+    worktree_id: &str,
+    entry: &SessionIndexEntry,
+    new_name: &str,
+) -> Result<(), String> {
+    let mut metadata = read_metadata_file(path)?.unwrap_or_else(|| {
+        SessionMetadata::new(
+            entry.id.clone(),
+            worktree_id.to_string(),
+            entry.name.clone(),
+            entry.order,
+        )
+    });
+    metadata.name = new_name.to_string();
+    write_metadata_file(path, &metadata)
+}
+
+/// Rename a session in both its metadata and the worktree index.
+///
+/// The name must already be normalized (see `normalize_session_name`).
+/// In-flight `RunLogWriter`s keep working: the name they carry is only used
+/// to create metadata that doesn't exist, and never overwrites this one.
+pub fn rename_session_metadata(
+    app: &AppHandle,
+    worktree_id: &str,
+    session_id: &str,
+    new_name: &str,
+) -> Result<(), String> {
+    let not_found = || format!("Session not found: {session_id}");
+    let index = load_index(app, worktree_id)?;
+    let entry = index
+        .sessions
+        .iter()
+        .find(|s| s.id == session_id)
+        .ok_or_else(not_found)?;
+
+    {
+        let lock = get_metadata_lock(session_id);
+        let _guard = lock.lock().unwrap();
+        let path = get_metadata_path(app, session_id)?;
+        rename_metadata_file(&path, worktree_id, entry, new_name)?;
+    }
+
+    with_index_mut(app, worktree_id, |index| {
+        let entry = index.find_session_mut(session_id).ok_or_else(not_found)?;
+        entry.name = new_name.to_string();
+        Ok(())
+    })?;
+
+    log::trace!("Renamed session {session_id} to: {new_name}");
+    Ok(())
+}
+
 /// Atomically load, modify, and save session metadata.
 /// Creates new metadata if it doesn't exist.
 pub fn with_metadata_mut<F, T>(
@@ -356,6 +434,37 @@ pub fn list_all_session_ids(app: &AppHandle) -> Result<Vec<String>, String> {
     Ok(session_ids)
 }
 
+/// Total size in bytes of all files under `dir` (recursively)
+fn dir_size(dir: &Path) -> Result<u64, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory {}: {e}", dir.display()))?;
+
+    let mut total = 0;
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            total += dir_size(&entry.path())?;
+        } else if file_type.is_file() {
+            total += entry.metadata().map(|m| m.len()).unwrap_or(0);
+        }
+    }
+    Ok(total)
+}
+
+/// On-disk size of every session (run logs, input files, metadata),
+/// largest first
+pub fn session_disk_usage(app: &AppHandle) -> Result<Vec<(String, u64)>, String> {
+    let mut usage = Vec::new();
+    for session_id in list_all_session_ids(app)? {
+        let bytes = dir_size(&get_session_dir(app, &session_id)?)?;
+        usage.push((session_id, bytes));
+    }
+    usage.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(usage)
+}
+
 // ============================================================================
 // High-Level Session API (Backward Compatibility)
 // ============================================================================
@@ -593,10 +702,7 @@ pub fn restore_base_sessions(
 /// Get the images directory path in app data directory (creates if not exists)
 /// Used for storing pasted images: ~/Library/Application Support/<app>/pasted-images/
 pub fn get_images_dir(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(app)?;
 
     let path = app_data_dir.join("pasted-images");
 
@@ -608,10 +714,7 @@ pub fn get_images_dir(app: &AppHandle) -> Result<PathBuf, String> {
 /// Get the pastes directory path in app data directory (creates if not exists)
 /// Used for storing pasted text files: ~/Library/Application Support/<app>/pasted-texts/
 pub fn get_pastes_dir(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(app)?;
 
     let path = app_data_dir.join("pasted-texts");
 
@@ -623,10 +726,7 @@ pub fn get_pastes_dir(app: &AppHandle) -> Result<PathBuf, String> {
 /// Get the saved contexts directory path in app data directory (creates if not exists)
 /// Used for storing conversation context summaries: ~/Library/Application Support/<app>/session-context/
 pub fn get_saved_contexts_dir(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(app)?;
 
     let path = app_data_dir.join("session-context");
 
@@ -703,6 +803,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_normalize_session_name() {
+        assert_eq!(
+            normalize_session_name("  Fix login  ").unwrap(),
+            "Fix login"
+        );
+        assert!(normalize_session_name("   ").is_err());
+        assert!(normalize_session_name(&"a".repeat(MAX_SESSION_NAME_CHARS)).is_ok());
+        assert!(normalize_session_name(&"é".repeat(MAX_SESSION_NAME_CHARS + 1)).is_err());
+    }
+
+    #[test]
+    fn test_dir_size_sums_nested_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("metadata.json"), vec![b'x'; 120]).unwrap();
+        fs::write(dir.path().join("run-1.jsonl"), vec![b'x'; 1000]).unwrap();
+        fs::create_dir(dir.path().join("inputs")).unwrap();
+        fs::write(dir.path().join("inputs").join("prompt.txt"), vec![b'x'; 30]).unwrap();
+
+        assert_eq!(dir_size(dir.path()).unwrap(), 1150);
+        assert!(dir_size(&dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_rename_persists_in_metadata_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metadata.json");
+        let mut metadata = SessionMetadata::new(
+            "session-1".to_string(),
+            "worktree-1".to_string(),
+            "Session 1".to_string(),
+            3,
+        );
+        metadata.claude_session_id = Some("claude-1".to_string());
+        write_metadata_file(&path, &metadata).unwrap();
+
+        let entry = metadata.to_index_entry();
+        rename_metadata_file(&path, "worktree-1", &entry, "Fix login flow").unwrap();
+
+        let reloaded = read_metadata_file(&path).unwrap().unwrap();
+        assert_eq!(reloaded.name, "Fix login flow");
+        // Everything else is untouched
+        assert_eq!(reloaded.order, 3);
+        assert_eq!(reloaded.claude_session_id.as_deref(), Some("claude-1"));
+    }
+
+    #[test]
+    fn test_rename_creates_missing_metadata_from_index_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metadata.json");
+        let entry = SessionIndexEntry {
+            id: "session-2".to_string(),
+            name: "Session 2".to_string(),
+            order: 1,
+            message_count: 0,
+            archived_at: None,
+        };
+
+        rename_metadata_file(&path, "worktree-1", &entry, "Fix login flow").unwrap();
+
+        let created = read_metadata_file(&path).unwrap().unwrap();
+        assert_eq!(created.id, "session-2");
+        assert_eq!(created.worktree_id, "worktree-1");
+        assert_eq!(created.name, "Fix login flow");
+        assert_eq!(created.order, 1);
+        assert!(created.runs.is_empty());
+    }
+
     #[test]
     fn test_worktree_index_new() {
         let index = WorktreeIndex::new("test-worktree".to_string());
@@ -2,23 +2,110 @@
 //!
 //! This module provides functionality to tail an NDJSON file and read new lines
 //! as they are written by a detached Claude CLI process.
+//!
+//! Finalized run logs may be stored gzip-compressed (`.jsonl.gz`); those are
+//! decoded transparently when reading from the start.
 
+use flate2::read::GzDecoder;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Seek, SeekFrom};
-use std::path::Path;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 /// Polling interval for tailing NDJSON files (50ms)
 pub const POLL_INTERVAL: Duration = Duration::from_millis(50);
 
+/// Longest a watched tailer blocks in `poll()` waiting for a filesystem event.
+/// Acts as a safety net for missed events and keeps callers' timeout checks running.
+#[cfg(feature = "watched-tail")]
+pub const WATCH_FALLBACK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Gzip magic bytes (RFC 1952)
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Path of the gzip-compressed sibling of a run log (`<run>.jsonl` → `<run>.jsonl.gz`)
+pub fn gzip_sibling(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".gz");
+    PathBuf::from(name)
+}
+
+/// Open an NDJSON file for reading from the start, decoding gzip if needed.
+///
+/// Falls back to the `.gz` sibling when `path` doesn't exist, and detects
+/// compressed content by its magic header regardless of extension.
+pub fn open_ndjson_reader(path: &Path) -> Result<Box<dyn BufRead + Send>, String> {
+    let path = if !path.exists() && gzip_sibling(path).exists() {
+        gzip_sibling(path)
+    } else {
+        path.to_path_buf()
+    };
+
+    let mut file =
+        File::open(&path).map_err(|e| format!("Failed to open file for tailing: {e}"))?;
+
+    let mut magic = [0u8; 2];
+    let is_gzip = file.read_exact(&mut magic).is_ok() && magic == GZIP_MAGIC;
+    file.seek(SeekFrom::Start(0))
+        .map_err(|e| format!("Failed to seek to start of file: {e}"))?;
+
+    if is_gzip {
+        Ok(Box::new(BufReader::new(GzDecoder::new(file))))
+    } else {
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
 /// Tailer for reading new lines from an NDJSON file.
 ///
 /// Maintains position in the file and returns only new complete lines
 /// since the last poll.
 pub struct NdjsonTailer {
-    reader: BufReader<File>,
-    /// Buffer for incomplete lines (no trailing newline yet)
-    buffer: String,
+    reader: Box<dyn BufRead + Send>,
+    /// Buffer for incomplete lines (no trailing newline yet).
+    /// Kept as raw bytes so a write that ends mid-way through a multi-byte
+    /// UTF-8 character is completed by the next poll instead of rejected.
+    buffer: Vec<u8>,
+    /// Path being tailed, checked by [`NdjsonTailer::file_removed`]
+    path: PathBuf,
+    /// Most complete lines returned by one `poll()` (None = read to EOF)
+    max_lines_per_poll: Option<usize>,
+    /// Whether the last `poll()` stopped at `max_lines_per_poll` with data left
+    capped: bool,
+    /// Filesystem watch used to wake `poll()` (see [`NdjsonTailer::new_watched`])
+    #[cfg(feature = "watched-tail")]
+    watch: Option<FileWatch>,
+}
+
+/// Filesystem watcher for a single file, delivering events over a channel
+#[cfg(feature = "watched-tail")]
+struct FileWatch {
+    _watcher: notify::RecommendedWatcher,
+    events: std::sync::mpsc::Receiver<notify::Result<notify::Event>>,
+}
+
+#[cfg(feature = "watched-tail")]
+impl FileWatch {
+    fn new(path: &Path) -> notify::Result<Self> {
+        use notify::Watcher;
+
+        let (tx, events) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        watcher.watch(path, notify::RecursiveMode::NonRecursive)?;
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+        })
+    }
+
+    /// Block until a filesystem event arrives or `timeout` elapses
+    fn wait(&self, timeout: Duration) {
+        if self.events.recv_timeout(timeout).is_ok() {
+            // Drain the rest of a burst so the next wait doesn't return immediately
+            while self.events.try_recv().is_ok() {}
+        }
+    }
 }
 
 impl NdjsonTailer {
@@ -38,48 +125,121 @@ impl NdjsonTailer {
             .map_err(|e| format!("Failed to seek to end of file: {e}"))?;
 
         Ok(Self {
-            reader,
-            buffer: String::new(),
+            reader: Box::new(reader),
+            buffer: Vec::new(),
+            path: path.to_path_buf(),
+            max_lines_per_poll: None,
+            capped: false,
+            #[cfg(feature = "watched-tail")]
+            watch: None,
         })
     }
 
     /// Create a new tailer, starting from the beginning of file.
     ///
     /// This is used when resuming a session where we need to read
-    /// all existing content first.
+    /// all existing content first. Gzip-compressed logs (finalized runs)
+    /// are decoded, see [`open_ndjson_reader`].
     pub fn new_from_start(path: &Path) -> Result<Self, String> {
-        let file = File::open(path).map_err(|e| format!("Failed to open file for tailing: {e}"))?;
-
-        let reader = BufReader::new(file);
-
         Ok(Self {
-            reader,
-            buffer: String::new(),
+            reader: open_ndjson_reader(path)?,
+            buffer: Vec::new(),
+            path: path.to_path_buf(),
+            max_lines_per_poll: None,
+            capped: false,
+            #[cfg(feature = "watched-tail")]
+            watch: None,
         })
     }
 
+    /// Create a tailer from the beginning of file that is woken by filesystem
+    /// events instead of fixed-interval polling.
+    ///
+    /// When no new data is available, `poll()` blocks until the file changes
+    /// or [`WATCH_FALLBACK_INTERVAL`] elapses. Falls back to a plain polling
+    /// tailer if the watch can't be set up.
+    #[cfg(feature = "watched-tail")]
+    pub fn new_watched(path: &Path) -> Result<Self, String> {
+        let mut tailer = Self::new_from_start(path)?;
+        match FileWatch::new(path) {
+            Ok(watch) => tailer.watch = Some(watch),
+            Err(e) => log::warn!("Failed to watch {path:?}, falling back to polling: {e}"),
+        }
+        Ok(tailer)
+    }
+
+    /// Cap the complete lines a single `poll()` returns.
+    ///
+    /// On a burst of output the rest stays unread (the reader position is kept)
+    /// and is returned by the following polls, so callers get to run their
+    /// own checks, like cancellation, between batches.
+    pub fn with_max_lines_per_poll(mut self, max_lines: usize) -> Self {
+        self.max_lines_per_poll = Some(max_lines.max(1));
+        self
+    }
+
+    /// Whether the last `poll()` stopped at the line cap, so more lines may be
+    /// available right away (callers shouldn't sleep before polling again).
+    pub fn last_poll_capped(&self) -> bool {
+        self.capped
+    }
+
+    /// Whether `poll()` waits for filesystem events itself, so callers
+    /// don't need to sleep between polls.
+    pub fn is_watched(&self) -> bool {
+        #[cfg(feature = "watched-tail")]
+        {
+            self.watch.is_some()
+        }
+        #[cfg(not(feature = "watched-tail"))]
+        {
+            false
+        }
+    }
+
     /// Poll for new complete lines.
     ///
     /// Returns a vector of complete lines (without trailing newlines).
     /// Incomplete lines (no newline yet) are buffered until complete.
     pub fn poll(&mut self) -> Result<Vec<String>, String> {
+        let lines = self.read_available()?;
+
+        #[cfg(feature = "watched-tail")]
+        if lines.is_empty() {
+            if let Some(watch) = &self.watch {
+                watch.wait(WATCH_FALLBACK_INTERVAL);
+                return self.read_available();
+            }
+        }
+
+        Ok(lines)
+    }
+
+    /// Read every complete line currently available without blocking
+    fn read_available(&mut self) -> Result<Vec<String>, String> {
         let mut lines = Vec::new();
+        self.capped = false;
 
         loop {
-            let mut line = String::new();
-            match self.reader.read_line(&mut line) {
+            if self
+                .max_lines_per_poll
+                .is_some_and(|max| lines.len() >= max)
+            {
+                self.capped = true;
+                break;
+            }
+            match self.reader.read_until(b'\n', &mut self.buffer) {
                 Ok(0) => {
                     // EOF reached, no more data available right now
                     break;
                 }
                 Ok(_) => {
-                    // Add to buffer
-                    self.buffer.push_str(&line);
-
                     // Check if we have a complete line (ends with newline)
-                    if self.buffer.ends_with('\n') {
-                        // Remove the trailing newline and add to results
-                        let complete_line = self.buffer.trim_end_matches(['\n', '\r']).to_string();
+                    if self.buffer.ends_with(b"\n") {
+                        // Decode only complete lines, so multi-byte characters are never split
+                        let complete_line = String::from_utf8_lossy(&self.buffer)
+                            .trim_end_matches(['\n', '\r'])
+                            .to_string();
                         lines.push(complete_line);
                         self.buffer.clear();
                     }
@@ -94,6 +254,30 @@ impl NdjsonTailer {
         Ok(lines)
     }
 
+    /// Take the buffered incomplete line, if any.
+    ///
+    /// Used once the writer is known to be done (process exited or run finished),
+    /// so a final line written without a trailing newline isn't dropped.
+    pub fn flush_buffer(&mut self) -> Option<String> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+        let line = String::from_utf8_lossy(&self.buffer)
+            .trim_end_matches(['\n', '\r'])
+            .to_string();
+        self.buffer.clear();
+        (!line.trim().is_empty()).then_some(line)
+    }
+
+    /// Whether the tailed file (and its `.gz` sibling) has been deleted.
+    ///
+    /// On Unix the open handle keeps reading the unlinked file, so `poll()`
+    /// still returns what the writer produces; callers use this to warn that
+    /// the output won't be kept, or to end gracefully if reading then fails.
+    pub fn file_removed(&self) -> bool {
+        !self.path.exists() && !gzip_sibling(&self.path).exists()
+    }
+
     /// Check if there's any buffered incomplete data.
     #[allow(dead_code)] // Used in tests
     pub fn has_incomplete_data(&self) -> bool {
@@ -133,6 +317,24 @@ mod tests {
         assert!(lines[0].contains("hello"));
     }
 
+    #[test]
+    fn test_flush_buffer_returns_unterminated_final_line() {
+        let mut file = NamedTempFile::new().unwrap();
+        let path = file.path().to_path_buf();
+        let mut tailer = NdjsonTailer::new_from_start(&path).unwrap();
+
+        write!(file, r#"{{"type": "result"}}"#).unwrap();
+        file.flush().unwrap();
+        assert!(tailer.poll().unwrap().is_empty());
+
+        assert_eq!(
+            tailer.flush_buffer().as_deref(),
+            Some(r#"{"type": "result"}"#)
+        );
+        assert!(!tailer.has_incomplete_data());
+        assert_eq!(tailer.flush_buffer(), None);
+    }
+
     #[test]
     fn test_tailer_incomplete_line() {
         let mut file = NamedTempFile::new().unwrap();
@@ -303,6 +505,61 @@ mod tests {
         assert!(lines[0].contains(r#""type": "crlf""#));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_tailer_survives_file_deleted_between_polls() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.jsonl");
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, r#"{{"type":"system"}}"#).unwrap();
+
+        let mut tailer = NdjsonTailer::new_from_start(&path).unwrap();
+        assert_eq!(tailer.poll().unwrap().len(), 1);
+        assert!(!tailer.file_removed());
+
+        std::fs::remove_file(&path).unwrap();
+        // The writer still holds its handle and keeps writing
+        writeln!(file, r#"{{"type":"assistant"}}"#).unwrap();
+        file.flush().unwrap();
+
+        assert!(tailer.file_removed());
+        assert_eq!(
+            tailer.poll().unwrap(),
+            vec![r#"{"type":"assistant"}"#.to_string()]
+        );
+        assert!(tailer.poll().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_poll_returns_at_most_cap_lines_per_call() {
+        let mut file = NamedTempFile::new().unwrap();
+        for i in 0..10_000 {
+            writeln!(file, r#"{{"n": {i}}}"#).unwrap();
+        }
+        file.flush().unwrap();
+
+        let mut tailer = NdjsonTailer::new_from_start(file.path())
+            .unwrap()
+            .with_max_lines_per_poll(512);
+
+        let mut all = Vec::new();
+        loop {
+            let batch = tailer.poll().unwrap();
+            assert!(batch.len() <= 512);
+            if batch.is_empty() {
+                assert!(!tailer.last_poll_capped());
+                break;
+            }
+            all.extend(batch);
+        }
+
+        // Nothing lost or reordered across batches
+        assert_eq!(all.len(), 10_000);
+        assert_eq!(all[0], r#"{"n": 0}"#);
+        assert_eq!(all[512], r#"{"n": 512}"#);
+        assert_eq!(all[9_999], r#"{"n": 9999}"#);
+    }
+
     #[test]
     fn test_poll_interval_constant() {
         // Verify the poll interval is a reasonable value
@@ -312,4 +569,126 @@ mod tests {
         // Should be at most 200ms for responsiveness
         assert!(POLL_INTERVAL <= Duration::from_millis(200));
     }
+
+    #[test]
+    fn test_tailer_reads_gzip_file() {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.jsonl");
+
+        let mut encoder = GzEncoder::new(
+            File::create(gzip_sibling(&path)).unwrap(),
+            Compression::default(),
+        );
+        writeln!(encoder, r#"{{"type": "line1"}}"#).unwrap();
+        writeln!(encoder, r#"{{"type": "line2"}}"#).unwrap();
+        encoder.finish().unwrap();
+
+        // Only the .gz sibling exists; reading the .jsonl path should decode it
+        let mut tailer = NdjsonTailer::new_from_start(&path).unwrap();
+        let lines = tailer.poll().unwrap();
+        assert_eq!(lines, vec![r#"{"type": "line1"}"#, r#"{"type": "line2"}"#]);
+        assert!(tailer.poll().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_gzip_detected_by_magic_header() {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+
+        let file = NamedTempFile::new().unwrap();
+        let mut encoder = GzEncoder::new(file.reopen().unwrap(), Compression::default());
+        writeln!(encoder, r#"{{"type": "compressed"}}"#).unwrap();
+        encoder.finish().unwrap();
+
+        let mut tailer = NdjsonTailer::new_from_start(file.path()).unwrap();
+        assert_eq!(tailer.poll().unwrap(), vec![r#"{"type": "compressed"}"#]);
+    }
+
+    #[test]
+    fn test_tailer_reassembles_multibyte_line_split_across_writes() {
+        let mut file = NamedTempFile::new().unwrap();
+        let path = file.path().to_path_buf();
+
+        let mut tailer = NdjsonTailer::new_from_start(&path).unwrap();
+
+        // ~3MB line of 3-byte characters, written in chunks that split characters
+        let content = "日本語".repeat(350_000);
+        let line = format!(r#"{{"content": "{content}"}}"#);
+        let bytes = line.as_bytes();
+        let mut lines = Vec::new();
+        for chunk in bytes.chunks(64 * 1024 + 1) {
+            file.write_all(chunk).unwrap();
+            file.flush().unwrap();
+            lines.extend(tailer.poll().unwrap());
+        }
+        assert!(lines.is_empty());
+        assert!(tailer.has_incomplete_data());
+
+        writeln!(file).unwrap();
+        file.flush().unwrap();
+        lines.extend(tailer.poll().unwrap());
+
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0], line);
+        assert!(!tailer.has_incomplete_data());
+    }
+
+    #[test]
+    fn test_unwatched_tailer_reports_not_watched() {
+        let file = NamedTempFile::new().unwrap();
+        let tailer = NdjsonTailer::new_from_start(file.path()).unwrap();
+        assert!(!tailer.is_watched());
+    }
+
+    #[cfg(feature = "watched-tail")]
+    #[test]
+    fn test_watched_tailer_wakes_on_write() {
+        use std::time::Instant;
+
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = file.reopen().unwrap();
+        let mut tailer = NdjsonTailer::new_watched(file.path()).unwrap();
+        assert!(tailer.is_watched());
+
+        // Nothing written yet: poll blocks for at most the fallback interval
+        let started = Instant::now();
+        assert!(tailer.poll().unwrap().is_empty());
+        assert!(started.elapsed() < WATCH_FALLBACK_INTERVAL * 4);
+
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            writeln!(writer, r#"{{"type": "watched"}}"#).unwrap();
+            writer.flush().unwrap();
+        });
+
+        let mut lines = Vec::new();
+        for _ in 0..20 {
+            lines.extend(tailer.poll().unwrap());
+            if !lines.is_empty() {
+                break;
+            }
+        }
+        handle.join().unwrap();
+        assert_eq!(lines, vec![r#"{"type": "watched"}"#]);
+    }
+
+    #[cfg(feature = "watched-tail")]
+    #[test]
+    fn test_watched_tailer_buffers_incomplete_line() {
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = file.reopen().unwrap();
+        let mut tailer = NdjsonTailer::new_watched(file.path()).unwrap();
+
+        write!(writer, r#"{{"type": "#).unwrap();
+        writer.flush().unwrap();
+        assert!(tailer.poll().unwrap().is_empty());
+        assert!(tailer.has_incomplete_data());
+
+        writeln!(writer, r#""done"}}"#).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(tailer.poll().unwrap(), vec![r#"{"type": "done"}"#]);
+    }
 }
@@ -39,7 +39,7 @@ pub struct CompactMetadata {
 // ============================================================================
 
 /// Token usage data from Claude CLI response
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct UsageData {
     /// Input tokens (context sent to Claude)
     pub input_tokens: u64,
@@ -137,6 +137,13 @@ pub struct ToolCall {
     /// Parent tool use ID for sub-agent tool calls (for parallel task attribution)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub parent_tool_use_id: Option<String>,
+    /// True if the tool result was reported as an error (e.g., non-zero exit code)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_error: bool,
+    /// Time from tool_use to tool_result in milliseconds (None when unknown,
+    /// e.g. for tool calls reconstructed from run logs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
 }
 
 /// A permission denial from Claude CLI when a tool requires approval
@@ -193,16 +200,16 @@ pub struct ChatMessage {
     /// True if the plan in this message was approved by the user
     #[serde(default)]
     pub plan_approved: bool,
-    /// Model used when this message was sent (user messages only)
+    /// Model used when this message was sent (assistant: the model that answered)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub model: Option<String>,
-    /// Execution mode when this message was sent (user messages only)
+    /// Execution mode of the run this message belongs to
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub execution_mode: Option<String>,
-    /// Thinking level when this message was sent (user messages only)
+    /// Thinking level of the run this message belongs to
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub thinking_level: Option<String>,
-    /// Effort level when this message was sent (user messages only, Opus 4.6)
+    /// Effort level of the run this message belongs to (Opus 4.6)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub effort_level: Option<String>,
     /// True if this message was recovered from a crash
@@ -211,6 +218,18 @@ pub struct ChatMessage {
     /// Token usage for this message (assistant messages only)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub usage: Option<UsageData>,
+    /// Working directory the run was started in (user messages only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    /// Git branch checked out when the run started (user messages only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
+    /// Run ID of the crashed run this message's run retries (user messages only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retried_from: Option<String>,
+    /// Number of earlier runs this message summarizes (compacted-history summaries only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compacted_runs: Option<usize>,
 }
 
 impl Default for ChatMessage {
@@ -231,6 +250,10 @@ impl Default for ChatMessage {
             effort_level: None,
             recovered: false,
             usage: None,
+            working_dir: None,
+            git_branch: None,
+            retried_from: None,
+            compacted_runs: None,
         }
     }
 }
@@ -790,6 +813,69 @@ pub struct RunEntry {
     /// Token usage for this run (captured from Claude CLI result)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub usage: Option<UsageData>,
+    /// Model the CLI actually ran with (from its init message), which may differ
+    /// from the requested `model` when it was empty or resolved from an alias
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_model: Option<String>,
+    /// User-supplied labels for grouping and filtering runs (e.g., "ticket-123")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    /// SHA-256 of the user message and ordered assistant content, ignoring
+    /// timestamps and IDs (used to detect duplicate runs across imports)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// Last lines of CLI stderr captured when the run crashed (capped at a few KB)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stderr_tail: Option<String>,
+    /// Working directory the CLI was spawned in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    /// Git branch checked out in the working directory when the run started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
+    /// Run ID of the crashed run this run retries (see retry_run)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retried_from: Option<String>,
+    /// Run ID whose Claude session this run resumed instead of the latest (branching)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branched_from: Option<String>,
+    /// Set on the synthetic run created by compact_session: how many runs it replaced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compacted_runs: Option<usize>,
+    /// Claude CLI permission mode the run was spawned with (plan, acceptEdits, bypassPermissions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permission_mode: Option<String>,
+    /// Whether web tools (WebFetch/WebSearch) could run without a permission prompt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub web_access: Option<bool>,
+    /// Whether thinking is kept out of the UI because the run's reasoning level
+    /// was below the configured threshold (it is still in the run log)
+    #[serde(default)]
+    pub thinking_hidden: bool,
+    /// Send options not recorded above, kept so a crashed run can be retried
+    /// with the same settings (older runs: unknown)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_options: Option<RunRequestOptions>,
+}
+
+/// Options a run was sent with that don't have their own RunEntry field
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RunRequestOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disable_thinking_for_mode: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallel_execution_prompt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai_language: Option<String>,
+    /// Tools requested by the frontend (before plan-mode web tools are added)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp_config: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chrome_enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_profile_name: Option<String>,
 }
 
 /// Session metadata - single source of truth for session data and run history
@@ -899,6 +985,12 @@ pub struct RunLogFileInfo {
     /// Token usage for this run (if completed)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub usage: Option<UsageData>,
+    /// Claude CLI permission mode the run was spawned with (older runs: unknown)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permission_mode: Option<String>,
+    /// Whether web tools could run without a permission prompt (older runs: unknown)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub web_access: Option<bool>,
 }
 
 /// Debug information about a session's storage
@@ -1223,6 +1315,8 @@ mod tests {
             input: serde_json::json!({"file_path": "/test.txt"}),
             output: Some("file contents".to_string()),
             parent_tool_use_id: None,
+            is_error: false,
+            duration_ms: None,
         };
 
         let json = serde_json::to_string(&tool_call).unwrap();
@@ -1240,6 +1334,8 @@ mod tests {
             input: serde_json::json!({}),
             output: None,
             parent_tool_use_id: Some("call-123".to_string()),
+            is_error: false,
+            duration_ms: None,
         };
 
         let json = serde_json::to_string(&tool_call).unwrap();
@@ -1315,6 +1411,19 @@ mod tests {
             claude_session_id: None,
             pid: Some(12345),
             usage: None,
+            resolved_model: None,
+            labels: vec![],
+            content_hash: None,
+            stderr_tail: None,
+            working_dir: None,
+            git_branch: None,
+            retried_from: None,
+            branched_from: None,
+            compacted_runs: None,
+            permission_mode: None,
+            web_access: None,
+            thinking_hidden: false,
+            request_options: None,
         });
 
         assert!(metadata.find_run("run-1").is_some());
@@ -1351,6 +1460,19 @@ mod tests {
             claude_session_id: None,
             pid: None,
             usage: None,
+            resolved_model: None,
+            labels: vec![],
+            content_hash: None,
+            stderr_tail: None,
+            working_dir: None,
+            git_branch: None,
+            retried_from: None,
+            branched_from: None,
+            compacted_runs: None,
+            permission_mode: None,
+            web_access: None,
+            thinking_hidden: false,
+            request_options: None,
         });
 
         assert!(metadata.latest_claude_session_id().is_none());
@@ -1373,6 +1495,19 @@ mod tests {
             claude_session_id: Some("claude-sess-abc".to_string()),
             pid: None,
             usage: None,
+            resolved_model: None,
+            labels: vec![],
+            content_hash: None,
+            stderr_tail: None,
+            working_dir: None,
+            git_branch: None,
+            retried_from: None,
+            branched_from: None,
+            compacted_runs: None,
+            permission_mode: None,
+            web_access: None,
+            thinking_hidden: false,
+            request_options: None,
         });
 
         assert_eq!(metadata.latest_claude_session_id(), Some("claude-sess-abc"));
@@ -5,7 +5,10 @@ use sha2::{Digest, Sha256};
 use std::io::Write;
 use tauri::AppHandle;
 
-use super::config::{ensure_cli_dir, get_cli_binary_path};
+use super::config::{
+    active_cli_source, cached_cli_version, ensure_cli_dir, get_cli_binary_path,
+    get_embedded_cli_binary_path, resolve_system_cli, CLI_SOURCE_EMBEDDED, CLI_SOURCE_SYSTEM,
+};
 use crate::http_server::EmitExt;
 use crate::platform::silent_command;
 
@@ -30,6 +33,151 @@ fn extract_version_number(version_str: &str) -> String {
     version_str.to_string()
 }
 
+/// Oldest Claude CLI version whose flags Jean's argument building relies on
+pub const MIN_CLAUDE_CLI_VERSION: &str = "1.0.0";
+
+/// Compare two semver-like versions ("1.0.28", "2.1.0-beta.1")
+///
+/// Missing components count as zero, and a prerelease sorts before the
+/// release it precedes. Prerelease identifiers follow semver precedence
+/// ("beta.2" < "beta.10" < "rc.1"); build metadata ("+abc") is ignored.
+fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    fn split(version: &str) -> (Vec<u64>, Option<&str>) {
+        let version = version.trim().trim_start_matches('v');
+        let version = version.split_once('+').map_or(version, |(v, _)| v);
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (version, None),
+        };
+        let numbers = core
+            .split('.')
+            .map(|part| part.parse::<u64>().unwrap_or(0))
+            .collect();
+        (numbers, pre)
+    }
+
+    let (a_nums, a_pre) = split(a);
+    let (b_nums, b_pre) = split(b);
+    let len = a_nums.len().max(b_nums.len());
+    for i in 0..len {
+        let ordering = a_nums.get(i).unwrap_or(&0).cmp(b_nums.get(i).unwrap_or(&0));
+        if ordering != std::cmp::Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    match (a_pre, b_pre) {
+        (None, None) => std::cmp::Ordering::Equal,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (Some(a), Some(b)) => compare_prerelease(a, b),
+    }
+}
+
+/// Semver precedence of two prerelease strings: dot-separated identifiers are
+/// compared in order, numeric ones numerically and below alphanumeric ones,
+/// and a shorter list of otherwise equal identifiers sorts first.
+fn compare_prerelease(a: &str, b: &str) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    let mut a_ids = a.split('.');
+    let mut b_ids = b.split('.');
+    loop {
+        let ordering = match (a_ids.next(), b_ids.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// Whether `latest` should be offered over the installed `current` version.
+/// No installed CLI (or an unknown version) always counts as an update.
+fn is_update_available(current: Option<&str>, latest: &str) -> bool {
+    current.is_none_or(|current| compare_versions(latest, current) == std::cmp::Ordering::Greater)
+}
+
+/// Whether a Claude CLI version meets [`MIN_CLAUDE_CLI_VERSION`]
+fn is_supported_cli_version(version: &str) -> bool {
+    compare_versions(version, MIN_CLAUDE_CLI_VERSION) != std::cmp::Ordering::Less
+}
+
+/// Error shown when the CLI is older than Jean supports
+fn unsupported_version_error(version: &str, binary_path: &std::path::Path) -> String {
+    format!(
+        "Claude CLI {version} at {} is older than the minimum supported version {MIN_CLAUDE_CLI_VERSION}. Install a newer version from Settings (or run `claude update` for a system CLI).",
+        binary_path.display()
+    )
+}
+
+/// Fail fast before spawning a Claude CLI that is too old for Jean's arguments
+///
+/// An unknown version (e.g., `--version` failed) is allowed through so a
+/// flaky version probe never blocks a working CLI.
+pub fn ensure_supported_cli_version(binary_path: &std::path::Path) -> Result<(), String> {
+    match cached_cli_version(binary_path) {
+        Some(version) if !is_supported_cli_version(&version) => {
+            Err(unsupported_version_error(&version, binary_path))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Run `<binary> --version` and extract the version number
+pub(super) fn query_cli_version(binary_path: &std::path::Path) -> Option<String> {
+    // Use the binary directly - shell wrapper causes PowerShell parsing issues on Windows
+    match silent_command(binary_path).arg("--version").output() {
+        Ok(output) => {
+            if output.status.success() {
+                let version_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                log::trace!("Claude CLI raw version output: {}", version_str);
+                // claude --version returns just the version number like "1.0.28"
+                // but handle any prefix like "v1.0.28" or "Claude CLI 1.0.28"
+                let version = extract_version_number(&version_str);
+                log::trace!("Claude CLI parsed version: {}", version);
+                Some(version)
+            } else {
+                log::warn!("Failed to get Claude CLI version");
+                None
+            }
+        }
+        Err(e) => {
+            log::warn!("Failed to execute Claude CLI: {}", e);
+            None
+        }
+    }
+}
+
+/// Check that a system Claude CLI binary meets [`MIN_CLAUDE_CLI_VERSION`]
+///
+/// Unlike [`ensure_supported_cli_version`], an unknown version is an error:
+/// a system binary that can't report its version isn't selected at all.
+pub(super) fn check_system_cli_version(
+    binary_path: &std::path::Path,
+    version: Option<String>,
+) -> Result<(), String> {
+    let version = version.ok_or_else(|| {
+        format!(
+            "Failed to run {} --version. Check the system Claude CLI works, or switch back to the embedded CLI.",
+            binary_path.display()
+        )
+    })?;
+
+    if !is_supported_cli_version(&version) {
+        return Err(unsupported_version_error(&version, binary_path));
+    }
+    Ok(())
+}
+
 /// Base URL for Claude CLI binary distribution
 const CLAUDE_DIST_BUCKET: &str =
     "https://storage.googleapis.com/claude-code-dist-86c565f3-f756-42ad-8dfa-d59b1c096819/claude-code-releases";
@@ -43,6 +191,30 @@ pub struct ClaudeCliStatus {
     pub version: Option<String>,
     /// Path to the CLI binary (if installed)
     pub path: Option<String>,
+    /// Diagnosis if the installed binary was built for a different platform
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform_mismatch: Option<String>,
+    /// Which binary is active: "embedded" (downloaded by Jean) or "system"
+    pub source: String,
+    /// Why the selected system binary can't be used (not found, too old, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_error: Option<String>,
+    /// True if the installed version is below MIN_CLAUDE_CLI_VERSION
+    #[serde(default)]
+    pub unsupported: bool,
+}
+
+/// Result of checking the installed binary against the host platform
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlatformCheck {
+    /// Whether the binary matches the host OS and architecture
+    pub compatible: bool,
+    /// Detected binary format and architecture (e.g., "ELF x86_64")
+    pub binary: Option<String>,
+    /// Host OS and architecture (e.g., "linux aarch64")
+    pub host: String,
+    /// Human-readable diagnosis when incompatible
+    pub message: Option<String>,
 }
 
 /// Information about a Claude CLI release from GitHub
@@ -73,8 +245,31 @@ pub struct InstallProgress {
 #[tauri::command]
 pub async fn check_claude_cli_installed(app: AppHandle) -> Result<ClaudeCliStatus, String> {
     log::trace!("Checking Claude CLI installation status");
+    install_status(&app, get_cli_binary_path(&app))
+}
 
-    let binary_path = get_cli_binary_path(&app)?;
+/// Installation status for an already-resolved binary path
+fn install_status(
+    app: &AppHandle,
+    binary_path: Result<std::path::PathBuf, String>,
+) -> Result<ClaudeCliStatus, String> {
+    let source = active_cli_source(app).to_string();
+    let binary_path = match binary_path {
+        Ok(path) => path,
+        Err(e) if source == CLI_SOURCE_SYSTEM => {
+            log::warn!("System Claude CLI unavailable: {e}");
+            return Ok(ClaudeCliStatus {
+                installed: false,
+                version: None,
+                path: None,
+                platform_mismatch: None,
+                source,
+                source_error: Some(e),
+                unsupported: false,
+            });
+        }
+        Err(e) => return Err(e),
+    };
 
     if !binary_path.exists() {
         log::trace!("Claude CLI not found at {:?}", binary_path);
@@ -82,39 +277,268 @@ pub async fn check_claude_cli_installed(app: AppHandle) -> Result<ClaudeCliStatu
             installed: false,
             version: None,
             path: None,
+            platform_mismatch: None,
+            source,
+            source_error: None,
+            unsupported: false,
         });
     }
 
     // Try to get the version by running claude --version
-    // Use the binary directly - shell wrapper causes PowerShell parsing issues on Windows
-    let version = match silent_command(&binary_path).arg("--version").output() {
-        Ok(output) => {
-            if output.status.success() {
-                let version_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                log::trace!("Claude CLI raw version output: {}", version_str);
-                // claude --version returns just the version number like "1.0.28"
-                // but handle any prefix like "v1.0.28" or "Claude CLI 1.0.28"
-                let version = extract_version_number(&version_str);
-                log::trace!("Claude CLI parsed version: {}", version);
-                Some(version)
-            } else {
-                log::warn!("Failed to get Claude CLI version");
-                None
-            }
-        }
-        Err(e) => {
-            log::warn!("Failed to execute Claude CLI: {}", e);
-            None
-        }
+    let version = query_cli_version(&binary_path);
+    let unsupported = version
+        .as_deref()
+        .is_some_and(|v| !is_supported_cli_version(v));
+    if unsupported {
+        log::warn!("Claude CLI {version:?} is below minimum version {MIN_CLAUDE_CLI_VERSION}");
+    }
+
+    // System installs are managed by the user (and npm's are `#!` scripts),
+    // so only Jean's own download is checked against the host platform
+    let platform_mismatch = if source == CLI_SOURCE_SYSTEM {
+        None
+    } else {
+        check_binary_platform(&binary_path).message
     };
 
     Ok(ClaudeCliStatus {
         installed: true,
+        unsupported,
         version,
         path: Some(binary_path.to_string_lossy().to_string()),
+        platform_mismatch,
+        source,
+        source_error: None,
+    })
+}
+
+/// Installed vs latest stable Claude CLI version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateInfo {
+    /// Installed version (None if not installed or the version is unknown)
+    pub current: Option<String>,
+    /// Latest stable version from the distribution bucket
+    pub latest: String,
+    /// Whether `latest` is newer than `current`
+    pub update_available: bool,
+}
+
+/// Check whether a newer stable Claude CLI than the installed one is available
+///
+/// A missing CLI is not an error: it's reported as `current: None` with an
+/// update available, so the UI can offer an install.
+#[tauri::command]
+pub async fn claude_update_available(app: AppHandle) -> Result<UpdateInfo, String> {
+    log::trace!("Checking for Claude CLI updates");
+
+    let current = get_cli_binary_path(&app)
+        .ok()
+        .filter(|path| path.exists())
+        .and_then(|path| query_cli_version(&path));
+    let latest = extract_version_number(&fetch_latest_version().await?);
+    let update_available = is_update_available(current.as_deref(), &latest);
+
+    log::trace!("Claude CLI {current:?}, latest {latest}, update available: {update_available}");
+    Ok(UpdateInfo {
+        current,
+        latest,
+        update_available,
     })
 }
 
+/// Switch between the embedded Claude CLI and a system-installed one
+///
+/// For "system", `system_path` pins a specific binary; when omitted the CLI is
+/// looked up on PATH. The binary is validated (exists, minimum version) before
+/// the preference is saved. Returns the status for the new selection.
+#[tauri::command]
+pub async fn set_claude_cli_source(
+    app: AppHandle,
+    source: String,
+    system_path: Option<String>,
+) -> Result<ClaudeCliStatus, String> {
+    let system_path = system_path
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+
+    match source.as_str() {
+        CLI_SOURCE_EMBEDDED => {}
+        CLI_SOURCE_SYSTEM => {
+            let path = resolve_system_cli(system_path.as_deref())?;
+            log::info!("Using system Claude CLI at {}", path.display());
+        }
+        other => {
+            return Err(format!(
+                "Unknown Claude CLI source '{other}' (expected '{CLI_SOURCE_EMBEDDED}' or '{CLI_SOURCE_SYSTEM}')"
+            ))
+        }
+    }
+
+    let mut preferences = crate::load_preferences(app.clone()).await?;
+    preferences.claude_cli_source = Some(source);
+    preferences.claude_cli_path = system_path;
+    crate::save_preferences(app.clone(), preferences).await?;
+
+    check_claude_cli_installed(app).await
+}
+
+/// Verify the installed Claude CLI binary was built for this machine
+///
+/// Reads the executable header (ELF/Mach-O/PE) instead of running the binary,
+/// so a wrong-architecture install is diagnosed before a confusing spawn failure.
+#[tauri::command]
+pub async fn verify_claude_cli_platform(app: AppHandle) -> Result<PlatformCheck, String> {
+    let binary_path = get_cli_binary_path(&app)?;
+    if !binary_path.exists() {
+        return Err("Claude CLI not installed".to_string());
+    }
+    Ok(check_binary_platform(&binary_path))
+}
+
+/// Binary format and architecture detected from an executable header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BinaryTarget {
+    /// Target OS family ("linux", "macos", "windows")
+    os: &'static str,
+    /// Format name for messages ("ELF", "Mach-O", "PE")
+    format: &'static str,
+    /// Architecture using Rust's naming ("x86_64", "aarch64", "universal")
+    arch: &'static str,
+}
+
+/// Detect the target of an executable from its first bytes
+fn detect_binary_target(header: &[u8]) -> Option<BinaryTarget> {
+    let u16_le = |at: usize| -> Option<u16> {
+        header
+            .get(at..at + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+    };
+    let u32_le = |at: usize| -> Option<u32> {
+        header
+            .get(at..at + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    };
+
+    // ELF: 0x7F 'E' 'L' 'F', e_machine at offset 18
+    if header.starts_with(b"\x7fELF") {
+        let arch = match u16_le(18)? {
+            0x3E => "x86_64",
+            0xB7 => "aarch64",
+            _ => "unknown",
+        };
+        return Some(BinaryTarget {
+            os: "linux",
+            format: "ELF",
+            arch,
+        });
+    }
+
+    // Mach-O 64-bit (little-endian magic 0xFEEDFACF), cputype follows the magic
+    if u32_le(0)? == 0xFEED_FACF {
+        let arch = match u32_le(4)? {
+            0x0100_0007 => "x86_64",
+            0x0100_000C => "aarch64",
+            _ => "unknown",
+        };
+        return Some(BinaryTarget {
+            os: "macos",
+            format: "Mach-O",
+            arch,
+        });
+    }
+
+    // Mach-O universal binary (big-endian magic 0xCAFEBABE) runs on both architectures
+    if header.starts_with(&[0xCA, 0xFE, 0xBA, 0xBE]) {
+        return Some(BinaryTarget {
+            os: "macos",
+            format: "Mach-O",
+            arch: "universal",
+        });
+    }
+
+    // PE: "MZ" DOS header, e_lfanew at 0x3C points at "PE\0\0" + machine
+    if header.starts_with(b"MZ") {
+        let pe_offset = u32_le(0x3C)? as usize;
+        if header.get(pe_offset..pe_offset + 4)? != b"PE\0\0" {
+            return None;
+        }
+        let arch = match u16_le(pe_offset + 4)? {
+            0x8664 => "x86_64",
+            0xAA64 => "aarch64",
+            _ => "unknown",
+        };
+        return Some(BinaryTarget {
+            os: "windows",
+            format: "PE",
+            arch,
+        });
+    }
+
+    None
+}
+
+/// Format a byte count as megabytes for progress messages (e.g., "12.3 MB")
+fn format_megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+/// Compare a binary's header against the host OS and architecture
+fn check_binary_platform(binary_path: &std::path::Path) -> PlatformCheck {
+    use std::io::Read;
+
+    let host_os = std::env::consts::OS;
+    let host_arch = std::env::consts::ARCH;
+    let host = format!("{host_os} {host_arch}");
+
+    // 4KB covers the ELF/Mach-O headers and the PE header offset in practice
+    let mut header = Vec::with_capacity(4096);
+    let read = std::fs::File::open(binary_path).and_then(|f| f.take(4096).read_to_end(&mut header));
+    if let Err(e) = read {
+        return PlatformCheck {
+            compatible: false,
+            binary: None,
+            host,
+            message: Some(format!("Failed to read installed binary: {e}")),
+        };
+    }
+
+    // Script launchers (npm's `#!/usr/bin/env node` shim) run on any platform
+    if header.starts_with(b"#!") {
+        return PlatformCheck {
+            compatible: true,
+            binary: None,
+            host,
+            message: None,
+        };
+    }
+
+    match detect_binary_target(&header) {
+        Some(target) => {
+            let arch_ok = target.arch == host_arch || target.arch == "universal";
+            let compatible = target.os == host_os && arch_ok;
+            let binary = format!("{} {}", target.format, target.arch);
+            let message = (!compatible).then(|| {
+                format!("Installed binary is {binary} ({}) but this machine is {host}. Please reinstall Claude CLI.", target.os)
+            });
+            PlatformCheck {
+                compatible,
+                binary: Some(binary),
+                host,
+                message,
+            }
+        }
+        None => PlatformCheck {
+            compatible: false,
+            binary: None,
+            host,
+            message: Some(
+                "Installed binary is not a recognized executable. Please reinstall Claude CLI."
+                    .to_string(),
+            ),
+        },
+    }
+}
+
 /// npm package metadata for version listing
 #[derive(Debug, Deserialize)]
 struct NpmPackageInfo {
@@ -313,7 +737,8 @@ pub async fn install_claude_cli(app: AppHandle, version: Option<String>) -> Resu
     }
 
     let _cli_dir = ensure_cli_dir(&app)?;
-    let binary_path = get_cli_binary_path(&app)?;
+    // Always install the embedded copy, even when a system CLI is selected
+    let binary_path = get_embedded_cli_binary_path(&app)?;
 
     // Emit progress: starting
     emit_progress(&app, "starting", "Preparing installation...", 0);
@@ -358,7 +783,7 @@ pub async fn install_claude_cli(app: AppHandle, version: Option<String>) -> Resu
 
     // Download the binary
     let client = reqwest::Client::new();
-    let response = client
+    let mut response = client
         .get(&download_url)
         .send()
         .await
@@ -371,11 +796,46 @@ pub async fn install_claude_cli(app: AppHandle, version: Option<String>) -> Resu
         ));
     }
 
-    // Get the binary content
-    let binary_content = response
-        .bytes()
+    // Stream the binary content, reporting progress in the 25-55% band
+    let total = response.content_length().filter(|t| *t > 0);
+    let mut binary_content: Vec<u8> = Vec::with_capacity(total.unwrap_or(0) as usize);
+    let mut last_reported = None;
+    while let Some(chunk) = response
+        .chunk()
         .await
-        .map_err(|e| format!("Failed to read binary content: {e}"))?;
+        .map_err(|e| format!("Failed to read binary content: {e}"))?
+    {
+        binary_content.extend_from_slice(&chunk);
+        let downloaded = binary_content.len() as u64;
+
+        match total {
+            Some(total) => {
+                let download_percent = downloaded.min(total) * 100 / total;
+                if last_reported != Some(download_percent) {
+                    last_reported = Some(download_percent);
+                    emit_progress(
+                        &app,
+                        "downloading",
+                        &format!("Downloading Claude CLI... {download_percent}%"),
+                        25 + (download_percent * 30 / 100) as u8,
+                    );
+                }
+            }
+            None => {
+                // No Content-Length: report size every MB without a percentage
+                let megabytes = downloaded / (1024 * 1024);
+                if last_reported != Some(megabytes) {
+                    last_reported = Some(megabytes);
+                    emit_progress(
+                        &app,
+                        "downloading",
+                        &format!("Downloaded {}...", format_megabytes(downloaded)),
+                        25,
+                    );
+                }
+            }
+        }
+    }
 
     log::trace!(
         "Downloaded {} bytes, saving to {:?}",
@@ -451,19 +911,28 @@ pub async fn check_claude_cli_auth(app: AppHandle) -> Result<ClaudeAuthStatus, S
     log::trace!("Checking Claude CLI authentication status");
 
     let binary_path = get_cli_binary_path(&app)?;
+    query_cli_auth(&binary_path)
+}
+
+/// Auth status when there is no binary to ask
+fn not_installed_auth_status() -> ClaudeAuthStatus {
+    ClaudeAuthStatus {
+        authenticated: false,
+        error: Some("Claude CLI not installed".to_string()),
+    }
+}
 
+/// Run a trivial query against `binary_path` to see if the CLI is authenticated
+fn query_cli_auth(binary_path: &std::path::Path) -> Result<ClaudeAuthStatus, String> {
     if !binary_path.exists() {
-        return Ok(ClaudeAuthStatus {
-            authenticated: false,
-            error: Some("Claude CLI not installed".to_string()),
-        });
+        return Ok(not_installed_auth_status());
     }
 
     // Run a simple non-interactive query to check if authenticated
     // Use --print to avoid interactive mode, and a simple prompt
     log::trace!("Running auth check: {:?}", binary_path);
 
-    let output = silent_command(&binary_path)
+    let output = silent_command(binary_path)
         .args([
             "--print",
             "--output-format",
@@ -491,6 +960,284 @@ pub async fn check_claude_cli_auth(app: AppHandle) -> Result<ClaudeAuthStatus, S
     }
 }
 
+/// Installation and authentication status in one payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaudeStatus {
+    #[serde(flatten)]
+    pub install: ClaudeCliStatus,
+    /// Whether the CLI is authenticated (always false when not installed)
+    pub authenticated: bool,
+    /// Why authentication failed or couldn't be checked
+    pub auth_error: Option<String>,
+}
+
+impl ClaudeStatus {
+    fn new(install: ClaudeCliStatus, auth: ClaudeAuthStatus) -> Self {
+        // An auth result for a binary that isn't usable would be misleading
+        let auth = if install.installed {
+            auth
+        } else {
+            not_installed_auth_status()
+        };
+        Self {
+            install,
+            authenticated: auth.authenticated,
+            auth_error: auth.error,
+        }
+    }
+}
+
+/// Check installation and authentication together
+///
+/// Resolves the binary once and runs the auth query on a separate thread
+/// while `--version` runs, so Settings gets both without two round trips.
+/// `check_claude_cli_installed` and `check_claude_cli_auth` remain available.
+#[tauri::command]
+pub async fn claude_cli_status(app: AppHandle) -> Result<ClaudeStatus, String> {
+    log::trace!("Checking Claude CLI installation and auth status");
+
+    let binary_path = get_cli_binary_path(&app);
+    let auth_check = binary_path
+        .as_ref()
+        .ok()
+        .filter(|path| path.exists())
+        .cloned()
+        .map(|path| std::thread::spawn(move || query_cli_auth(&path)));
+
+    let install = install_status(&app, binary_path)?;
+    let auth = match auth_check {
+        Some(handle) => handle
+            .join()
+            .map_err(|_| "Claude CLI auth check panicked".to_string())??,
+        None => not_installed_auth_status(),
+    };
+
+    Ok(ClaudeStatus::new(install, auth))
+}
+
+/// Outcome of one diagnosis step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosisStatus {
+    Pass,
+    Fail,
+    /// Not run because an earlier step failed
+    Skipped,
+}
+
+/// A named check in a [`DiagnosisReport`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosisStep {
+    /// Step identifier ("binary", "version", "auth", "stream")
+    pub name: String,
+    pub status: DiagnosisStatus,
+    /// What was found, or why the step failed
+    pub detail: Option<String>,
+}
+
+/// Result of diagnosing the Claude CLI end to end
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosisReport {
+    /// True if every step passed
+    pub healthy: bool,
+    pub steps: Vec<DiagnosisStep>,
+}
+
+impl DiagnosisReport {
+    fn new(steps: Vec<DiagnosisStep>) -> Self {
+        Self {
+            healthy: steps.iter().all(|s| s.status == DiagnosisStatus::Pass),
+            steps,
+        }
+    }
+}
+
+impl DiagnosisStep {
+    fn new(name: &str, result: Result<String, String>) -> Self {
+        let (status, detail) = match result {
+            Ok(detail) => (DiagnosisStatus::Pass, detail),
+            Err(detail) => (DiagnosisStatus::Fail, detail),
+        };
+        Self {
+            name: name.to_string(),
+            status,
+            detail: Some(detail),
+        }
+    }
+
+    fn skipped(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            status: DiagnosisStatus::Skipped,
+            detail: None,
+        }
+    }
+
+    fn passed(&self) -> bool {
+        self.status == DiagnosisStatus::Pass
+    }
+}
+
+/// How long the stream check waits for the CLI's init event
+const STREAM_CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Check the binary exists, is executable, and was built for this machine
+fn diagnose_binary(binary_path: &std::path::Path) -> Result<String, String> {
+    let metadata = std::fs::metadata(binary_path)
+        .map_err(|_| format!("Claude CLI not found at {}", binary_path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o111 == 0 {
+            return Err(format!("{} is not executable", binary_path.display()));
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = metadata;
+
+    let platform = check_binary_platform(binary_path);
+    match platform.message {
+        Some(message) if !platform.compatible => Err(message),
+        _ => Ok(binary_path.display().to_string()),
+    }
+}
+
+/// Check `--version` reports a supported version
+fn diagnose_version(binary_path: &std::path::Path) -> Result<String, String> {
+    let version = query_cli_version(binary_path)
+        .ok_or_else(|| format!("Failed to run {} --version", binary_path.display()))?;
+    if !is_supported_cli_version(&version) {
+        return Err(unsupported_version_error(&version, binary_path));
+    }
+    Ok(version)
+}
+
+/// True if a stream-json line is the CLI's `system`/`init` event
+fn is_init_event(line: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(line)
+        .map(|msg| {
+            msg.get("type").and_then(|v| v.as_str()) == Some("system")
+                && msg.get("subtype").and_then(|v| v.as_str()) == Some("init")
+        })
+        .unwrap_or(false)
+}
+
+/// Run a trivial stream-json prompt in a temp dir and wait for the init event
+fn diagnose_stream(binary_path: &std::path::Path) -> Result<String, String> {
+    let work_dir = std::env::temp_dir().join(format!("jean-diagnose-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&work_dir).map_err(|e| format!("Failed to create temp dir: {e}"))?;
+    let result = run_stream_check(binary_path, &work_dir);
+    let _ = std::fs::remove_dir_all(&work_dir);
+    result
+}
+
+fn run_stream_check(
+    binary_path: &std::path::Path,
+    work_dir: &std::path::Path,
+) -> Result<String, String> {
+    use std::io::BufRead;
+    use std::process::Stdio;
+
+    let mut child = silent_command(binary_path)
+        .args([
+            "--print",
+            "--output-format",
+            "stream-json",
+            "--verbose",
+            "-p",
+            "Reply with just the word OK",
+        ])
+        .current_dir(work_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start Claude CLI: {e}"))?;
+
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| "Failed to capture Claude CLI output".to_string())?;
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let found = std::io::BufReader::new(stdout)
+            .lines()
+            .map_while(Result::ok)
+            .any(|line| is_init_event(&line));
+        let _ = tx.send(found);
+    });
+
+    let started = std::time::Instant::now();
+    let result = match rx.recv_timeout(STREAM_CHECK_TIMEOUT) {
+        Ok(true) => Ok(format!(
+            "Received init event after {}ms",
+            started.elapsed().as_millis()
+        )),
+        Ok(false) => Err("Claude CLI exited without emitting a stream-json init event".to_string()),
+        Err(_) => Err(format!(
+            "No stream-json init event within {}s",
+            STREAM_CHECK_TIMEOUT.as_secs()
+        )),
+    };
+
+    let _ = child.kill();
+    let _ = child.wait();
+    result
+}
+
+/// Diagnose the active Claude CLI step by step
+///
+/// Checks the binary, its version, authentication, and that a trivial
+/// stream-json run in a temp dir emits its init event. Steps after a
+/// failure are reported as skipped so the UI can show a checklist.
+#[tauri::command]
+pub async fn diagnose_claude_cli(app: AppHandle) -> Result<DiagnosisReport, String> {
+    log::trace!("Diagnosing Claude CLI");
+
+    let binary_path = get_cli_binary_path(&app)?;
+    let mut steps = vec![DiagnosisStep::new("binary", diagnose_binary(&binary_path))];
+
+    if steps[0].passed() {
+        steps.push(DiagnosisStep::new(
+            "version",
+            diagnose_version(&binary_path),
+        ));
+    } else {
+        steps.push(DiagnosisStep::skipped("version"));
+    }
+
+    if steps[1].passed() {
+        let auth = check_claude_cli_auth(app.clone()).await?;
+        steps.push(DiagnosisStep::new(
+            "auth",
+            if auth.authenticated {
+                Ok("Authenticated".to_string())
+            } else {
+                Err(auth
+                    .error
+                    .unwrap_or_else(|| "Not authenticated".to_string()))
+            },
+        ));
+    } else {
+        steps.push(DiagnosisStep::skipped("auth"));
+    }
+
+    if steps[2].passed() {
+        let path = binary_path.clone();
+        let stream = tauri::async_runtime::spawn_blocking(move || diagnose_stream(&path))
+            .await
+            .map_err(|e| format!("Stream check failed to run: {e}"))?;
+        steps.push(DiagnosisStep::new("stream", stream));
+    } else {
+        steps.push(DiagnosisStep::skipped("stream"));
+    }
+
+    let report = DiagnosisReport::new(steps);
+    log::trace!("Claude CLI diagnosis healthy: {}", report.healthy);
+    Ok(report)
+}
+
 /// Helper function to emit installation progress events
 fn emit_progress(app: &AppHandle, stage: &str, message: &str, percent: u8) {
     let progress = InstallProgress {
@@ -503,3 +1250,252 @@ fn emit_progress(app: &AppHandle, stage: &str, message: &str, percent: u8) {
         log::warn!("Failed to emit install progress: {}", e);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_init_event() {
+        assert!(is_init_event(
+            r#"{"type":"system","subtype":"init","session_id":"abc"}"#
+        ));
+        assert!(!is_init_event(r#"{"type":"assistant","message":{}}"#));
+        assert!(!is_init_event("not json"));
+    }
+
+    #[test]
+    fn test_diagnosis_report_healthy_only_when_all_pass() {
+        let passing = DiagnosisReport::new(vec![
+            DiagnosisStep::new("binary", Ok("/usr/bin/claude".to_string())),
+            DiagnosisStep::new("version", Ok("2.1.0".to_string())),
+        ]);
+        assert!(passing.healthy);
+
+        let failing = DiagnosisReport::new(vec![
+            DiagnosisStep::new("binary", Err("not found".to_string())),
+            DiagnosisStep::skipped("version"),
+        ]);
+        assert!(!failing.healthy);
+        assert_eq!(failing.steps[0].status, DiagnosisStatus::Fail);
+        assert_eq!(failing.steps[0].detail.as_deref(), Some("not found"));
+        assert_eq!(failing.steps[1].status, DiagnosisStatus::Skipped);
+    }
+
+    #[test]
+    fn test_diagnose_binary_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = diagnose_binary(&dir.path().join("claude")).unwrap_err();
+        assert!(err.contains("not found"), "{err}");
+    }
+
+    #[test]
+    fn test_diagnose_binary_accepts_script_install() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("claude");
+        std::fs::write(&script, "#!/usr/bin/env node\nrequire('./cli.js')\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        assert_eq!(
+            diagnose_binary(&script).unwrap(),
+            script.display().to_string()
+        );
+    }
+
+    #[test]
+    fn test_detect_elf_arch() {
+        let mut header = vec![0u8; 64];
+        header[..4].copy_from_slice(b"\x7fELF");
+        header[18] = 0x3E;
+        let target = detect_binary_target(&header).unwrap();
+        assert_eq!((target.os, target.arch), ("linux", "x86_64"));
+
+        header[18] = 0xB7;
+        assert_eq!(detect_binary_target(&header).unwrap().arch, "aarch64");
+    }
+
+    #[test]
+    fn test_detect_macho_arch() {
+        let mut header = vec![0u8; 32];
+        header[..4].copy_from_slice(&0xFEED_FACFu32.to_le_bytes());
+        header[4..8].copy_from_slice(&0x0100_000Cu32.to_le_bytes());
+        let target = detect_binary_target(&header).unwrap();
+        assert_eq!((target.os, target.arch), ("macos", "aarch64"));
+
+        let fat = [0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 2];
+        assert_eq!(detect_binary_target(&fat).unwrap().arch, "universal");
+    }
+
+    #[test]
+    fn test_detect_pe_arch() {
+        let mut header = vec![0u8; 0x90];
+        header[..2].copy_from_slice(b"MZ");
+        header[0x3C..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+        header[0x80..0x84].copy_from_slice(b"PE\0\0");
+        header[0x84..0x86].copy_from_slice(&0xAA64u16.to_le_bytes());
+        let target = detect_binary_target(&header).unwrap();
+        assert_eq!((target.os, target.arch), ("windows", "aarch64"));
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        use std::cmp::Ordering;
+
+        assert_eq!(compare_versions("1.0.28", "1.0.28"), Ordering::Equal);
+        assert_eq!(compare_versions("v1.0.28", "1.0.28"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0.9", "1.0.28"), Ordering::Less);
+        assert_eq!(compare_versions("2.0.0", "1.9.99"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0", "1.0.0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_is_supported_cli_version() {
+        assert!(is_supported_cli_version(MIN_CLAUDE_CLI_VERSION));
+        assert!(is_supported_cli_version("2.0.14"));
+        assert!(!is_supported_cli_version("0.2.125"));
+        // A prerelease of the minimum version comes before it
+        assert!(!is_supported_cli_version(&format!(
+            "{MIN_CLAUDE_CLI_VERSION}-beta.1"
+        )));
+    }
+
+    #[test]
+    fn test_compare_versions_prerelease_sorts_before_release() {
+        use std::cmp::Ordering;
+
+        assert_eq!(compare_versions("2.0.0-beta.1", "2.0.0"), Ordering::Less);
+        assert_eq!(compare_versions("2.0.0", "2.0.0-beta.1"), Ordering::Greater);
+        assert_eq!(compare_versions("2.0.1-beta.1", "2.0.0"), Ordering::Greater);
+        assert_eq!(
+            compare_versions("2.0.0-alpha.1", "2.0.0-beta.1"),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_compare_versions_prerelease_identifiers() {
+        use std::cmp::Ordering;
+
+        assert_eq!(
+            compare_versions("2.0.0-beta.2", "2.0.0-beta.10"),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_versions("2.0.0-beta.10", "2.0.0-rc.1"),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_versions("2.0.0-beta", "2.0.0-beta.1"),
+            Ordering::Less
+        );
+        assert_eq!(compare_versions("2.0.0-1", "2.0.0-alpha"), Ordering::Less);
+        assert_eq!(compare_versions("2.0.0+build.5", "2.0.0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_is_update_available() {
+        // Not installed
+        assert!(is_update_available(None, "2.0.14"));
+        // Equal
+        assert!(!is_update_available(Some("2.0.14"), "2.0.14"));
+        assert!(!is_update_available(Some("v2.0.14"), "2.0.14"));
+        // Latest is newer
+        assert!(is_update_available(Some("2.0.9"), "2.0.14"));
+        // Installed is newer (e.g., a pinned prerelease of the next version)
+        assert!(!is_update_available(Some("2.1.0"), "2.0.14"));
+        assert!(!is_update_available(Some("2.1.0-beta.1"), "2.0.14"));
+        // A stable release supersedes its own prerelease
+        assert!(is_update_available(Some("2.0.14-beta.3"), "2.0.14"));
+    }
+
+    fn installed_status(installed: bool) -> ClaudeCliStatus {
+        ClaudeCliStatus {
+            installed,
+            version: installed.then(|| "2.0.14".to_string()),
+            path: installed.then(|| "/bin/claude".to_string()),
+            platform_mismatch: None,
+            source: CLI_SOURCE_EMBEDDED.to_string(),
+            source_error: None,
+            unsupported: false,
+        }
+    }
+
+    #[test]
+    fn test_claude_status_combines_install_and_auth() {
+        let status = ClaudeStatus::new(
+            installed_status(true),
+            ClaudeAuthStatus {
+                authenticated: true,
+                error: None,
+            },
+        );
+        assert!(status.authenticated);
+        assert_eq!(status.auth_error, None);
+
+        // Serialized flat, so it reads like ClaudeCliStatus plus auth fields
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["installed"], true);
+        assert_eq!(json["version"], "2.0.14");
+        assert_eq!(json["authenticated"], true);
+
+        let status = ClaudeStatus::new(
+            installed_status(true),
+            ClaudeAuthStatus {
+                authenticated: false,
+                error: Some("Invalid API key".to_string()),
+            },
+        );
+        assert!(!status.authenticated);
+        assert_eq!(status.auth_error.as_deref(), Some("Invalid API key"));
+
+        // Not installed never reports as authenticated
+        let status = ClaudeStatus::new(
+            installed_status(false),
+            ClaudeAuthStatus {
+                authenticated: true,
+                error: None,
+            },
+        );
+        assert!(!status.install.installed);
+        assert!(!status.authenticated);
+        assert_eq!(
+            status.auth_error.as_deref(),
+            Some("Claude CLI not installed")
+        );
+    }
+
+    #[test]
+    fn test_format_megabytes() {
+        assert_eq!(format_megabytes(0), "0.0 MB");
+        assert_eq!(format_megabytes(1024 * 1024), "1.0 MB");
+        assert_eq!(format_megabytes(5 * 1024 * 1024 + 512 * 1024), "5.5 MB");
+    }
+
+    #[test]
+    fn test_detect_unknown_binary() {
+        assert!(detect_binary_target(b"#!/bin/sh\necho hi\n").is_none());
+        assert!(detect_binary_target(&[]).is_none());
+    }
+
+    #[test]
+    fn test_check_binary_platform_accepts_scripts() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("claude");
+        std::fs::write(&script, "#!/usr/bin/env node\nrequire('./cli.js')\n").unwrap();
+        let check = check_binary_platform(&script);
+        assert!(check.compatible);
+        assert!(check.binary.is_none());
+        assert!(check.message.is_none());
+
+        // Anything else that isn't a known executable still asks for a reinstall
+        let garbage = dir.path().join("garbage");
+        std::fs::write(&garbage, "not an executable").unwrap();
+        let check = check_binary_platform(&garbage);
+        assert!(!check.compatible);
+        assert!(check.message.unwrap().contains("reinstall"));
+    }
+}
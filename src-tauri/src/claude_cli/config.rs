@@ -1,7 +1,11 @@
 //! Configuration and path management for the embedded Claude CLI
 
-use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tauri::AppHandle;
 
 /// Directory name for storing the Claude CLI binary
 pub const CLI_DIR_NAME: &str = "claude-cli";
@@ -12,24 +16,131 @@ pub const CLI_BINARY_NAME: &str = "claude.exe";
 #[cfg(not(windows))]
 pub const CLI_BINARY_NAME: &str = "claude";
 
+/// Names a system-installed Claude CLI goes by on PATH, in lookup order
+/// (npm installs a `claude.cmd` shim on Windows and a `claude` script elsewhere)
+#[cfg(windows)]
+const SYSTEM_CLI_NAMES: &[&str] = &["claude.exe", "claude.cmd", "claude"];
+#[cfg(not(windows))]
+const SYSTEM_CLI_NAMES: &[&str] = &[CLI_BINARY_NAME];
+
+/// CLI source using the binary Jean downloads into its app data directory
+pub const CLI_SOURCE_EMBEDDED: &str = "embedded";
+
+/// CLI source using a user-installed binary (explicit path or found on PATH)
+pub const CLI_SOURCE_SYSTEM: &str = "system";
+
+/// `--version` results by binary path and modification time, so an upgrade in
+/// place is re-checked without spawning `--version` on every run
+static CLI_VERSIONS: Lazy<Mutex<HashMap<(PathBuf, Option<SystemTime>), Option<String>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
 /// Get the directory where Claude CLI is installed
 ///
 /// Returns: `~/Library/Application Support/jean/claude-cli/`
 pub fn get_cli_dir(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(app)?;
     Ok(app_data_dir.join(CLI_DIR_NAME))
 }
 
-/// Get the full path to the Claude CLI binary
+/// Get the full path to the embedded Claude CLI binary (the install target)
 ///
 /// Returns: `~/Library/Application Support/jean/claude-cli/claude`
-pub fn get_cli_binary_path(app: &AppHandle) -> Result<PathBuf, String> {
+pub fn get_embedded_cli_binary_path(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(get_cli_dir(app)?.join(CLI_BINARY_NAME))
 }
 
+/// Get the full path to the Claude CLI binary Jean should run
+///
+/// This is the embedded binary unless preferences select the system CLI, in
+/// which case the configured path (or a PATH lookup) is used after checking it
+/// meets the minimum supported version.
+pub fn get_cli_binary_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let (source, system_path) = cli_source_preferences(app);
+    if source == CLI_SOURCE_SYSTEM {
+        resolve_system_cli(system_path.as_deref())
+    } else {
+        get_embedded_cli_binary_path(app)
+    }
+}
+
+/// Active CLI source from preferences ("embedded" or "system")
+pub fn active_cli_source(app: &AppHandle) -> &'static str {
+    if cli_source_preferences(app).0 == CLI_SOURCE_SYSTEM {
+        CLI_SOURCE_SYSTEM
+    } else {
+        CLI_SOURCE_EMBEDDED
+    }
+}
+
+/// Read `claude_cli_source` and `claude_cli_path` straight from preferences.json
+///
+/// Avoids `load_preferences` (which migrates and may rewrite the file) since
+/// this runs every time a CLI path is resolved.
+fn cli_source_preferences(app: &AppHandle) -> (String, Option<String>) {
+    let prefs: Option<serde_json::Value> = crate::get_preferences_path(app)
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok());
+    let field = |name: &str| {
+        prefs
+            .as_ref()
+            .and_then(|p| p.get(name))
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+
+    (
+        field("claude_cli_source").unwrap_or_else(|| CLI_SOURCE_EMBEDDED.to_string()),
+        field("claude_cli_path"),
+    )
+}
+
+/// Locate a system Claude CLI and make sure it's recent enough to use
+///
+/// `explicit_path` wins over a PATH lookup when set.
+pub fn resolve_system_cli(explicit_path: Option<&str>) -> Result<PathBuf, String> {
+    let path = match explicit_path.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(p) => {
+            let path = PathBuf::from(p);
+            if !path.is_file() {
+                return Err(format!("Claude CLI not found at {}", path.display()));
+            }
+            path
+        }
+        None => SYSTEM_CLI_NAMES
+            .iter()
+            .find_map(|name| which::which(name).ok())
+            .ok_or_else(|| {
+                "Claude CLI not found on PATH. Install it or switch back to the embedded CLI."
+                    .to_string()
+            })?,
+    };
+
+    super::commands::check_system_cli_version(&path, cached_cli_version(&path))?;
+    Ok(path)
+}
+
+/// Version reported by `<path> --version`, cached until the binary changes
+pub fn cached_cli_version(path: &Path) -> Option<String> {
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let key = (path.to_path_buf(), modified);
+
+    if let Some(version) = CLI_VERSIONS
+        .lock()
+        .ok()
+        .and_then(|versions| versions.get(&key).cloned())
+    {
+        return version;
+    }
+
+    let version = super::commands::query_cli_version(path);
+    if let Ok(mut versions) = CLI_VERSIONS.lock() {
+        versions.insert(key, version.clone());
+    }
+    version
+}
+
 /// Ensure the CLI directory exists, creating it if necessary
 pub fn ensure_cli_dir(app: &AppHandle) -> Result<PathBuf, String> {
     let cli_dir = get_cli_dir(app)?;
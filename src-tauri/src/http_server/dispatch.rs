@@ -483,17 +483,9 @@ pub async fn dispatch_command(
         }
         "rename_session" => {
             let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
             let session_id: String = field(&args, "sessionId", "session_id")?;
             let new_name: String = field(&args, "newName", "new_name")?;
-            crate::chat::rename_session(
-                app.clone(),
-                worktree_id,
-                worktree_path,
-                session_id,
-                new_name,
-            )
-            .await?;
+            crate::chat::rename_session(app.clone(), worktree_id, session_id, new_name).await?;
             emit_cache_invalidation(app, &["sessions"]);
             Ok(Value::Null)
         }
@@ -552,6 +544,9 @@ pub async fn dispatch_command(
             let chrome_enabled: Option<bool> = field_opt(&args, "chromeEnabled", "chrome_enabled")?;
             let custom_profile_name: Option<String> =
                 field_opt(&args, "customProfileName", "custom_profile_name")?;
+            let labels: Option<Vec<String>> = from_field_opt(&args, "labels")?;
+            let resume_from_run_id: Option<String> =
+                field_opt(&args, "resumeFromRunId", "resume_from_run_id")?;
             let result = crate::chat::send_chat_message(
                 app.clone(),
                 session_id,
@@ -569,16 +564,93 @@ pub async fn dispatch_command(
                 mcp_config,
                 chrome_enabled,
                 custom_profile_name,
+                labels,
+                resume_from_run_id,
             )
             .await?;
             to_value(result)
         }
+        "find_duplicate_runs" => {
+            let result = crate::chat::find_duplicate_runs(app.clone()).await?;
+            to_value(result)
+        }
+        "prune_run_logs" => {
+            let max_age_days: Option<u64> = field_opt(&args, "maxAgeDays", "max_age_days")?;
+            let max_total_bytes: Option<u64> =
+                field_opt(&args, "maxTotalBytes", "max_total_bytes")?;
+            let result =
+                crate::chat::prune_run_logs(app.clone(), max_age_days, max_total_bytes).await?;
+            to_value(result)
+        }
+        "session_disk_usage" => {
+            let result = crate::chat::session_disk_usage(app.clone()).await?;
+            to_value(result)
+        }
+        "search_sessions" => {
+            let query: String = from_field(&args, "query")?;
+            let max_hits_per_session: Option<usize> =
+                field_opt(&args, "maxHitsPerSession", "max_hits_per_session")?;
+            let result =
+                crate::chat::search_sessions(app.clone(), query, max_hits_per_session).await?;
+            to_value(result)
+        }
+        "rebuild_search_index" => {
+            let result = crate::chat::rebuild_search_index(app.clone()).await?;
+            to_value(result)
+        }
+        "list_runs_by_label" => {
+            let label: String = from_field(&args, "label")?;
+            let result = crate::chat::list_runs_by_label(app.clone(), label).await?;
+            to_value(result)
+        }
+        "export_session_json" => {
+            let session_id: String = field(&args, "sessionId", "session_id")?;
+            let result = crate::chat::export_session_json(app.clone(), session_id).await?;
+            to_value(result)
+        }
+        "import_session_json" => {
+            let json: String = from_field(&args, "json")?;
+            let result = crate::chat::import_session_json(app.clone(), json).await?;
+            to_value(result)
+        }
+        "replay_run" => {
+            let session_id: String = field(&args, "sessionId", "session_id")?;
+            let run_id: String = field(&args, "runId", "run_id")?;
+            let speed: f32 = from_field(&args, "speed")?;
+            let result = crate::chat::replay_run(app.clone(), session_id, run_id, speed).await?;
+            to_value(result)
+        }
+        "repair_session" => {
+            let session_id: String = field(&args, "sessionId", "session_id")?;
+            let result = crate::chat::repair_session(app.clone(), session_id).await?;
+            to_value(result)
+        }
+        "compact_session" => {
+            let session_id: String = field(&args, "sessionId", "session_id")?;
+            let keep_last: usize = field(&args, "keepLast", "keep_last")?;
+            let result = crate::chat::compact_session(app.clone(), session_id, keep_last).await?;
+            to_value(result)
+        }
+        "list_sessions_summary" => {
+            let result = crate::chat::list_sessions_summary(app.clone()).await?;
+            to_value(result)
+        }
         "cancel_chat_message" => {
             let session_id: String = field(&args, "sessionId", "session_id")?;
             let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
             crate::chat::cancel_chat_message(app.clone(), session_id, worktree_id).await?;
             Ok(Value::Null)
         }
+        "cancel_run" => {
+            let session_id: String = field(&args, "sessionId", "session_id")?;
+            let run_id: String = field(&args, "runId", "run_id")?;
+            let result = crate::chat::cancel_run(app.clone(), session_id, run_id).await?;
+            to_value(result)
+        }
+        "cancel_all_sessions" => {
+            let result = crate::chat::cancel_all_sessions(app.clone()).await?;
+            to_value(result)
+        }
         "clear_session_history" => {
             let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
             let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
@@ -799,6 +871,23 @@ pub async fn dispatch_command(
             let result = crate::chat::check_resumable_sessions(app.clone()).await?;
             to_value(result)
         }
+        "get_run_raw_events" => {
+            let session_id: String = field(&args, "sessionId", "session_id")?;
+            let run_id: String = field(&args, "runId", "run_id")?;
+            let limit: Option<usize> = field_opt(&args, "limit", "limit")?;
+            let result =
+                crate::chat::get_run_raw_events(app.clone(), session_id, run_id, limit).await?;
+            to_value(result)
+        }
+        "get_session_agent_ids" => {
+            let session_id: String = field(&args, "sessionId", "session_id")?;
+            let result = crate::chat::get_session_agent_ids(app.clone(), session_id).await?;
+            to_value(result)
+        }
+        "preview_run_recovery" => {
+            let result = crate::chat::preview_run_recovery(app.clone()).await?;
+            to_value(result)
+        }
         "cleanup_old_archives" => {
             let retention_days: u32 = field(&args, "retentionDays", "retention_days")?;
             let result = crate::projects::cleanup_old_archives(app.clone(), retention_days).await?;
@@ -1072,6 +1161,10 @@ pub async fn dispatch_command(
             let result = crate::projects::remove_project_avatar(app.clone(), project_id).await?;
             to_value(result)
         }
+        "get_data_locations" => {
+            let result = crate::projects::get_data_locations(app.clone()).await?;
+            to_value(result)
+        }
         "get_app_data_dir" => {
             let result = crate::projects::get_app_data_dir(app.clone()).await?;
             to_value(result)
@@ -1312,6 +1405,12 @@ pub async fn dispatch_command(
             let result = crate::chat::resume_session(app.clone(), session_id, worktree_id).await?;
             to_value(result)
         }
+        "retry_run" => {
+            let session_id: String = field(&args, "sessionId", "session_id")?;
+            let run_id: String = field(&args, "runId", "run_id")?;
+            crate::chat::retry_run(app.clone(), session_id, run_id).await?;
+            Ok(Value::Null)
+        }
         "broadcast_session_setting" => {
             let session_id: String = field(&args, "sessionId", "session_id")?;
             let key: String = field(&args, "key", "key")?;
@@ -1327,10 +1426,33 @@ pub async fn dispatch_command(
             let result = crate::claude_cli::check_claude_cli_installed(app.clone()).await?;
             to_value(result)
         }
+        "verify_claude_cli_platform" => {
+            let result = crate::claude_cli::verify_claude_cli_platform(app.clone()).await?;
+            to_value(result)
+        }
+        "set_claude_cli_source" => {
+            let source: String = field(&args, "source", "source")?;
+            let system_path: Option<String> = field_opt(&args, "systemPath", "system_path")?;
+            let result =
+                crate::claude_cli::set_claude_cli_source(app.clone(), source, system_path).await?;
+            to_value(result)
+        }
         "check_claude_cli_auth" => {
             let result = crate::claude_cli::check_claude_cli_auth(app.clone()).await?;
             to_value(result)
         }
+        "diagnose_claude_cli" => {
+            let result = crate::claude_cli::diagnose_claude_cli(app.clone()).await?;
+            to_value(result)
+        }
+        "claude_cli_status" => {
+            let result = crate::claude_cli::claude_cli_status(app.clone()).await?;
+            to_value(result)
+        }
+        "claude_update_available" => {
+            let result = crate::claude_cli::claude_update_available(app.clone()).await?;
+            to_value(result)
+        }
         "get_available_cli_versions" => {
             let result = crate::claude_cli::get_available_cli_versions().await?;
             to_value(result)
@@ -1349,7 +1471,13 @@ pub async fn dispatch_command(
             to_value(result)
         }
         "get_available_gh_versions" => {
-            let result = crate::gh_cli::get_available_gh_versions().await?;
+            let include_all: Option<bool> = field_opt(&args, "includeAll", "include_all")?;
+            let result = crate::gh_cli::get_available_gh_versions(include_all).await?;
+            to_value(result)
+        }
+        "get_gh_release_notes" => {
+            let tag_name: String = field(&args, "tagName", "tag_name")?;
+            let result = crate::gh_cli::get_gh_release_notes(tag_name).await?;
             to_value(result)
         }
         "install_gh_cli" => {
@@ -15,7 +15,7 @@ use tower_http::cors::{Any, CorsLayer};
 use tower_http::services::{ServeDir, ServeFile};
 
 use super::auth;
-use super::websocket::handle_ws_connection;
+use super::websocket::{handle_run_events_connection, handle_ws_connection};
 use super::WsBroadcaster;
 
 /// Shared state for the Axum server.
@@ -51,6 +51,14 @@ struct WsAuth {
     token: Option<String>,
 }
 
+/// Query for `/ws/run-events`: which run to stream
+#[derive(Deserialize)]
+struct RunEventsQuery {
+    token: Option<String>,
+    session_id: String,
+    run_id: String,
+}
+
 /// Resolve the dist directory path at runtime.
 /// Checks multiple locations for development and production scenarios.
 fn resolve_dist_path(app: &AppHandle) -> std::path::PathBuf {
@@ -132,6 +140,7 @@ pub async fn start_server(
 
     let router = Router::new()
         .route("/ws", get(ws_handler))
+        .route("/ws/run-events", get(run_events_handler))
         .route("/api/auth", get(auth_handler))
         .route("/api/init", get(init_handler))
         .fallback_service(serve_dir)
@@ -211,6 +220,32 @@ async fn ws_handler(
     ws.on_upgrade(move |socket| handle_ws_connection(socket, app, event_rx))
 }
 
+/// WebSocket upgrade handler streaming a single run's events (token auth).
+/// For external integrations that watch a run without the app event bus.
+async fn run_events_handler(
+    ws: WebSocketUpgrade,
+    Query(params): Query<RunEventsQuery>,
+    State(state): State<AppState>,
+) -> Response {
+    if state.token_required {
+        let provided = params.token.unwrap_or_default();
+        if !auth::validate_token(&provided, &state.token) {
+            return (StatusCode::UNAUTHORIZED, "Invalid token").into_response();
+        }
+    }
+
+    let sub = match crate::chat::run_stream::subscribe_run_events(
+        &state.app,
+        &params.session_id,
+        &params.run_id,
+    ) {
+        Ok(sub) => sub,
+        Err(e) => return (StatusCode::NOT_FOUND, e).into_response(),
+    };
+
+    ws.on_upgrade(move |socket| handle_run_events_connection(socket, sub))
+}
+
 /// Token validation endpoint. Returns 200 with { ok: true } on success,
 /// or 401 with { ok: false, error: "..." } on failure.
 async fn auth_handler(Query(params): Query<WsAuth>, State(state): State<AppState>) -> Response {
@@ -7,6 +7,7 @@ use tokio::sync::broadcast;
 
 use super::dispatch::dispatch_command;
 use super::WsEvent;
+use crate::chat::run_stream::RunSubscription;
 
 #[derive(Deserialize)]
 struct InvokeRequest {
@@ -148,3 +149,31 @@ pub async fn handle_ws_connection(
     event_forwarder.abort();
     log::trace!("WebSocket client disconnected");
 }
+
+/// Stream one run's raw stream-json events to a client, one text message per
+/// event, closing when the run finishes or the client disconnects.
+pub async fn handle_run_events_connection(mut socket: WebSocket, mut sub: RunSubscription) {
+    loop {
+        tokio::select! {
+            event = sub.events.recv() => match event {
+                Some(event) => {
+                    if socket.send(Message::Text(event.to_string().into())).await.is_err() {
+                        break;
+                    }
+                }
+                // Run finished or its log became unreadable
+                None => {
+                    let _ = socket.send(Message::Close(None)).await;
+                    break;
+                }
+            },
+            msg = socket.recv() => match msg {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                _ => {}
+            },
+        }
+    }
+
+    // Joining the tailer thread blocks, so do it off the async runtime
+    let _ = tauri::async_runtime::spawn_blocking(move || sub.unsubscribe()).await;
+}
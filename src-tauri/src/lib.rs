@@ -173,6 +173,20 @@ pub struct AppPreferences {
     pub default_provider: Option<String>, // Default provider profile name (None = Anthropic direct)
     #[serde(default = "default_canvas_layout")]
     pub canvas_layout: String, // Canvas display mode: grid or list
+    #[serde(default)]
+    pub claude_startup_timeout_secs: Option<u64>, // Seconds to wait for first Claude output (None = 120, clamped to 10-900)
+    #[serde(default)]
+    pub claude_dead_process_timeout_secs: Option<u64>, // Seconds to wait for more output after the Claude process exits (None = 2, clamped to 1-60)
+    #[serde(default)]
+    pub claude_max_input_kb: Option<u64>, // Largest message sent to a Claude run in KB (None = 4096)
+    #[serde(default)]
+    pub claude_cli_source: Option<String>, // Claude CLI binary to run: "embedded" or "system" (None = embedded)
+    #[serde(default)]
+    pub claude_cli_path: Option<String>, // System Claude CLI path (None = look up on PATH)
+    #[serde(default)]
+    pub claude_extra_env: std::collections::HashMap<String, String>, // Extra env vars for spawned Claude processes (override built-ins)
+    #[serde(default)]
+    pub claude_min_thinking_display_level: Option<String>, // Hide thinking for runs below this level: low/think, medium/megathink, high/ultrathink, max (None = always show)
 }
 
 fn default_true() -> Option<bool> {
@@ -767,6 +781,13 @@ impl Default for AppPreferences {
             custom_cli_profiles: Vec::new(),
             default_provider: None,
             canvas_layout: default_canvas_layout(),
+            claude_startup_timeout_secs: None,
+            claude_dead_process_timeout_secs: None,
+            claude_max_input_kb: None,
+            claude_cli_source: None,
+            claude_cli_path: None,
+            claude_extra_env: std::collections::HashMap::new(),
+            claude_min_thinking_display_level: None,
         }
     }
 }
@@ -877,10 +898,7 @@ impl Default for UIState {
 }
 
 fn get_preferences_path(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(app)?;
 
     // Ensure the directory exists
     std::fs::create_dir_all(&app_data_dir)
@@ -1055,10 +1073,7 @@ async fn delete_cli_profile(name: String) -> Result<(), String> {
 }
 
 fn get_ui_state_path(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(app)?;
 
     // Ensure the directory exists
     std::fs::create_dir_all(&app_data_dir)
@@ -1160,10 +1175,7 @@ async fn send_native_notification(
 
 // Recovery functions - simple pattern for saving JSON data to disk
 fn get_recovery_dir(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(app)?;
 
     let recovery_dir = app_data_dir.join("recovery");
 
@@ -1712,6 +1724,15 @@ pub fn run() {
                         if let Err(e) = app_handle.emit("runs:recovered", &recovered) {
                             log::warn!("Failed to emit runs:recovered event: {e}");
                         }
+
+                        // Re-attach tailers to detached processes that are still running
+                        for run in recovered.iter().filter(|r| r.resumable) {
+                            if let Err(e) =
+                                chat::resume_detached_run(&app_handle, &run.session_id, &run.run_id)
+                            {
+                                log::warn!("Failed to resume run {}: {e}", run.run_id);
+                            }
+                        }
                     }
                 }
                 Err(e) => {
@@ -1980,6 +2001,7 @@ pub fn run() {
             projects::set_project_avatar,
             projects::remove_project_avatar,
             projects::get_app_data_dir,
+            projects::get_data_locations,
             // Terminal commands
             terminal::start_terminal,
             terminal::terminal_write,
@@ -2015,6 +2037,8 @@ pub fn run() {
             chat::set_session_thinking_level,
             chat::set_session_provider,
             chat::cancel_chat_message,
+            chat::cancel_run,
+            chat::cancel_all_sessions,
             chat::has_running_sessions,
             chat::save_cancelled_message,
             chat::mark_plan_approved,
@@ -2049,15 +2073,37 @@ pub fn run() {
             // Chat commands - Session resume (detached process recovery)
             chat::resume_session,
             chat::check_resumable_sessions,
+            chat::retry_run,
+            chat::preview_run_recovery,
+            chat::get_run_raw_events,
+            chat::get_session_agent_ids,
+            chat::list_runs_by_label,
+            chat::list_sessions_summary,
+            chat::compact_session,
+            chat::repair_session,
+            chat::export_session_json,
+            chat::import_session_json,
+            chat::replay_run,
+            chat::search_sessions,
+            chat::rebuild_search_index,
+            chat::prune_run_logs,
+            chat::session_disk_usage,
+            chat::find_duplicate_runs,
             // Claude CLI management commands
             claude_cli::check_claude_cli_installed,
             claude_cli::check_claude_cli_auth,
             claude_cli::get_available_cli_versions,
             claude_cli::install_claude_cli,
+            claude_cli::verify_claude_cli_platform,
+            claude_cli::set_claude_cli_source,
+            claude_cli::diagnose_claude_cli,
+            claude_cli::claude_update_available,
+            claude_cli::claude_cli_status,
             // GitHub CLI management commands
             gh_cli::check_gh_cli_installed,
             gh_cli::check_gh_cli_auth,
             gh_cli::get_available_gh_versions,
+            gh_cli::get_gh_release_notes,
             gh_cli::install_gh_cli,
             // Background task commands
             background_tasks::commands::set_app_focus_state,
@@ -0,0 +1,135 @@
+// App data directory resolution with graceful fallbacks
+//
+// Tauri's `app_data_dir()` can fail (or point at a read-only location) in
+// sandboxed environments or when HOME is missing. Instead of failing every
+// storage call with a cryptic error, the directory is resolved once using a
+// fallback chain, the chosen location is logged, and the error says how to
+// configure one.
+
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Environment variable pointing at a user-configured data directory,
+/// used when the default app data directory is unavailable
+pub const DATA_DIR_OVERRIDE_ENV: &str = "JEAN_DATA_DIR";
+
+/// Where the resolved app data directory came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataDirSource {
+    /// The platform default app data directory
+    AppData,
+    /// The directory configured via `JEAN_DATA_DIR`
+    Override,
+}
+
+static RESOLVED_DATA_DIR: OnceCell<(PathBuf, DataDirSource)> = OnceCell::new();
+
+/// Get the app data directory, falling back when the default is unavailable.
+///
+/// Resolution order: app data dir → `JEAN_DATA_DIR`. There is deliberately no
+/// temp dir fallback: the data dir holds CLI binaries Jean executes, and a
+/// shared, predictable temp path could be pre-seeded by another user.
+/// A successful result is cached for the lifetime of the process.
+pub fn resolve_app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    resolve_app_data_dir_with_source(app).map(|(dir, _)| dir)
+}
+
+/// Same as [`resolve_app_data_dir`], but also reports which fallback was used
+pub fn resolve_app_data_dir_with_source(
+    app: &AppHandle,
+) -> Result<(PathBuf, DataDirSource), String> {
+    RESOLVED_DATA_DIR
+        .get_or_try_init(|| {
+            let default_dir = app
+                .path()
+                .app_data_dir()
+                .map_err(|e| format!("Failed to get app data directory: {e}"));
+            let override_dir = std::env::var_os(DATA_DIR_OVERRIDE_ENV)
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from);
+
+            let (dir, source) = choose_data_dir(default_dir, override_dir)?;
+            match source {
+                DataDirSource::AppData => log::trace!("Using app data directory: {dir:?}"),
+                DataDirSource::Override => {
+                    log::warn!("Using {DATA_DIR_OVERRIDE_ENV} data directory: {dir:?}")
+                }
+            }
+            Ok((dir, source))
+        })
+        .cloned()
+}
+
+/// Pick the first usable data directory from the fallback chain
+fn choose_data_dir(
+    default_dir: Result<PathBuf, String>,
+    override_dir: Option<PathBuf>,
+) -> Result<(PathBuf, DataDirSource), String> {
+    match default_dir {
+        Ok(dir) if is_writable_dir(&dir) => return Ok((dir, DataDirSource::AppData)),
+        Ok(dir) => log::warn!("App data directory is not writable: {dir:?}"),
+        Err(e) => log::warn!("{e}"),
+    }
+
+    if let Some(dir) = override_dir {
+        if is_writable_dir(&dir) {
+            return Ok((dir, DataDirSource::Override));
+        }
+        log::warn!("{DATA_DIR_OVERRIDE_ENV} directory is not writable: {dir:?}");
+    }
+
+    Err(format!(
+        "No writable data directory found. Set {DATA_DIR_OVERRIDE_ENV} to a writable directory that only you can access."
+    ))
+}
+
+/// Check that a directory exists (creating it if needed) and accepts writes
+fn is_writable_dir(dir: &Path) -> bool {
+    if std::fs::create_dir_all(dir).is_err() {
+        return false;
+    }
+    let probe = dir.join(".jean-write-probe");
+    let writable = std::fs::write(&probe, b"").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    writable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choose_data_dir_prefers_app_data() {
+        let app_data = tempfile::tempdir().unwrap();
+        let override_dir = tempfile::tempdir().unwrap();
+
+        let (dir, source) = choose_data_dir(
+            Ok(app_data.path().to_path_buf()),
+            Some(override_dir.path().to_path_buf()),
+        )
+        .unwrap();
+
+        assert_eq!(dir, app_data.path());
+        assert_eq!(source, DataDirSource::AppData);
+    }
+
+    #[test]
+    fn test_choose_data_dir_falls_back_to_override_then_fails() {
+        let override_dir = tempfile::tempdir().unwrap();
+
+        let (dir, source) = choose_data_dir(
+            Err("no home".to_string()),
+            Some(override_dir.path().to_path_buf()),
+        )
+        .unwrap();
+        assert_eq!(dir, override_dir.path());
+        assert_eq!(source, DataDirSource::Override);
+
+        // No temp dir fallback
+        let err = choose_data_dir(Err("no home".to_string()), None).unwrap_err();
+        assert!(err.contains(DATA_DIR_OVERRIDE_ENV), "{err}");
+    }
+}
@@ -1,7 +1,11 @@
 // Cross-platform abstractions for shell execution and process management
 
+pub mod data_dir;
+pub mod disk;
 pub mod process;
 pub mod shell;
 
+pub use data_dir::*;
+pub use disk::*;
 pub use process::*;
 pub use shell::*;
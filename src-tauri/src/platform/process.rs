@@ -133,6 +133,45 @@ pub fn kill_process_tree(pid: u32) -> Result<(), String> {
     }
 }
 
+/// Interrupt a process and its children so they can shut down cleanly
+/// - Unix: Sends SIGINT to the process group (falls back to just the process)
+/// - Windows: Sends CTRL_BREAK_EVENT to the process group, which only works
+///   when it shares our console; callers should escalate to a kill on error
+#[cfg(unix)]
+pub fn interrupt_process_tree(pid: u32) -> Result<(), String> {
+    let result = unsafe { libc::kill(-(pid as i32), libc::SIGINT) };
+    if result == 0 {
+        return Ok(());
+    }
+
+    let result = unsafe { libc::kill(pid as i32, libc::SIGINT) };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to interrupt process {}: {}",
+            pid,
+            std::io::Error::last_os_error()
+        ))
+    }
+}
+
+#[cfg(windows)]
+pub fn interrupt_process_tree(pid: u32) -> Result<(), String> {
+    use windows_sys::Win32::System::Console::{GenerateConsoleCtrlEvent, CTRL_BREAK_EVENT};
+
+    let result = unsafe { GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid) };
+    if result != 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to send CTRL_BREAK to process {}: {}",
+            pid,
+            std::io::Error::last_os_error()
+        ))
+    }
+}
+
 /// Send SIGTERM to gracefully terminate a process (Unix only)
 /// On Windows, this falls back to TerminateProcess
 #[cfg(unix)]
@@ -5,7 +5,7 @@ use std::path::Path;
 use std::process::Stdio;
 use std::thread;
 use std::time::{SystemTime, UNIX_EPOCH};
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 use tauri_plugin_dialog::DialogExt;
 use uuid::Uuid;
 
@@ -28,6 +28,7 @@ use super::types::{
     WorktreeDeletingEvent, WorktreePathExistsEvent, WorktreePermanentlyDeletedEvent,
     WorktreeUnarchivedEvent,
 };
+use crate::chat::types::UsageData;
 use crate::claude_cli::get_cli_binary_path;
 use crate::gh_cli::config::resolve_gh_binary;
 use crate::http_server::EmitExt;
@@ -2325,7 +2326,7 @@ pub async fn permanently_delete_worktree(
         }
 
         // Delete the sessions file for this worktree
-        if let Ok(app_data_dir) = app_clone.path().app_data_dir() {
+        if let Ok(app_data_dir) = crate::platform::resolve_app_data_dir(&app_clone) {
             let sessions_file = app_data_dir
                 .join("sessions")
                 .join(format!("{worktree_id_clone}.json"));
@@ -3704,11 +3705,32 @@ pub struct CreatePrResponse {
     pub pr_number: u32,
     pub pr_url: String,
     pub title: String,
+    /// Token usage of the PR content generation, if the CLI reported it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<UsageData>,
+}
+
+/// Structured output plus the token usage of the one-shot run that produced it
+#[derive(Debug, Clone)]
+struct StructuredOutput {
+    /// JSON input of the StructuredOutput tool call
+    json: String,
+    /// Usage from the final result message, if the CLI reported one
+    usage: Option<UsageData>,
 }
 
 /// Extract structured output from Claude CLI stream-json response
 /// Handles the StructuredOutput tool call pattern used with --json-schema
 fn extract_structured_output(output: &str) -> Result<String, String> {
+    extract_structured_output_with_usage(output).map(|structured| structured.json)
+}
+
+/// Like `extract_structured_output`, but also reads token usage from the
+/// `result` message so background generations can be cost-accounted
+fn extract_structured_output_with_usage(output: &str) -> Result<StructuredOutput, String> {
+    let mut json: Option<String> = None;
+    let mut usage = None;
+
     for line in output.lines() {
         let line = line.trim();
         if line.is_empty() {
@@ -3720,25 +3742,33 @@ fn extract_structured_output(output: &str) -> Result<String, String> {
             Err(_) => continue,
         };
 
-        if parsed.get("type").and_then(|t| t.as_str()) == Some("assistant") {
-            if let Some(message) = parsed.get("message") {
-                if let Some(content) = message.get("content").and_then(|c| c.as_array()) {
-                    for block in content {
-                        if block.get("type").and_then(|t| t.as_str()) == Some("tool_use")
-                            && block.get("name").and_then(|n| n.as_str())
-                                == Some("StructuredOutput")
-                        {
-                            if let Some(input) = block.get("input") {
-                                return Ok(input.to_string());
-                            }
-                        }
-                    }
+        match parsed.get("type").and_then(|t| t.as_str()) {
+            Some("assistant") if json.is_none() => {
+                if let Some(content) = parsed
+                    .get("message")
+                    .and_then(|m| m.get("content"))
+                    .and_then(|c| c.as_array())
+                {
+                    json = content
+                        .iter()
+                        .find(|block| {
+                            block.get("type").and_then(|t| t.as_str()) == Some("tool_use")
+                                && block.get("name").and_then(|n| n.as_str())
+                                    == Some("StructuredOutput")
+                        })
+                        .and_then(|block| block.get("input"))
+                        .map(|input| input.to_string());
                 }
             }
+            Some("result") => {
+                usage = parsed.get("usage").map(crate::chat::parse_usage);
+            }
+            _ => {}
         }
     }
 
-    Err("No structured output found in Claude response".to_string())
+    let json = json.ok_or_else(|| "No structured output found in Claude response".to_string())?;
+    Ok(StructuredOutput { json, usage })
 }
 
 /// Get git diff between current branch and target branch
@@ -3815,7 +3845,7 @@ fn generate_pr_content(
     custom_prompt: Option<&str>,
     model: Option<&str>,
     context: &str,
-) -> Result<PrContentResponse, String> {
+) -> Result<(PrContentResponse, Option<UsageData>), String> {
     let cli_path = get_cli_binary_path(app)?;
 
     if !cli_path.exists() {
@@ -3903,13 +3933,17 @@ fn generate_pr_content(
     let stdout = String::from_utf8_lossy(&output.stdout);
     log::trace!("Claude CLI PR generation stdout: {stdout}");
 
-    let json_content = extract_structured_output(&stdout)?;
+    let StructuredOutput {
+        json: json_content,
+        usage,
+    } = extract_structured_output_with_usage(&stdout)?;
     log::trace!("Extracted PR content JSON: {json_content}");
 
-    serde_json::from_str(&json_content).map_err(|e| {
+    let content = serde_json::from_str(&json_content).map_err(|e| {
         log::error!("Failed to parse PR content JSON: {e}, content: {json_content}");
         format!("Failed to parse PR content: {e}")
-    })
+    })?;
+    Ok((content, usage))
 }
 
 /// Parse PR number and URL from gh pr create output
@@ -4023,7 +4057,7 @@ pub async fn create_pr_with_ai_content(
 
     // Generate PR content using Claude CLI
     log::trace!("Generating PR content with AI");
-    let mut pr_content = generate_pr_content(
+    let (mut pr_content, usage) = generate_pr_content(
         &app,
         &worktree_path,
         &current_branch,
@@ -4082,6 +4116,7 @@ pub async fn create_pr_with_ai_content(
         pr_number,
         pr_url,
         title: pr_content.title,
+        usage,
     })
 }
 
@@ -4121,6 +4156,9 @@ pub struct CreateCommitResponse {
     pub commit_hash: String,
     pub message: String,
     pub pushed: bool,
+    /// Token usage of the commit message generation, if the CLI reported it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<UsageData>,
 }
 
 /// Get git status output
@@ -4240,7 +4278,7 @@ fn generate_commit_message(
     app: &AppHandle,
     prompt: &str,
     model: Option<&str>,
-) -> Result<CommitMessageResponse, String> {
+) -> Result<(CommitMessageResponse, Option<UsageData>), String> {
     let cli_path = get_cli_binary_path(app)?;
 
     if !cli_path.exists() {
@@ -4307,11 +4345,15 @@ fn generate_commit_message(
     let stdout = String::from_utf8_lossy(&output.stdout);
     log::trace!("Claude CLI commit generation stdout: {stdout}");
 
-    let json_content = extract_structured_output(&stdout)?;
+    let StructuredOutput {
+        json: json_content,
+        usage,
+    } = extract_structured_output_with_usage(&stdout)?;
     log::trace!("Extracted commit message JSON: {json_content}");
 
-    serde_json::from_str::<CommitMessageResponse>(&json_content)
-        .map_err(|e| format!("Failed to parse commit message response: {e}"))
+    let response = serde_json::from_str::<CommitMessageResponse>(&json_content)
+        .map_err(|e| format!("Failed to parse commit message response: {e}"))?;
+    Ok((response, usage))
 }
 
 /// Create a commit with AI-generated message
@@ -4358,7 +4400,7 @@ pub async fn create_commit_with_ai(
         .replace("{remote_info}", &remote_info);
 
     // 6. Generate commit message with Claude CLI
-    let response = generate_commit_message(&app, &prompt, model.as_deref())?;
+    let (response, usage) = generate_commit_message(&app, &prompt, model.as_deref())?;
 
     log::trace!(
         "Generated commit message: {}",
@@ -4383,6 +4425,7 @@ pub async fn create_commit_with_ai(
         commit_hash,
         message: response.message,
         pushed,
+        usage,
     })
 }
 
@@ -4964,7 +5007,7 @@ pub async fn merge_worktree_to_base(
             .replace("{remote_info}", &remote_info);
 
         match generate_commit_message(&app, &prompt, None) {
-            Ok(response) => {
+            Ok((response, _usage)) => {
                 // Create the commit with AI-generated message
                 match create_git_commit(&worktree.path, &response.message) {
                     Ok(hash) => log::trace!("Auto-committed with AI message: {hash}"),
@@ -5307,7 +5350,7 @@ pub async fn cleanup_old_archives(
         }
 
         // Delete the sessions file
-        if let Ok(app_data_dir) = app.path().app_data_dir() {
+        if let Ok(app_data_dir) = crate::platform::resolve_app_data_dir(&app) {
             let sessions_file = app_data_dir
                 .join("sessions")
                 .join(format!("{}.json", worktree.id));
@@ -5435,7 +5478,7 @@ pub async fn delete_all_archives(app: AppHandle) -> Result<CleanupResult, String
         }
 
         // Delete the sessions file
-        if let Ok(app_data_dir) = app.path().app_data_dir() {
+        if let Ok(app_data_dir) = crate::platform::resolve_app_data_dir(&app) {
             let sessions_file = app_data_dir
                 .join("sessions")
                 .join(format!("{}.json", worktree.id));
@@ -6061,10 +6104,7 @@ pub async fn list_claude_commands() -> Result<Vec<ClaudeCommand>, String> {
 
 /// Get the avatars directory, creating it if needed
 fn get_avatars_dir(app: &AppHandle) -> Result<std::path::PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(app)?;
 
     let avatars_dir = app_data_dir.join("avatars");
     std::fs::create_dir_all(&avatars_dir)
@@ -6150,10 +6190,7 @@ pub async fn remove_project_avatar(app: AppHandle, project_id: String) -> Result
 
     // Delete avatar file if it exists
     if let Some(ref avatar_path) = project.avatar_path {
-        let app_data_dir = app
-            .path()
-            .app_data_dir()
-            .map_err(|e| format!("Failed to get app data dir: {e}"))?;
+        let app_data_dir = crate::platform::resolve_app_data_dir(&app)?;
 
         let full_path = app_data_dir.join(avatar_path);
         if full_path.exists() {
@@ -6178,14 +6215,47 @@ pub async fn remove_project_avatar(app: AppHandle, project_id: String) -> Result
 /// Used by frontend to resolve relative avatar paths to absolute file:// URLs
 #[tauri::command]
 pub async fn get_app_data_dir(app: AppHandle) -> Result<String, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(&app)?;
 
     Ok(app_data_dir.to_string_lossy().to_string())
 }
 
+/// Where Jean keeps its data on disk
+#[derive(Debug, Clone, Serialize)]
+pub struct DataLocations {
+    /// Resolved app data directory
+    pub app_data_dir: String,
+    /// Which fallback produced the app data directory
+    pub source: crate::platform::DataDirSource,
+    /// Session metadata and run logs
+    pub sessions_dir: String,
+    /// Embedded Claude CLI binary
+    pub claude_cli_dir: String,
+    /// Embedded GitHub CLI binary
+    pub gh_cli_dir: String,
+}
+
+/// Get all data locations used by Jean
+/// Used by support/diagnostics to find where everything lives
+#[tauri::command]
+pub async fn get_data_locations(app: AppHandle) -> Result<DataLocations, String> {
+    let (app_data_dir, source) = crate::platform::resolve_app_data_dir_with_source(&app)?;
+
+    Ok(DataLocations {
+        app_data_dir: app_data_dir.to_string_lossy().to_string(),
+        source,
+        sessions_dir: crate::chat::storage::get_sessions_dir(&app)?
+            .to_string_lossy()
+            .to_string(),
+        claude_cli_dir: crate::claude_cli::get_cli_dir(&app)?
+            .to_string_lossy()
+            .to_string(),
+        gh_cli_dir: crate::gh_cli::config::get_gh_cli_dir(&app)?
+            .to_string_lossy()
+            .to_string(),
+    })
+}
+
 /// Get full jean.json config for a project
 #[tauri::command]
 pub async fn get_jean_config(project_path: String) -> Option<JeanConfig> {
@@ -6247,6 +6317,27 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_extract_structured_output_with_usage() {
+        let output = r#"{"type":"system","subtype":"init"}
+{"type":"assistant","message":{"content":[{"type":"tool_use","name":"StructuredOutput","input":{"subject":"Fix bug"}}]}}
+{"type":"result","subtype":"success","usage":{"input_tokens":812,"output_tokens":64,"cache_read_input_tokens":100}}"#;
+
+        let result = extract_structured_output_with_usage(output).unwrap();
+        assert!(result.json.contains("Fix bug"));
+        let usage = result.usage.unwrap();
+        assert_eq!(usage.input_tokens, 812);
+        assert_eq!(usage.output_tokens, 64);
+        assert_eq!(usage.cache_read_input_tokens, 100);
+
+        // No result message: output still extracted, usage unknown
+        let output = r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"StructuredOutput","input":{"title":"Test"}}]}}"#;
+        assert!(extract_structured_output_with_usage(output)
+            .unwrap()
+            .usage
+            .is_none());
+    }
+
     #[test]
     fn test_extract_structured_output_empty() {
         let result = extract_structured_output("");
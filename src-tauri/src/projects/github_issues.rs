@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
 use super::git::get_repo_identifier;
 use crate::gh_cli::config::resolve_gh_binary;
@@ -376,10 +376,7 @@ pub struct ContextReferences {
 
 /// Get the directory for shared GitHub contexts
 pub fn get_github_contexts_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(app)?;
     Ok(app_data_dir.join("git-context"))
 }
 
@@ -1,5 +1,4 @@
 use serde::{Deserialize, Serialize};
-use tauri::Manager;
 
 /// Attached saved context info returned to frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,10 +22,7 @@ pub async fn attach_saved_context(
 ) -> Result<AttachedSavedContext, String> {
     log::trace!("Attaching saved context '{slug}' for session {session_id}");
 
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(&app)?;
 
     let saved_contexts_dir = app_data_dir.join("session-context");
     std::fs::create_dir_all(&saved_contexts_dir)
@@ -87,10 +83,7 @@ pub async fn remove_saved_context(
 ) -> Result<(), String> {
     log::trace!("Removing saved context '{slug}' from session {session_id}");
 
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(&app)?;
 
     let context_file = app_data_dir
         .join("session-context")
@@ -113,10 +106,7 @@ pub async fn list_attached_saved_contexts(
 ) -> Result<Vec<AttachedSavedContext>, String> {
     log::trace!("Listing attached saved contexts for session {session_id}");
 
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(&app)?;
 
     let saved_contexts_dir = app_data_dir.join("session-context");
 
@@ -183,10 +173,7 @@ pub async fn get_saved_context_content(
     session_id: String,
     slug: String,
 ) -> Result<String, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(&app)?;
 
     let context_file = app_data_dir
         .join("session-context")
@@ -207,10 +194,7 @@ pub fn cleanup_saved_contexts_for_session(
     app: &tauri::AppHandle,
     session_id: &str,
 ) -> Result<(), String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let app_data_dir = crate::platform::resolve_app_data_dir(app)?;
 
     let saved_contexts_dir = app_data_dir.join("session-context");
     if !saved_contexts_dir.exists() {
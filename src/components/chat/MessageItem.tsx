@@ -531,6 +531,15 @@ export const MessageItem = memo(function MessageItem({
             {messageBoxContent}
           </div>
         </div>
+      ) : message.compacted_runs ? (
+        // Summary of compacted history: collapsed by default
+        <details className="text-muted-foreground w-full min-w-0 break-words border border-border rounded-lg px-3 py-2">
+          <summary className="cursor-pointer select-none text-xs">
+            Earlier conversation ({message.compacted_runs}{' '}
+            {message.compacted_runs === 1 ? 'turn' : 'turns'})
+          </summary>
+          <div className="mt-2">{messageBoxContent}</div>
+        </details>
       ) : (
         <div
           className={cn(
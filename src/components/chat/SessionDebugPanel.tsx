@@ -5,7 +5,12 @@ import { isNativeApp } from '@/lib/environment'
 import { toast } from 'sonner'
 import { Button } from '@/components/ui/button'
 import { Copy, FileText } from 'lucide-react'
-import type { SessionDebugInfo, RunStatus, UsageData } from '@/types/chat'
+import type {
+  SessionDebugInfo,
+  RunLogFileInfo,
+  RunStatus,
+  UsageData,
+} from '@/types/chat'
 import { cn } from '@/lib/utils'
 import {
   Tooltip,
@@ -31,6 +36,13 @@ function formatTokens(tokens: number): string {
   return tokens.toString()
 }
 
+/** Format a run's permission mode and web access for display */
+function formatPolicy(file: RunLogFileInfo): string {
+  if (!file.permission_mode) return ''
+  const web = file.web_access ? 'web on' : 'web off'
+  return `${file.permission_mode} / ${web}`
+}
+
 /** Format usage data for display */
 function formatUsage(usage: UsageData | undefined): string {
   if (!usage) return ''
@@ -261,6 +273,11 @@ export function SessionDebugPanel({
                     ({formatUsage(file.usage)})
                   </span>
                 )}
+                {file.permission_mode && (
+                  <span className="text-muted-foreground font-mono text-xs shrink-0">
+                    [{formatPolicy(file)}]
+                  </span>
+                )}
                 <span className="text-foreground truncate">
                   {file.user_message_preview}
                 </span>
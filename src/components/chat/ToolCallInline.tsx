@@ -68,7 +68,8 @@ export function ToolCallInline({
       <div
         className={cn(
           'rounded-md border border-border/50 bg-muted/30 min-w-0',
-          isOpen && 'bg-muted/50'
+          isOpen && 'bg-muted/50',
+          toolCall.is_error && 'border-destructive/40'
         )}
       >
         <CollapsibleTrigger className="flex w-full items-center gap-2 px-3 py-2 text-sm text-muted-foreground hover:bg-muted/50 cursor-pointer select-none min-w-0">
@@ -93,6 +94,9 @@ export function ToolCallInline({
               {detail}
             </code>
           ) : null}
+          {toolCall.is_error && (
+            <span className="shrink-0 text-xs text-destructive">Failed</span>
+          )}
           {isStreaming && isLastIncomplete ? (
             <Loader2 className="ml-auto h-3 w-3 shrink-0 animate-spin text-muted-foreground/50" />
           ) : (
@@ -112,10 +116,20 @@ export function ToolCallInline({
             {toolCall.output && (
               <>
                 <div className="border-t border-border/30 my-2" />
-                <div className="text-xs text-muted-foreground/60 mb-1">
-                  Output:
+                <div
+                  className={cn(
+                    'text-xs text-muted-foreground/60 mb-1',
+                    toolCall.is_error && 'text-destructive/80'
+                  )}
+                >
+                  {toolCall.is_error ? 'Error:' : 'Output:'}
                 </div>
-                <pre className="max-h-64 overflow-auto whitespace-pre-wrap text-xs text-foreground/80 bg-muted/50 rounded p-2">
+                <pre
+                  className={cn(
+                    'max-h-64 overflow-auto whitespace-pre-wrap text-xs text-foreground/80 bg-muted/50 rounded p-2',
+                    toolCall.is_error && 'bg-destructive/10 text-destructive'
+                  )}
+                >
                   {toolCall.output}
                 </pre>
               </>
@@ -415,7 +429,8 @@ function SubToolItem({ toolCall, onFileClick }: SubToolItemProps) {
       <div
         className={cn(
           'rounded border border-border/30 bg-background/50',
-          isOpen && 'bg-muted/30'
+          isOpen && 'bg-muted/30',
+          toolCall.is_error && 'border-destructive/40'
         )}
       >
         <CollapsibleTrigger className="flex w-full items-center gap-1.5 px-2 py-1 text-xs text-muted-foreground/80 hover:bg-muted/30 cursor-pointer select-none">
@@ -440,6 +455,11 @@ function SubToolItem({ toolCall, onFileClick }: SubToolItemProps) {
               {detail}
             </code>
           ) : null}
+          {toolCall.is_error && (
+            <span className="shrink-0 text-[0.625rem] text-destructive">
+              Failed
+            </span>
+          )}
           <ChevronRight
             className={cn(
               'ml-auto h-2.5 w-2.5 shrink-0 transition-transform duration-200',
@@ -455,10 +475,20 @@ function SubToolItem({ toolCall, onFileClick }: SubToolItemProps) {
             {toolCall.output && (
               <>
                 <div className="border-t border-border/20 my-1.5" />
-                <div className="text-[0.625rem] text-muted-foreground/50 mb-0.5">
-                  Output:
+                <div
+                  className={cn(
+                    'text-[0.625rem] text-muted-foreground/50 mb-0.5',
+                    toolCall.is_error && 'text-destructive/80'
+                  )}
+                >
+                  {toolCall.is_error ? 'Error:' : 'Output:'}
                 </div>
-                <pre className="max-h-40 overflow-auto whitespace-pre-wrap text-[0.625rem] text-foreground/70 bg-muted/30 rounded p-1.5">
+                <pre
+                  className={cn(
+                    'max-h-40 overflow-auto whitespace-pre-wrap text-[0.625rem] text-foreground/70 bg-muted/30 rounded p-1.5',
+                    toolCall.is_error && 'bg-destructive/10 text-destructive'
+                  )}
+                >
                   {toolCall.output}
                 </pre>
               </>
@@ -26,6 +26,8 @@ import type {
   PermissionDeniedEvent,
   CompactingEvent,
   CompactedEvent,
+  PolicyAdjustedEvent,
+  StreamWarningEvent,
   Session,
   SessionDigest,
   WorktreeSessions,
@@ -41,7 +43,7 @@ interface UseStreamingEventsParams {
  *
  * Handles: chat:chunk, chat:tool_use, chat:tool_block, chat:thinking,
  * chat:tool_result, chat:permission_denied, chat:done, chat:error,
- * chat:cancelled, chat:compacted
+ * chat:cancelled, chat:compacted, chat:stream_warning, chat:policy_adjusted
  */
 export default function useStreamingEvents({
   queryClient,
@@ -125,7 +127,8 @@ export default function useStreamingEvents({
     const unlistenToolResult = listen<ToolResultEvent>(
       'chat:tool_result',
       event => {
-        const { session_id, tool_use_id, output } = event.payload
+        const { session_id, tool_use_id, output, is_error, duration_ms } =
+          event.payload
 
         // Check if this tool was in pending denials - if so, it ran anyway
         // (e.g., yolo mode, or tool was pre-approved via allowedTools)
@@ -161,7 +164,13 @@ export default function useStreamingEvents({
           tool_use_id,
           outputLength: output.length,
         })
-        updateToolCallOutput(session_id, tool_use_id, output)
+        updateToolCallOutput(
+          session_id,
+          tool_use_id,
+          output,
+          is_error,
+          duration_ms ?? undefined
+        )
       }
     )
 
@@ -868,6 +877,29 @@ export default function useStreamingEvents({
       }
     )
 
+    // Warnings about the CLI output (rate-limited in Rust)
+    const unlistenStreamWarning = listen<StreamWarningEvent>(
+      'chat:stream_warning',
+      event => {
+        const { message, line_preview } = event.payload
+        toast.warning(message, {
+          description: line_preview || undefined,
+        })
+      }
+    )
+
+    // A run executes with a different setting than the one requested
+    const unlistenPolicyAdjusted = listen<PolicyAdjustedEvent>(
+      'chat:policy_adjusted',
+      event => {
+        const { field, requested, effective, reason } = event.payload
+        toast.info(
+          `Using ${field.replace(/_/g, ' ')} "${effective}" instead of "${requested}"`,
+          { description: reason }
+        )
+      }
+    )
+
     // Handle session setting changes (model, thinking level, execution mode)
     // Broadcast by other clients via broadcast_session_setting command
     const unlistenSettingChanged = listen<{
@@ -906,6 +938,8 @@ export default function useStreamingEvents({
       unlistenCancelled.then(f => f())
       unlistenCompacting.then(f => f())
       unlistenCompacted.then(f => f())
+      unlistenStreamWarning.then(f => f())
+      unlistenPolicyAdjusted.then(f => f())
       unlistenSettingChanged.then(f => f())
     }
   }, [queryClient, wsConnected])
@@ -1176,6 +1176,7 @@ export function useSendMessage() {
       mcpConfig,
       chromeEnabled,
       customProfileName,
+      resumeFromRunId,
     }: {
       sessionId: string
       worktreeId: string
@@ -1192,6 +1193,8 @@ export function useSendMessage() {
       mcpConfig?: string
       chromeEnabled?: boolean
       customProfileName?: string
+      /** Branch from this earlier run's Claude session instead of the latest */
+      resumeFromRunId?: string
     }): Promise<ChatMessage> => {
       if (!isTauri()) {
         throw new Error('Not in Tauri context')
@@ -1227,6 +1230,7 @@ export function useSendMessage() {
         mcpConfig,
         chromeEnabled,
         customProfileName,
+        resumeFromRunId,
       })
       logger.info('Chat message sent', { responseId: response.id })
       return response
@@ -14,8 +14,10 @@ import { logger } from '@/lib/logger'
 import type {
   ClaudeCliStatus,
   ClaudeAuthStatus,
+  DiagnosisReport,
   ReleaseInfo,
   InstallProgress,
+  UpdateInfo,
 } from '@/types/claude-cli'
 
 import { hasBackend } from '@/lib/environment'
@@ -28,6 +30,7 @@ export const claudeCliQueryKeys = {
   status: () => [...claudeCliQueryKeys.all, 'status'] as const,
   auth: () => [...claudeCliQueryKeys.all, 'auth'] as const,
   versions: () => [...claudeCliQueryKeys.all, 'versions'] as const,
+  update: () => [...claudeCliQueryKeys.all, 'update'] as const,
 }
 
 /**
@@ -60,6 +63,24 @@ export function useClaudeCliStatus() {
   })
 }
 
+/**
+ * Hook to check whether a newer stable Claude CLI is available
+ */
+export function useClaudeCliUpdate(options?: { enabled?: boolean }) {
+  return useQuery({
+    queryKey: claudeCliQueryKeys.update(),
+    queryFn: async (): Promise<UpdateInfo> => {
+      logger.debug('Checking for Claude CLI updates')
+      const info = await invoke<UpdateInfo>('claude_update_available')
+      logger.info('Claude CLI update check', { info })
+      return info
+    },
+    enabled: isTauri() && (options?.enabled ?? true),
+    staleTime: 1000 * 60 * 30, // 30 minutes
+    gcTime: 1000 * 60 * 60, // 1 hour
+  })
+}
+
 /**
  * Hook to check if Claude CLI is authenticated
  */
@@ -163,6 +184,65 @@ export function useInstallClaudeCli() {
   })
 }
 
+/**
+ * Hook to switch between the embedded and a system-installed Claude CLI
+ */
+export function useSetClaudeCliSource() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async ({
+      source,
+      systemPath,
+    }: {
+      source: 'embedded' | 'system'
+      systemPath?: string
+    }): Promise<ClaudeCliStatus> => {
+      if (!isTauri()) {
+        throw new Error('Cannot change CLI source outside Tauri context')
+      }
+
+      logger.info('Setting Claude CLI source', { source, systemPath })
+      return invoke<ClaudeCliStatus>('set_claude_cli_source', {
+        source,
+        systemPath: systemPath ?? null,
+      })
+    },
+    onSuccess: status => {
+      queryClient.setQueryData(claudeCliQueryKeys.status(), status)
+      queryClient.invalidateQueries({ queryKey: claudeCliQueryKeys.auth() })
+    },
+    onError: error => {
+      const message = error instanceof Error ? error.message : String(error)
+      logger.error('Failed to set Claude CLI source', { error })
+      toast.error('Failed to switch Claude CLI', { description: message })
+    },
+  })
+}
+
+/**
+ * Hook to run a step-by-step Claude CLI diagnosis (binary, version, auth, stream)
+ */
+export function useDiagnoseClaudeCli() {
+  return useMutation({
+    mutationFn: async (): Promise<DiagnosisReport> => {
+      if (!isTauri()) {
+        throw new Error('Cannot diagnose CLI outside Tauri context')
+      }
+
+      logger.info('Diagnosing Claude CLI')
+      const report = await invoke<DiagnosisReport>('diagnose_claude_cli')
+      logger.info('Claude CLI diagnosis', { report })
+      return report
+    },
+    onError: error => {
+      const message = error instanceof Error ? error.message : String(error)
+      logger.error('Failed to diagnose Claude CLI', { error })
+      toast.error('Failed to diagnose Claude CLI', { description: message })
+    },
+  })
+}
+
 /**
  * Hook to listen for installation progress events
  * Returns [progress, resetProgress] tuple to allow resetting state before new install
@@ -28,6 +28,7 @@ export const ghCliQueryKeys = {
   status: () => [...ghCliQueryKeys.all, 'status'] as const,
   auth: () => [...ghCliQueryKeys.all, 'auth'] as const,
   versions: () => [...ghCliQueryKeys.all, 'versions'] as const,
+  allVersions: () => [...ghCliQueryKeys.versions(), 'all'] as const,
 }
 
 /**
@@ -39,7 +40,7 @@ export function useGhCliStatus() {
     queryFn: async (): Promise<GhCliStatus> => {
       if (!isTauri()) {
         logger.debug('Not in Tauri context, returning mock gh CLI status')
-        return { installed: false, version: null, path: null }
+        return { installed: false, version: null, path: null, verified: false }
       }
 
       try {
@@ -49,7 +50,7 @@ export function useGhCliStatus() {
         return status
       } catch (error) {
         logger.error('Failed to check GitHub CLI status', { error })
-        return { installed: false, version: null, path: null }
+        return { installed: false, version: null, path: null, verified: false }
       }
     },
     staleTime: 1000 * 60 * 5, // 5 minutes
@@ -92,9 +93,11 @@ export function useGhCliAuth(options?: { enabled?: boolean }) {
 /**
  * Hook to fetch available GitHub CLI versions from GitHub releases
  */
-export function useAvailableGhVersions() {
+export function useAvailableGhVersions(includeAll = false) {
   return useQuery({
-    queryKey: ghCliQueryKeys.versions(),
+    queryKey: includeAll
+      ? ghCliQueryKeys.allVersions()
+      : ghCliQueryKeys.versions(),
     queryFn: async (): Promise<GhReleaseInfo[]> => {
       if (!isTauri()) {
         logger.debug('Not in Tauri context, returning empty versions list')
@@ -111,7 +114,7 @@ export function useAvailableGhVersions() {
             published_at: string
             prerelease: boolean
           }[]
-        >('get_available_gh_versions')
+        >('get_available_gh_versions', { includeAll })
 
         return versions.map(v => ({
           version: v.version,
@@ -130,6 +130,13 @@ describe('preferences service', () => {
         custom_cli_profiles: [],
         default_provider: null,
         canvas_layout: 'grid',
+        claude_startup_timeout_secs: null,
+        claude_dead_process_timeout_secs: null,
+        claude_max_input_kb: null,
+        claude_cli_source: null,
+        claude_cli_path: null,
+        claude_extra_env: {},
+        claude_min_thinking_display_level: null,
       }
       vi.mocked(invoke).mockResolvedValueOnce(mockPreferences)
 
@@ -228,6 +235,13 @@ describe('preferences service', () => {
         custom_cli_profiles: [],
         default_provider: null,
         canvas_layout: 'grid',
+        claude_startup_timeout_secs: null,
+        claude_dead_process_timeout_secs: null,
+        claude_max_input_kb: null,
+        claude_cli_source: null,
+        claude_cli_path: null,
+        claude_extra_env: {},
+        claude_min_thinking_display_level: null,
       }
       vi.mocked(invoke).mockResolvedValueOnce(prefsWithOldBinding)
 
@@ -303,6 +317,13 @@ describe('preferences service', () => {
         custom_cli_profiles: [],
         default_provider: null,
         canvas_layout: 'grid',
+        claude_startup_timeout_secs: null,
+        claude_dead_process_timeout_secs: null,
+        claude_max_input_kb: null,
+        claude_cli_source: null,
+        claude_cli_path: null,
+        claude_extra_env: {},
+        claude_min_thinking_display_level: null,
       }
 
       const { result } = renderHook(() => useSavePreferences(), {
@@ -376,6 +397,13 @@ describe('preferences service', () => {
         custom_cli_profiles: [],
         default_provider: null,
         canvas_layout: 'grid',
+        claude_startup_timeout_secs: null,
+        claude_dead_process_timeout_secs: null,
+        claude_max_input_kb: null,
+        claude_cli_source: null,
+        claude_cli_path: null,
+        claude_extra_env: {},
+        claude_min_thinking_display_level: null,
       }
 
       const { result } = renderHook(() => useSavePreferences(), {
@@ -449,6 +477,13 @@ describe('preferences service', () => {
         custom_cli_profiles: [],
         default_provider: null,
         canvas_layout: 'grid',
+        claude_startup_timeout_secs: null,
+        claude_dead_process_timeout_secs: null,
+        claude_max_input_kb: null,
+        claude_cli_source: null,
+        claude_cli_path: null,
+        claude_extra_env: {},
+        claude_min_thinking_display_level: null,
       }
 
       const { result } = renderHook(() => useSavePreferences(), {
@@ -520,6 +555,13 @@ describe('preferences service', () => {
         custom_cli_profiles: [],
         default_provider: null,
         canvas_layout: 'grid',
+        claude_startup_timeout_secs: null,
+        claude_dead_process_timeout_secs: null,
+        claude_max_input_kb: null,
+        claude_cli_source: null,
+        claude_cli_path: null,
+        claude_extra_env: {},
+        claude_min_thinking_display_level: null,
       }
 
       const { result } = renderHook(() => useSavePreferences(), {
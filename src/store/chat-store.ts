@@ -259,7 +259,9 @@ interface ChatUIState {
   updateToolCallOutput: (
     sessionId: string,
     toolUseId: string,
-    output: string
+    output: string,
+    isError?: boolean,
+    durationMs?: number
   ) => void
   clearToolCalls: (sessionId: string) => void
 
@@ -871,12 +873,27 @@ export const useChatStore = create<ChatUIState>()(
           'addToolCall'
         ),
 
-      updateToolCallOutput: (sessionId, toolUseId, output) =>
+      updateToolCallOutput: (
+        sessionId,
+        toolUseId,
+        output,
+        isError,
+        durationMs
+      ) =>
         set(
           state => {
             const toolCalls = state.activeToolCalls[sessionId] ?? []
             const updatedToolCalls = toolCalls.map(tc =>
-              tc.id === toolUseId ? { ...tc, output } : tc
+              tc.id === toolUseId
+                ? {
+                    ...tc,
+                    output,
+                    ...(isError ? { is_error: true } : {}),
+                    ...(durationMs !== undefined
+                      ? { duration_ms: durationMs }
+                      : {}),
+                  }
+                : tc
             )
             return {
               activeToolCalls: {
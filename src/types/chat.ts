@@ -49,6 +49,10 @@ export interface ToolCall {
   output?: string
   /** Parent tool use ID for sub-agent tool calls (for parallel task attribution) */
   parent_tool_use_id?: string
+  /** True if the tool result was reported as an error (e.g., non-zero exit code) */
+  is_error?: boolean
+  /** Time from tool_use to tool_result in milliseconds (absent when unknown) */
+  duration_ms?: number
 }
 
 /**
@@ -79,18 +83,26 @@ export interface ChatMessage {
   cancelled?: boolean
   /** True if the plan in this message was approved by the user */
   plan_approved?: boolean
-  /** Model used when this message was sent (user messages only) */
+  /** Model used when this message was sent (assistant: the model that answered) */
   model?: string
-  /** Execution mode when this message was sent (user messages only) */
+  /** Execution mode of the run this message belongs to */
   execution_mode?: ExecutionMode
-  /** Thinking level when this message was sent (user messages only) */
+  /** Thinking level of the run this message belongs to */
   thinking_level?: ThinkingLevel
-  /** Effort level when this message was sent (user messages only, Opus 4.6) */
+  /** Effort level of the run this message belongs to (Opus 4.6) */
   effort_level?: EffortLevel
   /** True if this message was recovered from a crash */
   recovered?: boolean
   /** Token usage for this message (assistant messages only) */
   usage?: UsageData
+  /** Working directory the run was started in (user messages only) */
+  working_dir?: string
+  /** Git branch checked out when the run started (user messages only) */
+  git_branch?: string
+  /** Run ID of the crashed run this message retries (user messages only) */
+  retried_from?: string
+  /** Number of earlier runs this message summarizes (compacted-history summaries only) */
+  compacted_runs?: number
 }
 
 // ============================================================================
@@ -262,6 +274,8 @@ export interface ChunkEvent {
   session_id: string
   worktree_id: string // Kept for backward compatibility
   content: string
+  /** Position of this event within the run (shared counter, starts at 0 per run) */
+  seq: number
 }
 
 /**
@@ -275,6 +289,8 @@ export interface ToolUseEvent {
   input: unknown
   /** Parent tool use ID for sub-agent tool calls (for parallel task attribution) */
   parent_tool_use_id?: string
+  /** Position of this event within the run (shared counter, starts at 0 per run) */
+  seq: number
 }
 
 /**
@@ -283,6 +299,14 @@ export interface ToolUseEvent {
 export interface DoneEvent {
   session_id: string
   worktree_id: string // Kept for backward compatibility
+  /** Position of this event within the run (shared counter, starts at 0 per run) */
+  seq: number
+  /** Final token usage from the result message (absent for partial responses) */
+  usage?: UsageData
+  /** Run duration reported by the CLI */
+  duration_ms?: number
+  /** Claude CLI session ID, if reported */
+  claude_session_id?: string
 }
 
 /**
@@ -320,6 +344,57 @@ export interface CancelledEvent {
   undo_send: boolean // True if user message should be restored to input (instant cancellation)
 }
 
+/**
+ * Event payload for running token usage from Rust
+ * Sent during a run as usage changes; final usage arrives with the completed message
+ */
+export interface UsageUpdateEvent {
+  session_id: string
+  worktree_id: string // Kept for backward compatibility
+  usage: UsageData
+}
+
+/**
+ * Event payload for policy adjustments from Rust
+ * Sent when a run executes with a different setting than the user requested
+ */
+export interface PolicyAdjustedEvent {
+  session_id: string
+  worktree_id: string // Kept for backward compatibility
+  /** Setting that was overridden (e.g., "thinking_level", "effort_level") */
+  field: string
+  requested: string
+  effective: string
+  /** Why the setting was overridden */
+  reason: string
+}
+
+/**
+ * Event payload for startup progress from Rust
+ * Sent immediately and every 10s while waiting for the CLI's first output
+ */
+export interface StreamProgressEvent {
+  session_id: string
+  worktree_id: string // Kept for backward compatibility
+  /** Seconds since the run started waiting for output */
+  elapsed_secs: number
+  process_alive: boolean
+}
+
+/**
+ * Event payload for stream warnings from Rust
+ * Sent (rate-limited) when a line of CLI output can't be parsed as JSON
+ */
+export interface StreamWarningEvent {
+  session_id: string
+  worktree_id: string // Kept for backward compatibility
+  message: string
+  /** Start of the offending line (truncated) */
+  line_preview: string
+  /** Unparseable lines seen so far in this run */
+  unparseable_count: number
+}
+
 /**
  * Event payload for tool block position from Rust
  * Signals where a tool_use block appears in the content stream
@@ -337,6 +412,8 @@ export interface ThinkingEvent {
   session_id: string
   worktree_id: string // Kept for backward compatibility
   content: string
+  /** Position of this event within the run (shared counter, starts at 0 per run) */
+  seq: number
 }
 
 /**
@@ -348,6 +425,12 @@ export interface ToolResultEvent {
   worktree_id: string // Kept for backward compatibility
   tool_use_id: string
   output: string
+  /** True if the tool reported a failure (e.g., non-zero exit code) */
+  is_error: boolean
+  /** Time from tool_use to tool_result in milliseconds, if the start was seen */
+  duration_ms: number | null
+  /** Position of this event within the run (shared counter, starts at 0 per run) */
+  seq: number
 }
 
 // ============================================================================
@@ -844,6 +927,10 @@ export interface RunLogFileInfo {
   user_message_preview: string
   /** Token usage for this run (if completed) */
   usage?: UsageData
+  /** Claude CLI permission mode the run was spawned with (older runs: unknown) */
+  permission_mode?: string
+  /** Whether web tools could run without a permission prompt (older runs: unknown) */
+  web_access?: boolean
 }
 
 /**
@@ -12,6 +12,24 @@ export interface ClaudeCliStatus {
   version: string | null
   /** Path to the CLI binary (if installed) */
   path: string | null
+  /** Diagnosis if the installed binary was built for a different platform */
+  platform_mismatch?: string
+  /** Which binary is active: downloaded by Jean ("embedded") or user-installed ("system") */
+  source?: 'embedded' | 'system'
+  /** Why the selected system binary can't be used (not found, too old, ...) */
+  source_error?: string
+  /** True if the installed version is below the minimum Jean supports */
+  unsupported?: boolean
+}
+
+/**
+ * Installation and authentication status from a single check
+ */
+export interface ClaudeStatus extends ClaudeCliStatus {
+  /** Whether the CLI is authenticated (false when not installed) */
+  authenticated: boolean
+  /** Why authentication failed or couldn't be checked */
+  auth_error: string | null
 }
 
 /**
@@ -24,6 +42,27 @@ export interface ClaudeAuthStatus {
   error: string | null
 }
 
+/**
+ * A named check in a Claude CLI diagnosis
+ */
+export interface DiagnosisStep {
+  /** Step identifier ("binary", "version", "auth", "stream") */
+  name: string
+  /** "skipped" when an earlier step failed */
+  status: 'pass' | 'fail' | 'skipped'
+  /** What was found, or why the step failed */
+  detail: string | null
+}
+
+/**
+ * Result of diagnosing the Claude CLI end to end
+ */
+export interface DiagnosisReport {
+  /** True if every step passed */
+  healthy: boolean
+  steps: DiagnosisStep[]
+}
+
 /**
  * Information about a Claude CLI release from GitHub
  */
@@ -38,6 +77,18 @@ export interface ReleaseInfo {
   prerelease: boolean
 }
 
+/**
+ * Installed vs latest stable Claude CLI version
+ */
+export interface UpdateInfo {
+  /** Installed version (null if not installed or unknown) */
+  current: string | null
+  /** Latest stable version */
+  latest: string
+  /** Whether latest is newer than current */
+  update_available: boolean
+}
+
 /**
  * Progress event during CLI installation
  */
@@ -12,6 +12,8 @@ export interface GhCliStatus {
   version: string | null
   /** Path to the CLI binary (if installed) */
   path: string | null
+  /** Whether the installed archive was verified against the release checksums */
+  verified: boolean
 }
 
 /**
@@ -45,7 +47,9 @@ export interface GhInstallProgress {
   /** Current stage of installation */
   stage:
     | 'starting'
+    | 'checking_disk_space'
     | 'downloading'
+    | 'verifying_checksum'
     | 'extracting'
     | 'installing'
     | 'verifying'
@@ -441,6 +441,13 @@ export interface AppPreferences {
   custom_cli_profiles: CustomCliProfile[] // Custom CLI settings profiles (e.g., OpenRouter, MiniMax)
   default_provider: string | null // Default provider profile name (null = Anthropic direct)
   canvas_layout: CanvasLayout // Canvas display mode: grid (cards) or list (compact rows)
+  claude_startup_timeout_secs: number | null // Seconds to wait for first Claude output (null = 120, clamped to 10-900)
+  claude_dead_process_timeout_secs: number | null // Seconds to wait for more output after the Claude process exits (null = 2, clamped to 1-60)
+  claude_max_input_kb: number | null // Largest message sent to a Claude run in KB (null = 4096)
+  claude_cli_source: 'embedded' | 'system' | null // Claude CLI binary to run (null = embedded)
+  claude_cli_path: string | null // System Claude CLI path (null = look up on PATH)
+  claude_extra_env: Record<string, string> // Extra env vars for spawned Claude processes (override built-ins)
+  claude_min_thinking_display_level: string | null // Hide thinking for runs below this level: low/think, medium/megathink, high/ultrathink, max (null = always show)
 }
 
 export type CanvasLayout = 'grid' | 'list'
@@ -820,4 +827,11 @@ export const defaultPreferences: AppPreferences = {
   custom_cli_profiles: [],
   default_provider: null,
   canvas_layout: 'grid',
+  claude_startup_timeout_secs: null,
+  claude_dead_process_timeout_secs: null,
+  claude_max_input_kb: null,
+  claude_cli_source: null,
+  claude_cli_path: null,
+  claude_extra_env: {},
+  claude_min_thinking_display_level: null,
 }
@@ -1,3 +1,5 @@
+import type { UsageData } from './chat'
+
 /**
  * Type of session (base branch or worktree)
  */
@@ -263,6 +265,8 @@ export interface CreatePrResponse {
   pr_url: string
   /** AI-generated PR title */
   title: string
+  /** Token usage of the PR content generation, if reported */
+  usage?: UsageData
 }
 
 // =============================================================================
@@ -277,6 +281,8 @@ export interface CreateCommitResponse {
   message: string
   /** Whether the commit was pushed to remote */
   pushed: boolean
+  /** Token usage of the commit message generation, if reported */
+  usage?: UsageData
 }
 
 // =============================================================================